@@ -7,6 +7,10 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 
+use crate::auth::session::{resolve_launch_account, LaunchAccount};
+use crate::config::model::AccountInfo;
+use crate::util::network::ensure_online;
+
 const CLIENT_ID: &str = env!("MICROSOFT_CLIENT_ID");
 const DEVICE_CODE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
 const MSA_AUTHORIZE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/authorize";
@@ -54,6 +58,7 @@ pub fn check_microsoft_login_availability() -> bool {
 
 #[tauri::command]
 pub async fn get_device_code(on_event: Channel<LoginEvent>) -> Result<(), String> {
+    ensure_online("登录微软账号").await?;
     let client = BasicClient::new(
         ClientId::new(CLIENT_ID.to_string()),
         None,
@@ -113,6 +118,12 @@ pub async fn get_device_code(on_event: Channel<LoginEvent>) -> Result<(), String
     Ok(())
 }
 
+/// 获取启动时实际使用的账号信息，离线模式下使用缓存的档案
+#[tauri::command]
+pub async fn get_launch_account(account: AccountInfo) -> Result<LaunchAccount, String> {
+    resolve_launch_account(&account).await
+}
+
 // #[tauri::command]
 // pub async fn poll_login_status() -> Result<MinecraftAuthResponse, String> {
 //     println!("Polling login status...");
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::config::model::{AccountInfo, AccountType};
+use crate::mojang::api::MinecraftClient;
+use crate::util::network::is_offline;
+
+/// 离线账号及离线模式下使用的占位令牌
+const OFFLINE_ACCESS_TOKEN: &str = "0";
+
+/// 启动游戏时使用的账号信息
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchAccount {
+    pub name: String,
+    pub uuid: String,
+    pub access_token: String,
+    /// 对应启动参数中的 `${user_type}`
+    pub user_type: String,
+    /// 是否使用了本地缓存的档案（未经在线验证）
+    pub cached: bool,
+}
+
+impl LaunchAccount {
+    fn from_cache(account: &AccountInfo, user_type: &str) -> Self {
+        LaunchAccount {
+            name: account.name.clone(),
+            uuid: account.uuid.clone(),
+            access_token: account
+                .access_token
+                .clone()
+                .unwrap_or_else(|| OFFLINE_ACCESS_TOKEN.to_string()),
+            user_type: user_type.to_string(),
+            cached: true,
+        }
+    }
+}
+
+/// 确定启动时使用的玩家名、UUID 与令牌
+///
+/// 微软账号在线时会重新获取玩家档案；离线模式下跳过验证，直接使用账号中缓存的档案，
+/// 此时游戏内只能进入单人世界或离线服务器。
+pub async fn resolve_launch_account(account: &AccountInfo) -> Result<LaunchAccount, String> {
+    match account.account_type {
+        AccountType::Offline => Ok(LaunchAccount {
+            access_token: OFFLINE_ACCESS_TOKEN.to_string(),
+            ..LaunchAccount::from_cache(account, "legacy")
+        }),
+        AccountType::Microsoft | AccountType::External => {
+            if is_offline().await {
+                eprintln!("离线模式：使用账号 {} 的缓存档案启动", account.name);
+                return Ok(LaunchAccount::from_cache(account, "msa"));
+            }
+            let access_token = account
+                .access_token
+                .as_deref()
+                .ok_or_else(|| format!("账号 {} 缺少访问令牌，请重新登录", account.name))?;
+            let profile = MinecraftClient::new()
+                .get_minecraft_profile(access_token)
+                .await
+                .map_err(|e| format!("验证账号 {} 失败，请重新登录: {}", account.name, e))?;
+            Ok(LaunchAccount {
+                name: profile.name,
+                uuid: profile.id,
+                access_token: access_token.to_string(),
+                user_type: "msa".to_string(),
+                cached: false,
+            })
+        }
+    }
+}
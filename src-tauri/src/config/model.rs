@@ -3,8 +3,12 @@ use std::{collections::HashMap, path::PathBuf};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    config::util::get_default_game_paths, jre::model::JreInfo,
-    util::init::get_launcher_language_tag,
+    config::util::get_default_game_paths,
+    jre::model::JreInfo,
+    util::{
+        init::get_launcher_language_tag,
+        scheduler::{DEFAULT_MAX_CONCURRENCY, DEFAULT_MAX_PER_HOST},
+    },
 };
 
 #[derive(Serialize, Deserialize)]
@@ -25,6 +29,28 @@ impl Default for JreConfig {
 pub enum DownloadSource {
     Official,
     BmclApi,
+    /// 优先使用测速最快的源，出错或超时时自动切换
+    Auto,
+    /// 用户自定义的镜像源，见 `LauncherConfig::custom_mirror`
+    Custom,
+}
+
+/// 自定义镜像源中各资源类别的根地址，未填写的类别使用官方地址
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomMirrorConfig {
+    /// 版本清单与版本 JSON（piston-meta.mojang.com）
+    pub meta: Option<String>,
+    /// 客户端 jar 等文件（piston-data.mojang.com）
+    pub data: Option<String>,
+    /// 游戏库文件（libraries.minecraft.net）
+    pub libraries: Option<String>,
+    /// 资源文件（resources.download.minecraft.net）
+    pub assets: Option<String>,
+    /// Fabric 元数据（meta.fabricmc.net）
+    pub fabric_meta: Option<String>,
+    /// Forge / NeoForge Maven 仓库
+    pub forge_maven: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -76,6 +102,21 @@ pub struct LauncherConfig {
     pub proxy: ProxyConfig,
     #[serde(default = "default_game_paths")]
     pub game_path: HashMap<String, PathBuf>,
+    #[serde(default)]
+    pub custom_mirror: CustomMirrorConfig,
+    #[serde(default = "default_max_download_concurrency")]
+    pub max_download_concurrency: usize,
+    #[serde(default = "default_max_downloads_per_host")]
+    pub max_downloads_per_host: usize,
+    /// 手动开启离线模式，此时不发起任何网络请求
+    #[serde(default = "default_false")]
+    pub offline_mode: bool,
+    /// CurseForge Core API 的 API Key，未填写时无法使用 CurseForge
+    #[serde(default)]
+    pub curseforge_api_key: Option<String>,
+    /// 每个存档保留的备份数量，为 0 时不限制
+    #[serde(default = "default_max_world_backups")]
+    pub max_world_backups: usize,
 }
 
 fn default_false() -> bool {
@@ -103,6 +144,18 @@ fn default_proxy_config() -> ProxyConfig {
     }
 }
 
+fn default_max_download_concurrency() -> usize {
+    DEFAULT_MAX_CONCURRENCY
+}
+
+fn default_max_downloads_per_host() -> usize {
+    DEFAULT_MAX_PER_HOST
+}
+
+fn default_max_world_backups() -> usize {
+    10
+}
+
 fn default_game_paths() -> HashMap<String, PathBuf> {
     get_default_game_paths()
 }
@@ -123,6 +176,12 @@ impl Default for LauncherConfig {
             enable_proxy: false,
             proxy: default_proxy_config(),
             game_path: default_game_paths(),
+            custom_mirror: CustomMirrorConfig::default(),
+            max_download_concurrency: default_max_download_concurrency(),
+            max_downloads_per_host: default_max_downloads_per_host(),
+            offline_mode: false,
+            curseforge_api_key: None,
+            max_world_backups: default_max_world_backups(),
         }
     }
 }
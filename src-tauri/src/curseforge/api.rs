@@ -0,0 +1,247 @@
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::model::{
+    CurseForgeFile, CurseForgeFingerprintResult, CurseForgeMod, CurseForgeModLoader,
+    CurseForgePage, CurseForgeResponse, CurseForgeSearchQuery, MINECRAFT_GAME_ID, MODS_CLASS_ID,
+};
+use crate::config::saveload::get_launcher_config;
+use crate::util::network::ensure_online;
+use crate::util::reqwest_client::REQWEST_CLIENT;
+
+const CURSEFORGE_API: &str = "https://api.curseforge.com";
+
+/// CurseForge Core API 客户端
+pub struct CurseForgeClient {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl CurseForgeClient {
+    /// 使用启动器配置中的 API Key 与共享的 HTTP 客户端访问 CurseForge
+    pub async fn new() -> Result<Self, String> {
+        let api_key = get_launcher_config()
+            .await?
+            .curseforge_api_key
+            .filter(|key| !key.trim().is_empty())
+            .ok_or_else(|| "未配置 CurseForge API Key，请在设置中填写".to_string())?;
+        ensure_online("访问 CurseForge").await?;
+        let client = {
+            let guard = REQWEST_CLIENT.lock().await;
+            match &*guard {
+                Some(c) => c.clone(),
+                None => return Err("HTTP客户端未初始化".to_string()),
+            }
+        };
+        Ok(Self::with_client(client, CURSEFORGE_API, api_key.trim()))
+    }
+
+    pub fn with_client(
+        client: Client,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<CurseForgeResponse<T>, String> {
+        let response = request
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| format!("请求 CurseForge 失败: {}", e))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return Err("CurseForge API Key 无效".to_string());
+        }
+        if !status.is_success() {
+            return Err(format!(
+                "请求 CurseForge 失败: {} {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("解析 CurseForge 响应失败: {}", e))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<CurseForgeResponse<T>, String> {
+        let url = format!("{}{}", self.base_url, path);
+        self.send(self.client.get(url).query(query)).await
+    }
+
+    async fn post<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, String> {
+        let url = format!("{}{}", self.base_url, path);
+        Ok(self.send(self.client.post(url).json(body)).await?.data)
+    }
+
+    /// 搜索项目
+    pub async fn search(
+        &self,
+        query: &CurseForgeSearchQuery,
+    ) -> Result<CurseForgePage<CurseForgeMod>, String> {
+        let mut params = vec![
+            ("gameId", MINECRAFT_GAME_ID.to_string()),
+            (
+                "classId",
+                query.class_id.unwrap_or(MODS_CLASS_ID).to_string(),
+            ),
+            ("searchFilter", query.query.clone()),
+            ("sortField", query.sort_field.unwrap_or(2).to_string()),
+            ("sortOrder", "desc".to_string()),
+        ];
+        if let Some(loader) = query.loader.and_then(CurseForgeModLoader::from_loader) {
+            params.push(("modLoaderType", (loader as u32).to_string()));
+        }
+        if let Some(version) = &query.game_version {
+            params.push(("gameVersion", version.clone()));
+        }
+        if let Some(category) = query.category_id {
+            params.push(("categoryId", category.to_string()));
+        }
+        if let Some(index) = query.index {
+            params.push(("index", index.to_string()));
+        }
+        if let Some(page_size) = query.page_size {
+            params.push(("pageSize", page_size.to_string()));
+        }
+        let response = self.get("/v1/mods/search", &params).await?;
+        Ok(CurseForgePage {
+            data: response.data,
+            pagination: response.pagination,
+        })
+    }
+
+    /// 批量获取项目
+    pub async fn get_mods(&self, mod_ids: &[u32]) -> Result<Vec<CurseForgeMod>, String> {
+        if mod_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.post("/v1/mods", &serde_json::json!({ "modIds": mod_ids }))
+            .await
+    }
+
+    /// 列出项目的文件，从新到旧排列，可按游戏版本与加载器过滤
+    pub async fn get_mod_files(
+        &self,
+        mod_id: u32,
+        game_version: Option<&str>,
+        loader: Option<CurseForgeModLoader>,
+        index: Option<u32>,
+    ) -> Result<CurseForgePage<CurseForgeFile>, String> {
+        let mut params = Vec::new();
+        if let Some(version) = game_version {
+            params.push(("gameVersion", version.to_string()));
+        }
+        if let Some(loader) = loader {
+            params.push(("modLoaderType", (loader as u32).to_string()));
+        }
+        if let Some(index) = index {
+            params.push(("index", index.to_string()));
+        }
+        let response = self
+            .get(&format!("/v1/mods/{}/files", mod_id), &params)
+            .await?;
+        Ok(CurseForgePage {
+            data: response.data,
+            pagination: response.pagination,
+        })
+    }
+
+    pub async fn get_file(&self, mod_id: u32, file_id: u32) -> Result<CurseForgeFile, String> {
+        Ok(self
+            .get(&format!("/v1/mods/{}/files/{}", mod_id, file_id), &[])
+            .await?
+            .data)
+    }
+
+    /// 批量获取文件
+    pub async fn get_files(&self, file_ids: &[u32]) -> Result<Vec<CurseForgeFile>, String> {
+        if file_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.post(
+            "/v1/mods/files",
+            &serde_json::json!({ "fileIds": file_ids }),
+        )
+        .await
+    }
+
+    /// 根据文件指纹查询对应的文件
+    pub async fn get_fingerprint_matches(
+        &self,
+        fingerprints: &[u32],
+    ) -> Result<CurseForgeFingerprintResult, String> {
+        if fingerprints.is_empty() {
+            return Ok(CurseForgeFingerprintResult {
+                exact_matches: Vec::new(),
+            });
+        }
+        self.post(
+            &format!("/v1/fingerprints/{}", MINECRAFT_GAME_ID),
+            &serde_json::json!({ "fingerprints": fingerprints }),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::version::model::ModLoaderType;
+    use crate::util::test_server::serve;
+
+    #[tokio::test]
+    async fn test_search() {
+        let base = serve(|request| {
+            if request.header("x-api-key") != Some("test-key") {
+                return (403, b"{}".to_vec());
+            }
+            assert!(request.path.starts_with("/v1/mods/search?"));
+            assert!(request.path.contains("gameId=432"));
+            assert!(request.path.contains("modLoaderType=4"));
+            assert!(request.path.contains("gameVersion=1.20.1"));
+            let body = r#"{
+                "data": [{ "id": 238222, "name": "Just Enough Items", "slug": "jei",
+                           "links": { "websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/jei" },
+                           "downloadCount": 1.5e8, "allowModDistribution": true }],
+                "pagination": { "index": 0, "pageSize": 50, "resultCount": 1, "totalCount": 1 }
+            }"#;
+            (200, body.as_bytes().to_vec())
+        })
+        .await;
+        let query = CurseForgeSearchQuery {
+            query: "jei".to_string(),
+            loader: Some(ModLoaderType::Fabric),
+            game_version: Some("1.20.1".to_string()),
+            ..Default::default()
+        };
+
+        let client = CurseForgeClient::with_client(Client::new(), base.clone(), "test-key");
+        let page = client.search(&query).await.unwrap();
+        assert_eq!(page.data[0].slug, "jei");
+        assert_eq!(page.pagination.unwrap().total_count, 1);
+
+        let client = CurseForgeClient::with_client(Client::new(), base, "wrong-key");
+        assert!(client.search(&query).await.unwrap_err().contains("API Key"));
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::ipc::Channel;
+
+use super::api::CurseForgeClient;
+use super::install::{identify_curseforge_files, install_curseforge_file, CurseForgeTarget};
+use super::model::{
+    CurseForgeDownloadResult, CurseForgeFile, CurseForgeInstalledFile, CurseForgeMod,
+    CurseForgePage, CurseForgeSearchQuery,
+};
+use crate::game::mods::util::mod_environment;
+use crate::game::profile::util::{get_profile, resolve_game_directory};
+use crate::game::version::util::resolve_client_json;
+use crate::util::{model::DownloadEvent, scheduler::DownloadEventSink};
+
+/// 配置的模组目录，以及其加载器与游戏版本
+fn profile_target(
+    game_path: &Path,
+    profile_id: &str,
+) -> Result<(PathBuf, CurseForgeTarget), String> {
+    let profile = get_profile(game_path, profile_id)?;
+    let resolved = resolve_client_json(game_path, &profile.version_name)?;
+    let target = CurseForgeTarget::from_environment(mod_environment(&resolved, None));
+    let mods_dir = resolve_game_directory(game_path, &profile).join("mods");
+    Ok((mods_dir, target))
+}
+
+#[tauri::command]
+pub async fn search_curseforge_command(
+    query: CurseForgeSearchQuery,
+) -> Result<CurseForgePage<CurseForgeMod>, String> {
+    CurseForgeClient::new().await?.search(&query).await
+}
+
+/// 列出项目的文件，指定配置时只返回适用于该配置的文件
+#[tauri::command]
+pub async fn get_curseforge_files_command(
+    mod_id: u32,
+    game_path: Option<PathBuf>,
+    profile_id: Option<String>,
+    index: Option<u32>,
+) -> Result<CurseForgePage<CurseForgeFile>, String> {
+    let target = match (game_path, profile_id) {
+        (Some(game_path), Some(profile_id)) => profile_target(&game_path, &profile_id)?.1,
+        _ => CurseForgeTarget::default(),
+    };
+    CurseForgeClient::new()
+        .await?
+        .get_mod_files(mod_id, target.game_version.as_deref(), target.loader, index)
+        .await
+}
+
+/// 安装文件及其必需的前置到配置的模组目录，返回需要手动下载的文件
+#[tauri::command]
+pub async fn install_curseforge_command(
+    on_event: Channel<DownloadEvent>,
+    game_path: PathBuf,
+    profile_id: String,
+    mod_id: u32,
+    file_id: u32,
+) -> Result<CurseForgeDownloadResult, String> {
+    let (mods_dir, target) = profile_target(&game_path, &profile_id)?;
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    let client = CurseForgeClient::new().await?;
+    install_curseforge_file(&client, mod_id, file_id, &mods_dir, &target, Some(sink)).await
+}
+
+/// 通过指纹识别配置中来自 CurseForge 的模组
+#[tauri::command]
+pub async fn identify_curseforge_mods_command(
+    game_path: PathBuf,
+    profile_id: String,
+) -> Result<Vec<CurseForgeInstalledFile>, String> {
+    let (mods_dir, _) = profile_target(&game_path, &profile_id)?;
+    let client = CurseForgeClient::new().await?;
+    identify_curseforge_files(&client, &mods_dir).await
+}
@@ -0,0 +1,84 @@
+//! CurseForge 文件指纹：去除空白字符后计算的 MurmurHash2（种子为 1）
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::game::mods::scanner::mod_file_state;
+
+const M: u32 = 0x5bd1e995;
+
+/// 32 位 MurmurHash2
+pub fn murmur2(data: &[u8], seed: u32) -> u32 {
+    let mut h = seed ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> 24;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M) ^ k;
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, b) in tail.iter().enumerate() {
+            h ^= (*b as u32) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^ (h >> 15)
+}
+
+/// 计算数据的 CurseForge 指纹，忽略制表符、换行、回车与空格
+pub fn fingerprint(data: &[u8]) -> u32 {
+    let filtered: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, 9 | 10 | 13 | 32))
+        .collect();
+    murmur2(&filtered, 1)
+}
+
+pub fn fingerprint_file(path: &Path) -> std::io::Result<u32> {
+    Ok(fingerprint(&fs::read(path)?))
+}
+
+/// 计算模组目录中所有模组文件（包括已禁用的）的指纹
+pub fn fingerprint_mod_files(mods_dir: &Path) -> Vec<(PathBuf, u32)> {
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, u32)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && mod_file_state(path).is_some())
+        .filter_map(|path| fingerprint_file(&path).ok().map(|f| (path, f)))
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_murmur2() {
+        assert_eq!(murmur2(b"", 0), 0);
+        assert_eq!(
+            murmur2(b"The quick brown fox jumps over the lazy dog", 0x9747b28c),
+            495243318
+        );
+        assert_eq!(murmur2(b"abcd", 1), 3376380438);
+        assert_eq!(murmur2(b"abc", 1), 1621425345);
+        assert_eq!(murmur2(b"hello", 1), 2788266382);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace() {
+        assert_eq!(fingerprint(b"a b\tc\r\nd"), fingerprint(b"abcd"));
+        assert_eq!(fingerprint(b"abcd"), murmur2(b"abcd", 1));
+        assert_ne!(fingerprint(b"abcd"), fingerprint(b"abce"));
+    }
+}
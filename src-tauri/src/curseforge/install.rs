@@ -0,0 +1,428 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::api::CurseForgeClient;
+use super::fingerprint::fingerprint_mod_files;
+use super::model::{
+    CurseForgeDownloadResult, CurseForgeFile, CurseForgeInstalledFile, CurseForgeManualDownload,
+    CurseForgeModLoader,
+};
+use crate::game::mods::model::ModEnvironment;
+use crate::util::model::DownloadRequest;
+use crate::util::scheduler::{DownloadEventSink, DOWNLOAD_SCHEDULER};
+use crate::util::utils::verify_sha1;
+
+/// 依赖关系中表示必需前置的 relationType
+const REQUIRED_DEPENDENCY: u32 = 3;
+
+/// 安装到配置时使用的加载器与游戏版本
+#[derive(Debug, Clone, Default)]
+pub struct CurseForgeTarget {
+    pub loader: Option<CurseForgeModLoader>,
+    pub game_version: Option<String>,
+}
+
+impl CurseForgeTarget {
+    /// 取环境中第一个 CurseForge 支持的加载器
+    pub fn from_environment(environment: ModEnvironment) -> Self {
+        CurseForgeTarget {
+            loader: environment
+                .loaders
+                .iter()
+                .find_map(|l| CurseForgeModLoader::from_loader(l.loader)),
+            game_version: environment.minecraft,
+        }
+    }
+}
+
+fn is_valid_file_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && !name.starts_with('.')
+}
+
+/// 文件已存在且与 SHA-1 一致，未提供 SHA-1 时只比较大小
+fn is_downloaded(path: &Path, file: &CurseForgeFile) -> bool {
+    match file.sha1() {
+        Some(sha1) => verify_sha1(path, sha1),
+        None => fs::metadata(path).is_ok_and(|m| m.len() == file.file_length),
+    }
+}
+
+/// 在阻塞线程中逐个检查文件是否已下载，校验 SHA-1 需要读取整个文件
+async fn check_downloaded(files: Vec<(PathBuf, CurseForgeFile)>) -> Result<Vec<bool>, String> {
+    tokio::task::spawn_blocking(move || {
+        files
+            .iter()
+            .map(|(path, file)| is_downloaded(path, file))
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("校验文件失败: {}", e))
+}
+
+/// 下载文件到指定目录
+///
+/// 作者禁止第三方分发的文件没有下载地址，放入需要手动下载的列表，
+/// 已经手动放到目录中的文件视为已下载。目标位置已有内容不同的文件时报错，
+/// 除非该文件在 `replaceable` 中。
+pub async fn download_curseforge_files(
+    client: &CurseForgeClient,
+    name: &str,
+    files: &[CurseForgeFile],
+    dir: &Path,
+    replaceable: &[PathBuf],
+    sink: Option<DownloadEventSink>,
+) -> Result<CurseForgeDownloadResult, String> {
+    if let Some(file) = files.iter().find(|f| !is_valid_file_name(&f.file_name)) {
+        return Err(format!("无效的文件名: {}", file.file_name));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+
+    let downloaded = check_downloaded(
+        files
+            .iter()
+            .map(|f| (dir.join(&f.file_name), f.clone()))
+            .collect(),
+    )
+    .await?;
+    let mut result = CurseForgeDownloadResult::default();
+    let mut requests = Vec::new();
+    let mut blocked = Vec::new();
+    for (file, downloaded) in files.iter().zip(downloaded) {
+        let path = dir.join(&file.file_name);
+        if downloaded {
+            result.downloaded.push(path);
+            continue;
+        }
+        match &file.download_url {
+            Some(_) if path.exists() && !replaceable.contains(&path) => {
+                return Err(format!("目标文件已存在: {}", path.display()));
+            }
+            Some(url) => requests.push((
+                file,
+                DownloadRequest {
+                    url: url.clone(),
+                    path,
+                    priority: Default::default(),
+                    size: Some(file.file_length),
+                },
+            )),
+            None => blocked.push((file, path)),
+        }
+    }
+
+    if !requests.is_empty() {
+        DOWNLOAD_SCHEDULER
+            .run(
+                name,
+                requests.iter().map(|(_, r)| r.clone()).collect(),
+                sink,
+            )
+            .await?;
+    }
+    let verified = check_downloaded(
+        requests
+            .iter()
+            .map(|(file, request)| (request.path.clone(), (*file).clone()))
+            .collect(),
+    )
+    .await?;
+    for ((_, request), verified) in requests.into_iter().zip(verified) {
+        if !verified {
+            let _ = fs::remove_file(&request.path);
+            return Err(format!("文件校验失败: {}", request.path.display()));
+        }
+        result.downloaded.push(request.path);
+    }
+
+    if !blocked.is_empty() {
+        let mod_ids: Vec<u32> = blocked
+            .iter()
+            .map(|(f, _)| f.mod_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mods: HashMap<u32, _> = client
+            .get_mods(&mod_ids)
+            .await?
+            .into_iter()
+            .map(|m| (m.id, m))
+            .collect();
+        for (file, path) in blocked {
+            let project = mods.get(&file.mod_id);
+            result.manual.push(CurseForgeManualDownload {
+                mod_id: file.mod_id,
+                file_id: file.id,
+                mod_name: project
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| file.display_name.clone()),
+                file_name: file.file_name.clone(),
+                url: match project {
+                    Some(m) => m.download_page(file.id),
+                    None => format!(
+                        "https://www.curseforge.com/projects/{}/files/{}",
+                        file.mod_id, file.id
+                    ),
+                },
+                path,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// 通过指纹识别模组目录中来自 CurseForge 的文件
+pub async fn identify_curseforge_files(
+    client: &CurseForgeClient,
+    mods_dir: &Path,
+) -> Result<Vec<CurseForgeInstalledFile>, String> {
+    let dir = mods_dir.to_path_buf();
+    let files = tokio::task::spawn_blocking(move || fingerprint_mod_files(&dir))
+        .await
+        .map_err(|e| format!("计算模组指纹失败: {}", e))?;
+    let fingerprints: Vec<u32> = files.iter().map(|(_, f)| *f).collect();
+    let mut matches: HashMap<u32, CurseForgeFile> = client
+        .get_fingerprint_matches(&fingerprints)
+        .await?
+        .exact_matches
+        .into_iter()
+        .map(|m| (m.file.file_fingerprint, m.file))
+        .collect();
+    Ok(files
+        .into_iter()
+        .filter_map(|(path, fingerprint)| {
+            let file = matches.remove(&fingerprint)?;
+            Some(CurseForgeInstalledFile {
+                file_name: path.file_name()?.to_string_lossy().into_owned(),
+                path,
+                fingerprint,
+                file,
+            })
+        })
+        .collect())
+}
+
+/// 从指定文件出发递归收集需要安装的文件，已安装或已收集的项目会跳过
+///
+/// 返回的第一个文件为指定的文件，其余为必需的前置。
+pub async fn resolve_install_plan(
+    client: &CurseForgeClient,
+    file: CurseForgeFile,
+    installed: &HashSet<u32>,
+    target: &CurseForgeTarget,
+) -> Result<Vec<CurseForgeFile>, String> {
+    let mut seen = installed.clone();
+    seen.insert(file.mod_id);
+    let mut plan = Vec::new();
+    let mut queue = VecDeque::from([file]);
+    while let Some(file) = queue.pop_front() {
+        for dependency in file
+            .dependencies
+            .iter()
+            .filter(|d| d.relation_type == REQUIRED_DEPENDENCY)
+        {
+            if !seen.insert(dependency.mod_id) {
+                continue;
+            }
+            let latest = client
+                .get_mod_files(
+                    dependency.mod_id,
+                    target.game_version.as_deref(),
+                    target.loader,
+                    None,
+                )
+                .await?
+                .data
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    format!(
+                        "{} 的前置 {} 没有适用于当前版本的文件",
+                        file.display_name, dependency.mod_id
+                    )
+                })?;
+            queue.push_back(latest);
+        }
+        plan.push(file);
+    }
+    Ok(plan)
+}
+
+/// 安装指定文件及其必需的前置到模组目录
+///
+/// 指定文件的项目已安装时，新文件下载并校验完成后删除该项目的旧文件。
+pub async fn install_curseforge_file(
+    client: &CurseForgeClient,
+    mod_id: u32,
+    file_id: u32,
+    mods_dir: &Path,
+    target: &CurseForgeTarget,
+    sink: Option<DownloadEventSink>,
+) -> Result<CurseForgeDownloadResult, String> {
+    let file = client.get_file(mod_id, file_id).await?;
+    let mut installed: HashMap<u32, Vec<PathBuf>> = HashMap::new();
+    for f in identify_curseforge_files(client, mods_dir).await? {
+        installed.entry(f.file.mod_id).or_default().push(f.path);
+    }
+    let previous = installed.remove(&file.mod_id).unwrap_or_default();
+    let installed: HashSet<u32> = installed.into_keys().collect();
+    let plan = resolve_install_plan(client, file, &installed, target).await?;
+    let name = format!("CurseForge {}", plan[0].display_name);
+    let result = download_curseforge_files(client, &name, &plan, mods_dir, &previous, sink).await?;
+    // 需要手动下载时新文件尚未就位，保留旧文件
+    let new_path = mods_dir.join(&plan[0].file_name);
+    if result.downloaded.contains(&new_path) {
+        for old in previous.iter().filter(|old| **old != new_path) {
+            fs::remove_file(old).map_err(|e| format!("删除旧版本失败 {}: {}", old.display(), e))?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curseforge::fingerprint::fingerprint;
+    use crate::util::test_server::{init_client, serve};
+
+    fn file_json(id: u32, mod_id: u32, url: Option<&str>, fingerprint: u32, deps: &str) -> String {
+        format!(
+            r#"{{
+                "id": {id}, "modId": {mod_id}, "displayName": "file {id}", "fileName": "file-{id}.jar",
+                "releaseType": 1, "fileDate": "2024-01-01T00:00:00Z", "fileLength": 3,
+                "downloadUrl": {}, "gameVersions": ["1.20.1", "Fabric"], "hashes": [],
+                "dependencies": {deps}, "fileFingerprint": {fingerprint}
+            }}"#,
+            url.map(|u| format!("\"{}\"", u))
+                .unwrap_or_else(|| "null".to_string())
+        )
+    }
+
+    #[tokio::test]
+    async fn test_identify_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("known.jar"), b"known").unwrap();
+        fs::write(dir.join("unknown.jar.disabled"), b"unknown").unwrap();
+        fs::write(dir.join("notes.txt"), b"notes").unwrap();
+        let known = fingerprint(b"known");
+
+        let base = serve(move |request| {
+            assert_eq!(request.path, "/v1/fingerprints/432");
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            assert_eq!(body["fingerprints"].as_array().unwrap().len(), 2);
+            let response = format!(
+                r#"{{ "data": {{ "exactMatches": [{{ "id": 10, "file": {}, "latestFiles": [] }}],
+                      "unmatchedFingerprints": [{}] }} }}"#,
+                file_json(100, 10, Some("https://edge.forgecdn.net/a"), known, "[]"),
+                fingerprint(b"unknown")
+            );
+            (200, response.into_bytes())
+        })
+        .await;
+        let client = CurseForgeClient::with_client(reqwest::Client::new(), base, "key");
+        let installed = identify_curseforge_files(&client, dir).await.unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].file_name, "known.jar");
+        assert_eq!(installed[0].file.mod_id, 10);
+    }
+
+    #[tokio::test]
+    async fn test_blocked_files_need_manual_download() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let base = serve(|request| {
+            assert_eq!(request.path, "/v1/mods");
+            let body = r#"{ "data": [{ "id": 20, "name": "Blocked", "slug": "blocked",
+                            "links": { "websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/blocked" },
+                            "allowModDistribution": false }] }"#;
+            (200, body.as_bytes().to_vec())
+        })
+        .await;
+        let client = CurseForgeClient::with_client(reqwest::Client::new(), base, "key");
+        let files: Vec<CurseForgeFile> = serde_json::from_str(&format!(
+            "[{}, {}]",
+            file_json(200, 20, None, 0, "[]"),
+            file_json(300, 30, None, 0, "[]")
+        ))
+        .unwrap();
+
+        // 已手动放入目录的文件视为已下载
+        fs::write(dir.join("file-300.jar"), b"abc").unwrap();
+
+        let result = download_curseforge_files(&client, "test", &files, dir, &[], None)
+            .await
+            .unwrap();
+        assert_eq!(result.downloaded, vec![dir.join("file-300.jar")]);
+        assert_eq!(result.manual.len(), 1);
+        assert_eq!(result.manual[0].mod_name, "Blocked");
+        assert_eq!(
+            result.manual[0].url,
+            "https://www.curseforge.com/minecraft/mc-mods/blocked/download/200"
+        );
+        assert_eq!(result.manual[0].path, dir.join("file-200.jar"));
+    }
+
+    #[tokio::test]
+    async fn test_install_replaces_previous_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        init_client().await;
+        fs::write(dir.join("file-100.jar"), b"old").unwrap();
+        let old = fingerprint(b"old");
+
+        let downloads = serve(|request| match request.path.as_str() {
+            "/file-101.jar" => (200, b"new".to_vec()),
+            _ => (404, Vec::new()),
+        })
+        .await;
+        let url = format!("{}/file-101.jar", downloads);
+        let base = serve(move |request| match request.path.as_str() {
+            "/v1/mods/10/files/101" => (
+                200,
+                format!(r#"{{ "data": {} }}"#, file_json(101, 10, Some(&url), 0, "[]"))
+                    .into_bytes(),
+            ),
+            "/v1/fingerprints/432" => (
+                200,
+                format!(
+                    r#"{{ "data": {{ "exactMatches": [{{ "id": 10, "file": {}, "latestFiles": [] }}],
+                          "unmatchedFingerprints": [] }} }}"#,
+                    file_json(100, 10, None, old, "[]")
+                )
+                .into_bytes(),
+            ),
+            _ => (404, b"{}".to_vec()),
+        })
+        .await;
+        let client = CurseForgeClient::with_client(reqwest::Client::new(), base, "key");
+
+        let result =
+            install_curseforge_file(&client, 10, 101, dir, &CurseForgeTarget::default(), None)
+                .await
+                .unwrap();
+        assert_eq!(result.downloaded, vec![dir.join("file-101.jar")]);
+        assert_eq!(fs::read(dir.join("file-101.jar")).unwrap(), b"new");
+        assert!(!dir.join("file-100.jar").exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_does_not_overwrite_unrelated_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("file-101.jar"), b"unrelated").unwrap();
+        let client =
+            CurseForgeClient::with_client(reqwest::Client::new(), "http://127.0.0.1:9", "key");
+        let files: Vec<CurseForgeFile> = serde_json::from_str(&format!(
+            "[{}]",
+            file_json(101, 10, Some("http://127.0.0.1:9/file-101.jar"), 0, "[]")
+        ))
+        .unwrap();
+
+        let err = download_curseforge_files(&client, "test", &files, dir, &[], None)
+            .await
+            .unwrap_err();
+        assert!(err.contains("目标文件已存在"), "{}", err);
+        assert_eq!(fs::read(dir.join("file-101.jar")).unwrap(), b"unrelated");
+    }
+}
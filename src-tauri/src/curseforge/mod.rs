@@ -0,0 +1,5 @@
+pub mod api;
+pub mod command;
+pub mod fingerprint;
+pub mod install;
+pub mod model;
@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::version::model::ModLoaderType;
+
+/// Minecraft 在 CurseForge 中的游戏 ID
+pub const MINECRAFT_GAME_ID: u32 = 432;
+/// 模组分类的 classId
+pub const MODS_CLASS_ID: u32 = 6;
+
+/// 加载器在 CurseForge 中的编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurseForgeModLoader {
+    Forge = 1,
+    LiteLoader = 3,
+    Fabric = 4,
+    Quilt = 5,
+    NeoForge = 6,
+}
+
+impl CurseForgeModLoader {
+    pub fn from_loader(loader: ModLoaderType) -> Option<Self> {
+        match loader {
+            ModLoaderType::Forge => Some(Self::Forge),
+            ModLoaderType::LiteLoader => Some(Self::LiteLoader),
+            ModLoaderType::Fabric => Some(Self::Fabric),
+            ModLoaderType::Quilt => Some(Self::Quilt),
+            ModLoaderType::NeoForge => Some(Self::NeoForge),
+            ModLoaderType::OptiFine => None,
+        }
+    }
+}
+
+/// 搜索条件
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeSearchQuery {
+    #[serde(default)]
+    pub query: String,
+    pub loader: Option<ModLoaderType>,
+    pub game_version: Option<String>,
+    /// 分类 ID，默认为模组
+    pub class_id: Option<u32>,
+    pub category_id: Option<u32>,
+    /// 排序字段：1 精选、2 热度、3 最近更新、4 名称、5 作者、6 下载量
+    pub sort_field: Option<u32>,
+    pub index: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeLinks {
+    pub website_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeLogo {
+    pub url: String,
+    pub thumbnail_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CurseForgeAuthor {
+    pub name: String,
+}
+
+/// CurseForge 上的项目
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeMod {
+    pub id: u32,
+    pub name: String,
+    pub slug: String,
+    #[serde(default)]
+    pub links: CurseForgeLinks,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub download_count: f64,
+    pub logo: Option<CurseForgeLogo>,
+    #[serde(default)]
+    pub authors: Vec<CurseForgeAuthor>,
+    pub class_id: Option<u32>,
+    /// 为 false 时第三方启动器无法通过 API 下载其文件
+    pub allow_mod_distribution: Option<bool>,
+    pub date_modified: Option<String>,
+}
+
+impl CurseForgeMod {
+    /// 网页上下载指定文件的地址
+    pub fn download_page(&self, file_id: u32) -> String {
+        match &self.links.website_url {
+            Some(url) => format!("{}/download/{}", url.trim_end_matches('/'), file_id),
+            None => format!(
+                "https://www.curseforge.com/minecraft/mc-mods/{}/download/{}",
+                self.slug, file_id
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CurseForgeFileHash {
+    pub value: String,
+    /// 1 为 SHA-1，2 为 MD5
+    pub algo: u32,
+}
+
+/// 依赖关系：1 内嵌库、2 可选、3 必需、4 工具、5 不兼容、6 包含
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeFileDependency {
+    pub mod_id: u32,
+    pub relation_type: u32,
+}
+
+/// 项目的一个文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeFile {
+    pub id: u32,
+    pub mod_id: u32,
+    pub display_name: String,
+    pub file_name: String,
+    /// 1 正式版、2 测试版、3 开发版
+    pub release_type: u32,
+    pub file_date: String,
+    pub file_length: u64,
+    /// 作者禁止第三方分发时为空
+    pub download_url: Option<String>,
+    /// 游戏版本与加载器名称
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub hashes: Vec<CurseForgeFileHash>,
+    #[serde(default)]
+    pub dependencies: Vec<CurseForgeFileDependency>,
+    #[serde(default)]
+    pub file_fingerprint: u32,
+}
+
+impl CurseForgeFile {
+    pub fn sha1(&self) -> Option<&str> {
+        self.hashes
+            .iter()
+            .find(|h| h.algo == 1)
+            .map(|h| h.value.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgePagination {
+    pub index: u32,
+    pub page_size: u32,
+    pub result_count: u32,
+    pub total_count: u32,
+}
+
+/// API 响应的外层结构
+#[derive(Deserialize, Debug)]
+pub struct CurseForgeResponse<T> {
+    pub data: T,
+    pub pagination: Option<CurseForgePagination>,
+}
+
+/// 带分页信息的列表
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CurseForgePage<T> {
+    pub data: Vec<T>,
+    pub pagination: Option<CurseForgePagination>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CurseForgeFingerprintMatch {
+    pub file: CurseForgeFile,
+}
+
+/// `/v1/fingerprints` 的结果
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeFingerprintResult {
+    #[serde(default)]
+    pub exact_matches: Vec<CurseForgeFingerprintMatch>,
+}
+
+/// 通过指纹识别出的已安装文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeInstalledFile {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub fingerprint: u32,
+    pub file: CurseForgeFile,
+}
+
+/// 禁止第三方分发，需要用户在网页上手动下载的文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManualDownload {
+    pub mod_id: u32,
+    pub file_id: u32,
+    pub mod_name: String,
+    pub file_name: String,
+    /// 网页下载地址
+    pub url: String,
+    /// 下载后应放置的位置
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeDownloadResult {
+    pub downloaded: Vec<PathBuf>,
+    pub manual: Vec<CurseForgeManualDownload>,
+}
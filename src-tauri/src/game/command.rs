@@ -1,6 +1,10 @@
 use std::path::PathBuf;
 
-use crate::game::version::{model::LocalVersionInfo, util::get_local_versions};
+use crate::game::version::{
+    integrity::{check_version_integrity, get_launchable_versions, VersionIntegrityReport},
+    model::LocalVersionInfo,
+    util::get_local_versions,
+};
 
 #[tauri::command]
 pub async fn get_local_versions_command(
@@ -10,3 +14,23 @@ pub async fn get_local_versions_command(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// 检查本地版本文件是否完整，不需要联网
+#[tauri::command]
+pub async fn check_version_integrity_command(
+    game_path: PathBuf,
+    version_name: String,
+    verify_hashes: Option<bool>,
+) -> Result<VersionIntegrityReport, String> {
+    tokio::task::spawn_blocking(move || {
+        check_version_integrity(&game_path, &version_name, verify_hashes.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("检查版本完整性失败: {}", e))?
+}
+
+/// 列出无需下载即可启动的版本
+#[tauri::command]
+pub async fn get_launchable_versions_command(game_path: PathBuf) -> Result<Vec<String>, String> {
+    get_launchable_versions(game_path).await
+}
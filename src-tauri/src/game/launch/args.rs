@@ -0,0 +1,373 @@
+use std::collections::{HashMap, HashSet};
+use std::net::Ipv6Addr;
+use std::path::{Path, PathBuf};
+
+use super::model::QuickPlay;
+use crate::game::version::model::{Arg, ClientJson, ValueOrList};
+use crate::game::version::util::{library_path, rules_allow, ResolvedVersion};
+
+/// 1.13 以前的版本没有 `arguments.jvm`，使用与官方启动器相同的默认参数
+const LEGACY_JVM_ARGS: &[&str] = &[
+    "-Djava.library.path=${natives_directory}",
+    "-cp",
+    "${classpath}",
+];
+
+#[cfg(target_os = "windows")]
+pub const CLASSPATH_SEPARATOR: &str = ";";
+#[cfg(not(target_os = "windows"))]
+pub const CLASSPATH_SEPARATOR: &str = ":";
+
+/// 展开参数列表，跳过规则不满足的条目
+fn collect_args(args: &[Arg], features: &HashMap<String, bool>) -> Vec<String> {
+    let mut result = Vec::new();
+    for arg in args {
+        match arg {
+            Arg::Value(v) => result.push(v.clone()),
+            Arg::DividedValue { value } => result.extend(value.iter().cloned()),
+            Arg::Conditional { rules, value } => {
+                if rules_allow(rules, features) {
+                    match value {
+                        ValueOrList::One(v) => result.push(v.clone()),
+                        ValueOrList::Many(v) => result.extend(v.iter().cloned()),
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// 版本的游戏参数规则中出现的所有功能
+pub fn declared_features(json: &ClientJson) -> HashSet<&str> {
+    json.arguments
+        .as_ref()
+        .and_then(|a| a.game.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|arg| match arg {
+            Arg::Conditional { rules, .. } => Some(rules),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|rule| rule.features.as_ref())
+        .flat_map(|features| features.keys().map(String::as_str))
+        .collect()
+}
+
+/// 解析服务器地址中的主机与端口，支持 `[IPv6]:端口` 与不带端口的 IPv6 地址，
+/// 返回的主机不含方括号
+pub fn split_server_address(address: &str) -> (String, Option<u16>) {
+    let address = address.trim();
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, rest)) = rest.split_once(']') {
+            let port = rest.strip_prefix(':').and_then(|p| p.parse().ok());
+            return (host.to_string(), port);
+        }
+    }
+    // 不带方括号的 IPv6 地址中的冒号不是端口分隔符
+    if address.parse::<Ipv6Addr>().is_ok() {
+        return (address.to_string(), None);
+    }
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && !host.contains(':') => match port.parse() {
+            Ok(port) => (host.to_string(), Some(port)),
+            Err(_) => (address.to_string(), None),
+        },
+        _ => (address.to_string(), None),
+    }
+}
+
+/// 启用快速游戏对应的功能与占位符，返回需要额外追加的游戏参数
+///
+/// 1.20 起版本通过 `is_quick_play_*` 规则提供参数；更早的版本只能用
+/// `--server`、`--port` 进入服务器，不支持直接进入存档与 Realms。
+pub fn apply_quick_play(
+    quick_play: &QuickPlay,
+    json: &ClientJson,
+    features: &mut HashMap<String, bool>,
+    vars: &mut HashMap<&str, String>,
+) -> Result<Vec<String>, String> {
+    let (feature, var, value) = match quick_play {
+        QuickPlay::Singleplayer { world } => {
+            ("is_quick_play_singleplayer", "quickPlaySingleplayer", world)
+        }
+        QuickPlay::Multiplayer { address } => {
+            ("is_quick_play_multiplayer", "quickPlayMultiplayer", address)
+        }
+        QuickPlay::Realms { realm_id } => ("is_quick_play_realms", "quickPlayRealms", realm_id),
+    };
+    if declared_features(json).contains(feature) {
+        features.insert(feature.to_string(), true);
+        vars.insert(var, value.clone());
+        return Ok(Vec::new());
+    }
+    match quick_play {
+        QuickPlay::Multiplayer { address } => {
+            let (host, port) = split_server_address(address);
+            let mut args = vec!["--server".to_string(), host];
+            if let Some(port) = port {
+                args.extend(["--port".to_string(), port.to_string()]);
+            }
+            Ok(args)
+        }
+        QuickPlay::Singleplayer { .. } => Err("该版本不支持启动后直接进入存档".to_string()),
+        QuickPlay::Realms { .. } => Err("该版本不支持启动后直接进入 Realms".to_string()),
+    }
+}
+
+/// 替换参数中的 `${name}` 占位符，未知的占位符保持原样
+pub fn substitute(arg: &str, vars: &HashMap<&str, String>) -> String {
+    let mut result = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let key = &rest[start + 2..start + end];
+        result.push_str(&rest[..start]);
+        match vars.get(key) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 生成 JVM 参数与游戏参数（均已替换占位符）
+pub fn build_arguments(
+    json: &ClientJson,
+    vars: &HashMap<&str, String>,
+    features: &HashMap<String, bool>,
+) -> (Vec<String>, Vec<String>) {
+    let (jvm, game) = match &json.arguments {
+        Some(arguments) => (
+            arguments
+                .jvm
+                .as_deref()
+                .map(|a| collect_args(a, features))
+                .unwrap_or_else(|| LEGACY_JVM_ARGS.iter().map(|s| s.to_string()).collect()),
+            arguments
+                .game
+                .as_deref()
+                .map(|a| collect_args(a, features))
+                .unwrap_or_default(),
+        ),
+        None => (
+            LEGACY_JVM_ARGS.iter().map(|s| s.to_string()).collect(),
+            json.minecraft_arguments
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect(),
+        ),
+    };
+    (
+        jvm.iter().map(|a| substitute(a, vars)).collect(),
+        game.iter().map(|a| substitute(a, vars)).collect(),
+    )
+}
+
+/// 库的去重键：`group:artifact[:classifier]`
+fn library_key(name: &str) -> String {
+    let coords = name.split('@').next().unwrap_or(name);
+    let parts: Vec<&str> = coords.split(':').collect();
+    match parts.as_slice() {
+        [group, artifact, _, classifier, ..] => format!("{}:{}:{}", group, artifact, classifier),
+        [group, artifact, ..] => format!("{}:{}", group, artifact),
+        _ => coords.to_string(),
+    }
+}
+
+/// 生成类路径，子版本的库优先，同名库只保留一个，最后加入客户端 jar
+pub fn build_classpath(
+    resolved: &ResolvedVersion,
+    libraries_dir: &Path,
+    features: &HashMap<String, bool>,
+) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut classpath = Vec::new();
+    for library in resolved.json.libraries.iter().flatten() {
+        if !rules_allow(library.rules.as_deref().unwrap_or_default(), features) {
+            continue;
+        }
+        // 旧式原生库只需解压，不加入类路径
+        let has_artifact = library
+            .downloads
+            .as_ref()
+            .is_some_and(|d| d.artifact.is_some());
+        if library.natives.is_some() && !has_artifact {
+            continue;
+        }
+        if let Some(name) = &library.name {
+            if !seen.insert(library_key(name)) {
+                continue;
+            }
+        }
+        if let Some(path) = library_path(library) {
+            classpath.push(libraries_dir.join(path));
+        }
+    }
+    classpath.push(resolved.jar_path.clone());
+    classpath
+}
+
+/// 将路径列表拼接为类路径字符串
+pub fn join_classpath(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(CLASSPATH_SEPARATOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute() {
+        let vars = HashMap::from([
+            ("auth_player_name", "Steve".to_string()),
+            ("path", "/tmp/log.xml".to_string()),
+        ]);
+        assert_eq!(substitute("${auth_player_name}", &vars), "Steve");
+        assert_eq!(
+            substitute("-Dlog4j.configurationFile=${path}", &vars),
+            "-Dlog4j.configurationFile=/tmp/log.xml"
+        );
+        assert_eq!(substitute("${unknown}-${path", &vars), "${unknown}-${path");
+    }
+
+    #[test]
+    fn test_build_arguments() {
+        let json: ClientJson = serde_json::from_str(
+            r#"{
+                "arguments": {
+                    "game": [
+                        "--username", "${auth_player_name}",
+                        {
+                            "rules": [{ "action": "allow", "features": { "has_custom_resolution": true } }],
+                            "value": ["--width", "${resolution_width}"]
+                        }
+                    ],
+                    "jvm": ["-cp", "${classpath}"]
+                }
+            }"#,
+        )
+        .unwrap();
+        let vars = HashMap::from([
+            ("auth_player_name", "Steve".to_string()),
+            ("classpath", "a.jar".to_string()),
+            ("resolution_width", "854".to_string()),
+        ]);
+        let (jvm, game) = build_arguments(&json, &vars, &HashMap::new());
+        assert_eq!(jvm, vec!["-cp", "a.jar"]);
+        assert_eq!(game, vec!["--username", "Steve"]);
+
+        let features = HashMap::from([("has_custom_resolution".to_string(), true)]);
+        let (_, game) = build_arguments(&json, &vars, &features);
+        assert_eq!(game, vec!["--username", "Steve", "--width", "854"]);
+
+        let legacy: ClientJson = serde_json::from_str(
+            r#"{ "minecraftArguments": "--username ${auth_player_name} --tweakClass foo" }"#,
+        )
+        .unwrap();
+        let (jvm, game) = build_arguments(&legacy, &vars, &HashMap::new());
+        assert_eq!(jvm[1..], ["-cp", "a.jar"]);
+        assert_eq!(game, vec!["--username", "Steve", "--tweakClass", "foo"]);
+    }
+
+    #[test]
+    fn test_apply_quick_play() {
+        let json: ClientJson = serde_json::from_str(
+            r#"{
+                "arguments": {
+                    "game": [
+                        "--username", "${auth_player_name}",
+                        {
+                            "rules": [{ "action": "allow", "features": { "is_quick_play_singleplayer": true } }],
+                            "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]
+                        },
+                        {
+                            "rules": [{ "action": "allow", "features": { "is_quick_play_multiplayer": true } }],
+                            "value": ["--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+                        }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        let multiplayer = QuickPlay::Multiplayer {
+            address: "mc.example.com:25566".to_string(),
+        };
+        let mut features = HashMap::new();
+        let mut vars = HashMap::from([("auth_player_name", "Steve".to_string())]);
+        let extra = apply_quick_play(&multiplayer, &json, &mut features, &mut vars).unwrap();
+        assert!(extra.is_empty());
+        let (_, game) = build_arguments(&json, &vars, &features);
+        assert_eq!(
+            game,
+            vec![
+                "--username",
+                "Steve",
+                "--quickPlayMultiplayer",
+                "mc.example.com:25566"
+            ]
+        );
+        let realms = QuickPlay::Realms {
+            realm_id: "1".to_string(),
+        };
+        assert!(apply_quick_play(&realms, &json, &mut features, &mut vars).is_err());
+
+        let legacy: ClientJson =
+            serde_json::from_str(r#"{ "minecraftArguments": "--username ${auth_player_name}" }"#)
+                .unwrap();
+        let mut features = HashMap::new();
+        let extra = apply_quick_play(&multiplayer, &legacy, &mut features, &mut vars).unwrap();
+        assert_eq!(extra, vec!["--server", "mc.example.com", "--port", "25566"]);
+        assert!(features.is_empty());
+        let singleplayer = QuickPlay::Singleplayer {
+            world: "New World".to_string(),
+        };
+        assert!(apply_quick_play(&singleplayer, &legacy, &mut features, &mut vars).is_err());
+    }
+
+    #[test]
+    fn test_split_server_address() {
+        assert_eq!(
+            split_server_address("mc.example.com"),
+            ("mc.example.com".to_string(), None)
+        );
+        assert_eq!(
+            split_server_address("127.0.0.1:25566"),
+            ("127.0.0.1".to_string(), Some(25566))
+        );
+        assert_eq!(split_server_address("[::1]"), ("::1".to_string(), None));
+        assert_eq!(
+            split_server_address("[2001:db8::1]:25566"),
+            ("2001:db8::1".to_string(), Some(25566))
+        );
+        assert_eq!(split_server_address("::1"), ("::1".to_string(), None));
+        assert_eq!(
+            split_server_address("2001:db8::25565"),
+            ("2001:db8::25565".to_string(), None)
+        );
+        assert_eq!(
+            split_server_address("mc.example.com:abc"),
+            ("mc.example.com:abc".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_library_key() {
+        assert_eq!(library_key("org.ow2.asm:asm:9.6"), "org.ow2.asm:asm");
+        assert_eq!(
+            library_key("org.lwjgl:lwjgl:3.3.3:natives-linux"),
+            "org.lwjgl:lwjgl:natives-linux"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use tauri::ipc::Channel;
+
+use crate::config::model::AccountInfo;
+use crate::game::launch::model::{LaunchEvent, QuickPlay};
+use crate::game::launch::util::launch_profile;
+
+/// 使用指定配置启动游戏，返回游戏进程的 PID
+///
+/// `quick_play` 为启动后直接进入的存档、服务器或 Realm。
+#[tauri::command]
+pub async fn launch_profile_command(
+    game_path: PathBuf,
+    profile_id: String,
+    account: AccountInfo,
+    quick_play: Option<QuickPlay>,
+    on_event: Channel<LaunchEvent>,
+) -> Result<u32, String> {
+    launch_profile(
+        &game_path,
+        &profile_id,
+        &account,
+        quick_play,
+        move |event| {
+            if let Err(e) = on_event.send(event) {
+                eprintln!("发送启动事件失败: {}", e);
+            }
+        },
+    )
+    .await
+}
@@ -0,0 +1,229 @@
+use serde::Serialize;
+use sysinfo::{MemoryRefreshKind, RefreshKind, System};
+
+use crate::game::version::model::ClientJson;
+
+/// 32 位 JVM 能稳定分配的最大堆内存
+const MAX_32BIT_HEAP_MB: u64 = 1024;
+/// 为系统和其他程序保留的内存比例，分配给游戏的内存不超过物理内存的 3/4
+const MAX_TOTAL_RATIO: (u64, u64) = (3, 4);
+/// 每个模组额外分配的内存
+const MB_PER_MOD: u64 = 48;
+/// 模组带来的额外内存上限
+const MAX_MOD_EXTRA_MB: u64 = 8192;
+/// 使用 ZGC 的最小堆内存，更小的堆使用 G1 效果更好
+const ZGC_MIN_HEAP_MB: u64 = 4096;
+
+/// 按启动方式与性能需求划分的游戏版本时期
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionEra {
+    /// 1.13 以前，使用 `minecraftArguments`
+    Legacy,
+    /// 1.13 至 1.17
+    Flattening,
+    /// 1.18 及以后，需要 Java 17 以上，世界生成更耗内存
+    Modern,
+}
+
+impl VersionEra {
+    pub fn from_client_json(json: &ClientJson) -> Self {
+        let java_major = json
+            .java_version
+            .as_ref()
+            .and_then(|j| j.major_version)
+            .unwrap_or(8);
+        if json.arguments.is_none() {
+            VersionEra::Legacy
+        } else if java_major >= 17 {
+            VersionEra::Modern
+        } else {
+            VersionEra::Flattening
+        }
+    }
+
+    /// 原版在该时期的推荐最大内存
+    fn base_memory_mb(&self) -> u64 {
+        match self {
+            VersionEra::Legacy => 1536,
+            VersionEra::Flattening => 2048,
+            VersionEra::Modern => 3072,
+        }
+    }
+}
+
+/// 内存估算所需的信息，物理内存由调用方传入以便测试
+#[derive(Debug, Clone)]
+pub struct MemoryInput {
+    /// 物理内存总量（MB）
+    pub total_mb: u64,
+    /// 当前可用物理内存（MB）
+    pub available_mb: u64,
+    /// 是否为 64 位 JRE
+    pub is_64bit: bool,
+    /// JRE 主版本号
+    pub java_major: u32,
+    pub era: VersionEra,
+    /// 已启用的模组数量
+    pub mod_count: usize,
+}
+
+/// 自动内存分配的结果
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryAdvice {
+    pub max_mb: u64,
+    pub min_mb: u64,
+    /// 推荐的垃圾回收器参数
+    pub gc_args: Vec<String>,
+}
+
+impl MemoryAdvice {
+    /// 转换为 JVM 参数，`with_gc` 为 false 时不包含垃圾回收器参数
+    pub fn to_args(&self, with_gc: bool) -> Vec<String> {
+        let mut args = vec![
+            format!("-Xmx{}M", self.max_mb),
+            format!("-Xms{}M", self.min_mb),
+        ];
+        if with_gc {
+            args.extend(self.gc_args.iter().cloned());
+        }
+        args
+    }
+}
+
+/// 读取物理内存总量与可用量（MB）
+pub fn read_system_memory() -> (u64, u64) {
+    let system = System::new_with_specifics(
+        RefreshKind::nothing().with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    (
+        system.total_memory() / 1024 / 1024,
+        system.available_memory() / 1024 / 1024,
+    )
+}
+
+/// 根据 Java 版本与堆大小推荐垃圾回收器参数
+///
+/// Java 21 起且堆足够大时使用分代 ZGC，其余情况使用官方启动器同款的 G1 调优参数。
+fn gc_args(java_major: u32, is_64bit: bool, max_mb: u64) -> Vec<String> {
+    let args: &[&str] = if java_major >= 21 && is_64bit && max_mb >= ZGC_MIN_HEAP_MB {
+        // Java 23 起分代模式为默认值，24 起该参数已废弃
+        if java_major < 23 {
+            &["-XX:+UseZGC", "-XX:+ZGenerational"]
+        } else {
+            &["-XX:+UseZGC"]
+        }
+    } else {
+        &[
+            "-XX:+UseG1GC",
+            "-XX:+UnlockExperimentalVMOptions",
+            "-XX:G1NewSizePercent=20",
+            "-XX:G1ReservePercent=20",
+            "-XX:MaxGCPauseMillis=50",
+            "-XX:G1HeapRegionSize=32M",
+        ]
+    };
+    args.iter().map(|s| s.to_string()).collect()
+}
+
+/// 估算 `JvmMemory::Auto` 时使用的内存
+///
+/// 以版本时期的推荐值为基础，按模组数量增加，再受物理内存、可用内存与 JRE 位数限制。
+pub fn advise_memory(input: &MemoryInput) -> MemoryAdvice {
+    let wanted =
+        input.era.base_memory_mb() + (input.mod_count as u64 * MB_PER_MOD).min(MAX_MOD_EXTRA_MB);
+    let mut cap = (input.total_mb * MAX_TOTAL_RATIO.0 / MAX_TOTAL_RATIO.1)
+        .min((input.available_mb * 9 / 10).max(1024));
+    if !input.is_64bit {
+        cap = cap.min(MAX_32BIT_HEAP_MB);
+    }
+    // 内存极少时至少保证 512 MB，否则游戏无法启动
+    let max_mb = wanted.min(cap).max(512);
+    let min_mb = (max_mb / 4).clamp(256, 1024).min(max_mb);
+    MemoryAdvice {
+        max_mb,
+        min_mb,
+        gc_args: gc_args(input.java_major, input.is_64bit, max_mb),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(total_mb: u64, available_mb: u64) -> MemoryInput {
+        MemoryInput {
+            total_mb,
+            available_mb,
+            is_64bit: true,
+            java_major: 21,
+            era: VersionEra::Modern,
+            mod_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_advise_vanilla() {
+        let advice = advise_memory(&input(16384, 12000));
+        assert_eq!(advice.max_mb, 3072);
+        assert_eq!(advice.min_mb, 768);
+        assert_eq!(advice.gc_args[0], "-XX:+UseG1GC");
+
+        let legacy = advise_memory(&MemoryInput {
+            era: VersionEra::Legacy,
+            java_major: 8,
+            ..input(16384, 12000)
+        });
+        assert_eq!(legacy.max_mb, 1536);
+        assert_eq!(
+            legacy.to_args(false),
+            vec!["-Xmx1536M".to_string(), "-Xms384M".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_advise_modded() {
+        let advice = advise_memory(&MemoryInput {
+            mod_count: 200,
+            ..input(32768, 24000)
+        });
+        assert_eq!(advice.max_mb, 3072 + 8192);
+        assert_eq!(advice.min_mb, 1024);
+        assert_eq!(advice.gc_args, vec!["-XX:+UseZGC", "-XX:+ZGenerational"]);
+
+        let java25 = advise_memory(&MemoryInput {
+            mod_count: 200,
+            java_major: 25,
+            ..input(32768, 24000)
+        });
+        assert_eq!(java25.gc_args, vec!["-XX:+UseZGC"]);
+    }
+
+    #[test]
+    fn test_advise_limited_memory() {
+        // 可用内存不足时不超过可用内存的 90%
+        let advice = advise_memory(&MemoryInput {
+            mod_count: 100,
+            ..input(8192, 2000)
+        });
+        assert_eq!(advice.max_mb, 1800);
+
+        // 物理内存很小时不超过 3/4
+        let advice = advise_memory(&input(2048, 2000));
+        assert_eq!(advice.max_mb, 1536);
+
+        // 32 位 JRE 限制为 1 GB
+        let advice = advise_memory(&MemoryInput {
+            is_64bit: false,
+            java_major: 8,
+            ..input(16384, 12000)
+        });
+        assert_eq!(advice.max_mb, 1024);
+        assert_eq!(advice.gc_args[0], "-XX:+UseG1GC");
+
+        let advice = advise_memory(&input(512, 100));
+        assert_eq!(advice.max_mb, 512);
+        assert!(advice.min_mb <= advice.max_mb);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod args;
+pub mod command;
+pub mod memory;
+pub mod model;
+pub mod util;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum LaunchEvent {
+    /// 游戏进程已启动
+    Started { pid: u32 },
+    /// 游戏输出或启动器提示的一行日志
+    Log { line: String },
+    /// 启动前检查发现的非致命问题，不影响启动
+    Warning { message: String },
+    /// 游戏进程退出
+    Exited { code: Option<i32> },
+}
+
+/// 启动后直接进入的存档、服务器或 Realm
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum QuickPlay {
+    /// 单人存档的文件夹名称
+    Singleplayer { world: String },
+    /// 服务器地址，可带端口
+    Multiplayer { address: String },
+    #[serde(rename_all = "camelCase")]
+    Realms { realm_id: String },
+}
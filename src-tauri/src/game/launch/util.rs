@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::auth::session::resolve_launch_account;
+use crate::config::model::AccountInfo;
+use crate::game::launch::args::{
+    apply_quick_play, build_arguments, build_classpath, join_classpath, substitute,
+};
+use crate::game::launch::memory::{advise_memory, read_system_memory, MemoryInput, VersionEra};
+use crate::game::launch::model::{LaunchEvent, QuickPlay};
+use crate::game::mods::resolver::check_mods;
+use crate::game::mods::scanner::scan_mods;
+use crate::game::mods::util::mod_environment;
+use crate::game::profile::model::{JvmMemory, Profile};
+use crate::game::profile::util::{
+    get_profile, missing_version_message, resolve_game_directory, set_last_profile,
+};
+use crate::game::version::integrity::check_version_integrity;
+use crate::game::version::util::{
+    native_artifact, resolve_client_json, version_installed, ResolvedVersion,
+};
+use crate::game::worlds::util::world_dir;
+use crate::jre::finder::java_binary;
+use crate::jre::model::{Architecture, JreInfo};
+use crate::jre::selector::select_java_for_profile;
+use crate::util::network::is_offline;
+
+const LAUNCHER_NAME: &str = "AsanMCL";
+
+/// 本次启动独占的 natives 目录，释放时删除
+///
+/// 同一版本可以同时运行多个实例，每次启动解压到 `natives-<uuid>`，不影响正在运行的实例。
+struct NativesDir(PathBuf);
+
+impl NativesDir {
+    fn new(version_dir: &Path) -> Self {
+        NativesDir(version_dir.join(format!("natives-{}", Uuid::new_v4().simple())))
+    }
+}
+
+impl Drop for NativesDir {
+    fn drop(&mut self) {
+        if self.0.exists() {
+            if let Err(e) = fs::remove_dir_all(&self.0) {
+                eprintln!("清理 natives 目录失败 {}: {}", self.0.display(), e);
+            }
+        }
+    }
+}
+
+/// 将旧式原生库解压到 natives 目录
+fn extract_natives(
+    resolved: &ResolvedVersion,
+    libraries_dir: &Path,
+    natives_dir: &Path,
+) -> Result<(), String> {
+    fs::create_dir_all(natives_dir).map_err(|e| format!("创建 natives 目录失败: {}", e))?;
+    for library in resolved.json.libraries.iter().flatten() {
+        let Some(path) = native_artifact(library).and_then(|a| a.path.as_ref()) else {
+            continue;
+        };
+        let excludes: &[String] = library
+            .extract
+            .as_ref()
+            .and_then(|e| e.exclude.as_deref())
+            .unwrap_or_default();
+        let jar_path = libraries_dir.join(path);
+        let file = File::open(&jar_path)
+            .map_err(|e| format!("打开原生库失败 {}: {}", jar_path.display(), e))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| format!("读取原生库失败 {}: {}", jar_path.display(), e))?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+            if entry.is_dir()
+                || excludes
+                    .iter()
+                    .any(|ex| entry.name().starts_with(ex.as_str()))
+            {
+                continue;
+            }
+            let Some(name) = entry.enclosed_name() else {
+                continue;
+            };
+            let out_path = natives_dir.join(name);
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let mut out = File::create(&out_path).map_err(|e| e.to_string())?;
+            io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn java_executable(java_home: &Path) -> Result<PathBuf, String> {
+    let java = java_binary(java_home);
+    if !java.exists() {
+        return Err(format!("Java 不存在: {}", java.display()));
+    }
+    Ok(java)
+}
+
+/// 统计模组目录中已启用的模组数量
+fn count_mods(game_directory: &Path) -> usize {
+    fs::read_dir(game_directory.join("mods"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "jar"))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// 生成内存相关的 JVM 参数，自动模式下同时给出垃圾回收器参数
+fn memory_args(
+    profile: &Profile,
+    jre: &JreInfo,
+    era: VersionEra,
+    game_directory: &Path,
+) -> Vec<String> {
+    match &profile.jvm_memory {
+        Some(JvmMemory::Custom(mb)) => vec![format!("-Xmx{}M", mb)],
+        Some(JvmMemory::Auto) | None => {
+            let (total_mb, available_mb) = read_system_memory();
+            let input = MemoryInput {
+                total_mb,
+                available_mb,
+                is_64bit: jre.arch != Architecture::X86,
+                java_major: jre.major().unwrap_or(8),
+                era,
+                mod_count: count_mods(game_directory),
+            };
+            // 用户已指定垃圾回收器时不再添加，避免 JVM 因选择了多个回收器而无法启动
+            let custom_gc = profile
+                .advanced
+                .as_ref()
+                .and_then(|a| a.jvm_args.as_deref())
+                .is_some_and(|args| {
+                    args.split_whitespace()
+                        .any(|a| a.starts_with("-XX:+Use") && a.ends_with("GC"))
+                });
+            advise_memory(&input).to_args(!custom_gc)
+        }
+    }
+}
+
+fn forward_output<R>(reader: R, on_event: impl Fn(LaunchEvent) + Send + Sync + 'static)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            on_event(LaunchEvent::Log { line });
+        }
+    });
+}
+
+/// 按配置启动游戏，返回游戏进程的 PID
+///
+/// 配置中的 Java、内存、分辨率、全屏、服务器与高级参数都在这里生效。
+/// 未指定 `quick_play` 时，配置了服务器地址的配置会直接进入该服务器。
+pub async fn launch_profile<F>(
+    game_path: &Path,
+    profile_id: &str,
+    account: &AccountInfo,
+    quick_play: Option<QuickPlay>,
+    on_event: F,
+) -> Result<u32, String>
+where
+    F: Fn(LaunchEvent) + Clone + Send + Sync + 'static,
+{
+    let profile = get_profile(game_path, profile_id)?;
+    let version_name = profile.version_name.as_str();
+    let advanced = profile.advanced.clone().unwrap_or_default();
+
+    // 导入整合包时不会安装游戏与加载器，需要先安装对应的版本
+    if !version_installed(game_path, version_name) {
+        return Err(missing_version_message(
+            version_name,
+            profile.requirement.as_ref(),
+        ));
+    }
+
+    if !advanced.skip_game_integrity_check.unwrap_or(false) {
+        let (path, name) = (game_path.to_path_buf(), version_name.to_string());
+        let report =
+            tokio::task::spawn_blocking(move || check_version_integrity(&path, &name, false))
+                .await
+                .map_err(|e| format!("检查版本完整性失败: {}", e))??;
+        if !report.complete {
+            let hint = if is_offline().await {
+                "当前处于离线模式，无法补全缺失的文件"
+            } else {
+                "请重新安装该版本以补全文件"
+            };
+            return Err(format!(
+                "版本 {} 缺少 {} 个文件，{}",
+                version_name,
+                report.missing.len() + report.corrupted.len(),
+                hint
+            ));
+        }
+    }
+
+    let resolved = resolve_client_json(game_path, version_name)?;
+    let main_class = resolved
+        .json
+        .main_class
+        .clone()
+        .ok_or_else(|| format!("版本 {} 缺少主类", version_name))?;
+    let launch_account = resolve_launch_account(account).await?;
+    let libraries_dir = game_path.join("libraries");
+    let selection = select_java_for_profile(&profile, &resolved.json, &libraries_dir).await?;
+    on_event(LaunchEvent::Log {
+        line: selection.reason.clone(),
+    });
+    let java = java_executable(&selection.jre.path)?;
+
+    let game_directory = resolve_game_directory(game_path, &profile);
+    fs::create_dir_all(&game_directory).map_err(|e| format!("创建游戏目录失败: {}", e))?;
+
+    if !advanced.skip_mod_check.unwrap_or(false) {
+        let mods_dir = game_directory.join("mods");
+        let mods = tokio::task::spawn_blocking(move || scan_mods(&mods_dir))
+            .await
+            .map_err(|e| format!("扫描模组失败: {}", e))??;
+        let report = check_mods(&mods, &mod_environment(&resolved, selection.jre.major()));
+        for issue in report.issues.iter().filter(|i| !i.fatal) {
+            on_event(LaunchEvent::Warning {
+                message: issue.message.clone(),
+            });
+        }
+        let fatal: Vec<&str> = report.fatal_issues().map(|i| i.message.as_str()).collect();
+        if !fatal.is_empty() {
+            return Err(format!(
+                "模组检查未通过，可在高级设置中跳过检查:\n{}",
+                fatal.join("\n")
+            ));
+        }
+    }
+    let assets_dir = game_path.join("assets");
+    let natives = NativesDir::new(&game_path.join("versions").join(version_name));
+    let natives_dir = natives.0.clone();
+    let resolved = {
+        let libraries_dir = libraries_dir.clone();
+        let natives_dir = natives_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            extract_natives(&resolved, &libraries_dir, &natives_dir).map(|()| resolved)
+        })
+        .await
+        .map_err(|e| format!("解压原生库失败: {}", e))??
+    };
+
+    let mut features = HashMap::new();
+    if profile.resolution.is_some() {
+        features.insert("has_custom_resolution".to_string(), true);
+    }
+    let classpath = join_classpath(&build_classpath(&resolved, &libraries_dir, &features));
+    let assets_index_name = resolved
+        .json
+        .asset_index
+        .as_ref()
+        .and_then(|a| a.id.clone())
+        .or_else(|| resolved.json.assets.clone())
+        .unwrap_or_else(|| "legacy".to_string());
+    let uuid = launch_account.uuid.replace('-', "");
+
+    let mut vars: HashMap<&str, String> = HashMap::from([
+        ("auth_player_name", launch_account.name.clone()),
+        ("version_name", version_name.to_string()),
+        (
+            "game_directory",
+            game_directory.to_string_lossy().into_owned(),
+        ),
+        ("assets_root", assets_dir.to_string_lossy().into_owned()),
+        ("assets_index_name", assets_index_name.clone()),
+        (
+            "game_assets",
+            assets_dir
+                .join("virtual")
+                .join(&assets_index_name)
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        ("auth_uuid", uuid.clone()),
+        ("auth_access_token", launch_account.access_token.clone()),
+        (
+            "auth_session",
+            format!("token:{}:{}", launch_account.access_token, uuid),
+        ),
+        ("user_type", launch_account.user_type.clone()),
+        ("user_properties", "{}".to_string()),
+        ("clientid", String::new()),
+        ("auth_xuid", String::new()),
+        ("version_type", LAUNCHER_NAME.to_string()),
+        ("launcher_name", LAUNCHER_NAME.to_string()),
+        ("launcher_version", env!("CARGO_PKG_VERSION").to_string()),
+        (
+            "natives_directory",
+            natives_dir.to_string_lossy().into_owned(),
+        ),
+        (
+            "library_directory",
+            libraries_dir.to_string_lossy().into_owned(),
+        ),
+        (
+            "classpath_separator",
+            super::args::CLASSPATH_SEPARATOR.to_string(),
+        ),
+        ("classpath", classpath),
+    ]);
+    if let Some(resolution) = &profile.resolution {
+        vars.insert("resolution_width", resolution.width.to_string());
+        vars.insert("resolution_height", resolution.height.to_string());
+    }
+    let quick_play = quick_play.or_else(|| {
+        profile
+            .server_address
+            .as_deref()
+            .filter(|a| !a.trim().is_empty())
+            .map(|address| QuickPlay::Multiplayer {
+                address: address.trim().to_string(),
+            })
+    });
+    let quick_play_args = match &quick_play {
+        Some(quick_play) => {
+            if let QuickPlay::Singleplayer { world } = quick_play {
+                world_dir(&game_directory, world)?;
+            }
+            apply_quick_play(quick_play, &resolved.json, &mut features, &mut vars)?
+        }
+        None => Vec::new(),
+    };
+
+    let (jvm_args, mut game_args) = build_arguments(&resolved.json, &vars, &features);
+
+    let mut args: Vec<String> = Vec::new();
+    args.extend(memory_args(
+        &profile,
+        &selection.jre,
+        VersionEra::from_client_json(&resolved.json),
+        &game_directory,
+    ));
+    if let Some(logging) = resolved
+        .json
+        .logging
+        .as_ref()
+        .and_then(|l| l.client.as_ref())
+    {
+        let config = logging
+            .file
+            .as_ref()
+            .and_then(|f| f.id.as_ref())
+            .map(|id| assets_dir.join("log_configs").join(id));
+        if let (Some(argument), Some(config)) = (&logging.argument, config) {
+            if config.exists() {
+                let vars = HashMap::from([("path", config.to_string_lossy().into_owned())]);
+                args.push(substitute(argument, &vars));
+            }
+        }
+    }
+    args.extend(jvm_args);
+    if let Some(extra) = &advanced.jvm_args {
+        args.extend(extra.split_whitespace().map(|s| s.to_string()));
+    }
+    args.push(main_class);
+
+    // 1.13 以前的版本不通过规则提供分辨率参数
+    if resolved.json.arguments.is_none() {
+        if let Some(resolution) = &profile.resolution {
+            game_args.extend([
+                "--width".to_string(),
+                resolution.width.to_string(),
+                "--height".to_string(),
+                resolution.height.to_string(),
+            ]);
+        }
+    }
+    if profile.fullscreen.unwrap_or(false) {
+        game_args.push("--fullscreen".to_string());
+    }
+    game_args.extend(quick_play_args);
+    if let Some(extra) = &advanced.game_args {
+        game_args.extend(extra.split_whitespace().map(|s| s.to_string()));
+    }
+    args.extend(game_args);
+
+    let mut command = match advanced.wrap_command.as_deref().map(str::split_whitespace) {
+        Some(mut wrapper) if wrapper.clone().next().is_some() => {
+            let mut command = Command::new(wrapper.next().unwrap());
+            command.args(wrapper).arg(&java);
+            command
+        }
+        _ => Command::new(&java),
+    };
+    let show_logs = profile.show_logs.unwrap_or(false);
+    command
+        .args(&args)
+        .current_dir(&game_directory)
+        .stdin(Stdio::null())
+        .stdout(if show_logs {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stderr(if show_logs {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        });
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("启动游戏进程失败: {}", e))?;
+    let pid = child.id().unwrap_or_default();
+    if let Err(e) = set_last_profile(game_path, profile_id) {
+        eprintln!("{}", e);
+    }
+    on_event(LaunchEvent::Started { pid });
+    if let Some(stdout) = child.stdout.take() {
+        forward_output(stdout, on_event.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_output(stderr, on_event.clone());
+    }
+    tokio::spawn(async move {
+        let code = child.wait().await.ok().and_then(|s| s.code());
+        let _ = tokio::task::spawn_blocking(move || drop(natives)).await;
+        on_event(LaunchEvent::Exited { code });
+    });
+    Ok(pid)
+}
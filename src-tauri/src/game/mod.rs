@@ -1,5 +1,10 @@
 pub mod asset;
 pub mod command;
+pub mod launch;
 pub mod modloader;
+pub mod mods;
+pub mod packs;
 pub mod profile;
+pub mod servers;
 pub mod version;
+pub mod worlds;
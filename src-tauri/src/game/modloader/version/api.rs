@@ -2,7 +2,10 @@ use crate::config::model::DownloadSource;
 use crate::game::modloader::version::models::fabric::{
     FabricLoaderVersionJson, FabricSupportedGameVersion,
 };
-use crate::util::reqwest_client::REQWEST_CLIENT;
+use crate::util::cache::get_cached_json;
+use crate::util::mirror::current_source;
+use std::collections::HashMap;
+use std::time::Duration;
 
 // #[derive(serde::Deserialize)]
 // pub struct BmclApiError {
@@ -10,23 +13,21 @@ use crate::util::reqwest_client::REQWEST_CLIENT;
 //     pub http_code: Option<u16>,
 //     pub message: Option<String>,
 // }
+const FABRIC_META_ROOT: &str = "https://meta.fabricmc.net";
+/// 支持的游戏版本列表变化较少，缓存时间较长
+const FABRIC_GAME_VERSIONS_TTL: Duration = Duration::from_secs(60 * 60);
+const FABRIC_LOADER_VERSIONS_TTL: Duration = Duration::from_secs(10 * 60);
+/// Forge 各游戏版本对应的加载器版本，BMCLAPI 与自定义源通过 Maven 改写提供
+const FORGE_MAVEN_METADATA_URL: &str =
+    "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json";
+const FORGE_GAME_VERSIONS_TTL: Duration = Duration::from_secs(60 * 60);
+
 pub async fn get_fabric_supported_game_versions(
     download_source: DownloadSource,
 ) -> Result<Vec<FabricSupportedGameVersion>, String> {
-    let url = match download_source {
-        DownloadSource::Official => "https://meta.fabricmc.net/v2/versions/game",
-        DownloadSource::BmclApi => "https://bmclapi2.bangbang93.com/fabric-meta/v2/versions/game",
-    };
-    let client = {
-        let guard = REQWEST_CLIENT.lock().await;
-        match &*guard {
-            Some(c) => c.clone(),
-            None => return Err("HTTP客户端未初始化".to_string()),
-        }
-    };
-    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let url = format!("{}/v2/versions/game", FABRIC_META_ROOT);
     let versions: Vec<FabricSupportedGameVersion> =
-        response.json().await.map_err(|e| e.to_string())?;
+        get_cached_json(&url, &download_source, FABRIC_GAME_VERSIONS_TTL).await?;
 
     Ok(versions)
 }
@@ -35,32 +36,14 @@ pub async fn get_fabric_loader_versions_by_game_version(
     game_version: String,
     download_source: DownloadSource,
 ) -> Result<Vec<String>, String> {
-    // 先判断是否支持该游戏版本
-    let supported_versions = get_fabric_supported_game_versions(download_source.clone()).await?;
-    let is_supported = supported_versions.iter().any(|v| v.version == game_version);
-    if !is_supported {
+    // BMCLAPI 不支持 v1 格式，统一使用 v2
+    let url = format!("{}/v2/versions/loader/{}", FABRIC_META_ROOT, game_version);
+    let versions: Vec<FabricLoaderVersionJson> =
+        get_cached_json(&url, &download_source, FABRIC_LOADER_VERSIONS_TTL).await?;
+    // 不支持的游戏版本返回空列表
+    if versions.is_empty() {
         return Err("不支持该游戏版本".to_string());
     }
-    let url = match download_source {
-        DownloadSource::Official => format!(
-            "https://meta.fabricmc.net/v1/versions/loader/{}",
-            game_version
-        ),
-        DownloadSource::BmclApi => format!(
-            "https://bmclapi2.bangbang93.com/fabric-meta/v2/versions/loader/{}",
-            game_version
-        ), // BMCLAPI 不支持 v1 格式
-    };
-    let client = {
-        let guard = REQWEST_CLIENT.lock().await;
-        match &*guard {
-            Some(c) => c.clone(),
-            None => return Err("HTTP客户端未初始化".to_string()),
-        }
-    };
-    let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
-    let versions: Vec<FabricLoaderVersionJson> =
-        response.json().await.map_err(|e| e.to_string())?;
     let versions_list = versions
         .into_iter()
         .map(|v| v.loader.version)
@@ -69,17 +52,24 @@ pub async fn get_fabric_loader_versions_by_game_version(
     Ok(versions_list)
 }
 
+/// 按数字段比较游戏版本号，如 1.7.10_pre4 视为 [1, 7, 10, 4]
+fn game_version_key(version: &str) -> Vec<u32> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// 获取 Forge 支持的游戏版本，按版本从新到旧排列
 pub async fn get_forge_supported_game_versions() -> Result<Vec<String>, String> {
-    let url = "https://bmclapi2.bangbang93.com/forge/minecraft";
-    let client = {
-        let guard = REQWEST_CLIENT.lock().await;
-        match &*guard {
-            Some(c) => c.clone(),
-            None => return Err("HTTP客户端未初始化".to_string()),
-        }
-    };
-    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
-    let versions: Vec<String> = response.json().await.map_err(|e| e.to_string())?;
+    let metadata: HashMap<String, Vec<String>> = get_cached_json(
+        FORGE_MAVEN_METADATA_URL,
+        &current_source(),
+        FORGE_GAME_VERSIONS_TTL,
+    )
+    .await?;
+    let mut versions: Vec<String> = metadata.into_keys().collect();
+    versions.sort_by_cached_key(|v| std::cmp::Reverse(game_version_key(v)));
     Ok(versions)
 }
 
@@ -87,6 +77,13 @@ pub async fn get_forge_supported_game_versions() -> Result<Vec<String>, String>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_game_version_key() {
+        let mut versions = vec!["1.7.10_pre4", "1.20.1", "1.7.10", "1.9"];
+        versions.sort_by_cached_key(|v| std::cmp::Reverse(game_version_key(v)));
+        assert_eq!(versions, ["1.20.1", "1.9", "1.7.10_pre4", "1.7.10"]);
+    }
+
     #[tokio::test]
     async fn test_get_fabric_supported_game_versions() {
         let versions = get_fabric_supported_game_versions(DownloadSource::Official).await;
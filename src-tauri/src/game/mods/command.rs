@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use crate::game::mods::{
+    model::{ModCheckReport, ModFile},
+    resolver::check_mods,
+    scanner::scan_mods,
+    util::{get_profile_mods_dir, mod_environment, read_mod_icon, set_mods_enabled},
+};
+use crate::game::{profile::util::get_profile, version::util::resolve_client_json};
+use crate::jre::selector::select_java_for_profile;
+
+/// 扫描配置模组目录中的所有模组，包括已禁用的
+#[tauri::command]
+pub async fn scan_mods_command(
+    game_path: PathBuf,
+    profile_id: String,
+) -> Result<Vec<ModFile>, String> {
+    let mods_dir = get_profile_mods_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || scan_mods(&mods_dir))
+        .await
+        .map_err(|e| format!("扫描模组失败: {}", e))?
+}
+
+/// 批量启用或禁用模组，任一失败时全部撤销，返回重命名后的路径
+#[tauri::command]
+pub async fn set_mods_enabled_command(
+    paths: Vec<PathBuf>,
+    enabled: bool,
+) -> Result<Vec<PathBuf>, String> {
+    tokio::task::spawn_blocking(move || set_mods_enabled(&paths, enabled))
+        .await
+        .map_err(|e| format!("切换模组状态失败: {}", e))?
+}
+
+/// 读取模组图标，返回 data URL
+#[tauri::command]
+pub async fn get_mod_icon_command(path: PathBuf, icon: String) -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(move || read_mod_icon(&path, &icon))
+        .await
+        .map_err(|e| format!("读取图标失败: {}", e))?
+}
+
+/// 检查配置中已启用模组的前置、版本要求、冲突与重复
+#[tauri::command]
+pub async fn check_mods_command(
+    game_path: PathBuf,
+    profile_id: String,
+) -> Result<ModCheckReport, String> {
+    let profile = get_profile(&game_path, &profile_id)?;
+    let resolved = resolve_client_json(&game_path, &profile.version_name)?;
+    // 找不到合适的 Java 时不检查模组对 Java 的要求
+    let java_major =
+        select_java_for_profile(&profile, &resolved.json, &game_path.join("libraries"))
+            .await
+            .ok()
+            .and_then(|selection| selection.jre.major());
+    let environment = mod_environment(&resolved, java_major);
+    let mods_dir = get_profile_mods_dir(&game_path, &profile_id)?;
+    let mods = tokio::task::spawn_blocking(move || scan_mods(&mods_dir))
+        .await
+        .map_err(|e| format!("扫描模组失败: {}", e))??;
+    Ok(check_mods(&mods, &environment))
+}
@@ -0,0 +1,6 @@
+pub mod command;
+pub mod model;
+pub mod resolver;
+pub mod scanner;
+pub mod util;
+pub mod version;
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::version::model::{ModLoaderInfo, ModLoaderType};
+
+/// 依赖关系的类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    /// 必需
+    Required,
+    /// 可选，存在时需要满足版本要求
+    Optional,
+    /// 不兼容，不能同时存在
+    Incompatible,
+    /// 不推荐同时使用
+    Discouraged,
+}
+
+/// 模组声明的依赖
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModDependency {
+    /// 依赖的模组 ID
+    pub id: String,
+    pub kind: DependencyKind,
+    /// 版本要求，满足其中任意一个即可，为空表示任意版本
+    pub versions: Vec<String>,
+}
+
+/// 从模组元数据文件中解析出的信息
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModMetadata {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    /// 图标在 jar 中的路径
+    pub icon: Option<String>,
+    /// 元数据文件声明的加载器
+    pub loader: ModLoaderType,
+    pub dependencies: Vec<ModDependency>,
+    /// 额外提供的模组 ID
+    pub provides: Vec<String>,
+}
+
+/// 模组目录中的一个模组文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModFile {
+    /// 文件名，禁用的模组包含 `.disabled` 后缀
+    pub file_name: String,
+    pub path: PathBuf,
+    pub enabled: bool,
+    pub size: u64,
+    /// 文件中声明的模组，一个 jar 可能同时包含多个加载器的元数据
+    pub mods: Vec<ModMetadata>,
+    /// 内嵌在 jar 中的模组（Jar-in-Jar）
+    pub bundled: Vec<ModMetadata>,
+    /// 解析失败的原因
+    pub error: Option<String>,
+}
+
+/// 字符串或字符串数组
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            StringOrList::One(s) => vec![s],
+            StringOrList::Many(list) => list,
+        }
+    }
+}
+
+/// `fabric.mod.json` 中的作者，可以是名字或包含名字的对象
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum FabricPerson {
+    Name(String),
+    Object { name: String },
+}
+
+/// `fabric.mod.json` 中的图标，可以是单个路径或尺寸到路径的映射
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ModIcon {
+    Path(String),
+    Sizes(HashMap<String, String>),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FabricNestedJar {
+    pub file: String,
+}
+
+/// `fabric.mod.json`
+#[derive(Deserialize, Debug, Clone)]
+pub struct FabricModJson {
+    pub id: String,
+    pub version: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<FabricPerson>,
+    pub icon: Option<ModIcon>,
+    #[serde(default)]
+    pub depends: HashMap<String, StringOrList>,
+    #[serde(default)]
+    pub recommends: HashMap<String, StringOrList>,
+    #[serde(default)]
+    pub suggests: HashMap<String, StringOrList>,
+    #[serde(default)]
+    pub breaks: HashMap<String, StringOrList>,
+    #[serde(default)]
+    pub conflicts: HashMap<String, StringOrList>,
+    #[serde(default)]
+    pub provides: Vec<String>,
+    #[serde(default)]
+    pub jars: Vec<FabricNestedJar>,
+}
+
+/// `quilt.mod.json` 中的依赖，可以只写模组 ID
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum QuiltDependency {
+    Id(String),
+    Object {
+        id: String,
+        versions: Option<serde_json::Value>,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+/// `quilt.mod.json` 中的 provides 条目
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum QuiltProvides {
+    Id(String),
+    Object { id: String },
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct QuiltMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 贡献者名字到角色的映射
+    #[serde(default)]
+    pub contributors: HashMap<String, serde_json::Value>,
+    pub icon: Option<ModIcon>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QuiltLoader {
+    pub id: String,
+    pub version: String,
+    #[serde(default)]
+    pub metadata: QuiltMetadata,
+    #[serde(default)]
+    pub depends: Vec<QuiltDependency>,
+    #[serde(default)]
+    pub breaks: Vec<QuiltDependency>,
+    #[serde(default)]
+    pub provides: Vec<QuiltProvides>,
+    #[serde(default)]
+    pub jars: Vec<String>,
+}
+
+/// `quilt.mod.json`
+#[derive(Deserialize, Debug, Clone)]
+pub struct QuiltModJson {
+    pub quilt_loader: QuiltLoader,
+}
+
+/// `mods.toml` 中的 `[[mods]]` 条目
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeModEntry {
+    pub mod_id: String,
+    pub version: Option<String>,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub authors: Option<StringOrList>,
+    pub logo_file: Option<String>,
+}
+
+/// `mods.toml` 中的依赖
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeDependency {
+    pub mod_id: String,
+    /// Forge 使用的必需标记
+    pub mandatory: Option<bool>,
+    /// NeoForge 使用的依赖类型：required、optional、incompatible、discouraged
+    #[serde(rename = "type")]
+    pub dependency_type: Option<String>,
+    pub version_range: Option<String>,
+    /// 依赖生效的一端：BOTH、CLIENT 或 SERVER
+    pub side: Option<String>,
+}
+
+/// `META-INF/mods.toml` 与 `META-INF/neoforge.mods.toml`
+#[derive(Deserialize, Debug, Clone)]
+pub struct ForgeModsToml {
+    #[serde(default)]
+    pub mods: Vec<ForgeModEntry>,
+    /// 模组 ID 到其依赖列表的映射
+    #[serde(default)]
+    pub dependencies: HashMap<String, Vec<ForgeDependency>>,
+}
+
+/// `mcmod.info` 中的模组条目
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct McModInfoEntry {
+    pub modid: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    #[serde(default, alias = "authors")]
+    pub author_list: Vec<String>,
+    pub logo_file: Option<String>,
+    /// 形如 `modid@[1.0,)` 的必需依赖
+    #[serde(default)]
+    pub required_mods: Vec<String>,
+}
+
+/// `mcmod.info`，旧版本为数组，新版本为带 `modList` 的对象
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum McModInfo {
+    List(Vec<McModInfoEntry>),
+    Versioned {
+        #[serde(rename = "modList")]
+        mod_list: Vec<McModInfoEntry>,
+    },
+}
+
+/// Forge 的 `META-INF/jarjar/metadata.json` 中的条目
+#[derive(Deserialize, Debug, Clone)]
+pub struct JarJarEntry {
+    pub path: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct JarJarMetadata {
+    #[serde(default)]
+    pub jars: Vec<JarJarEntry>,
+}
+
+/// 检查模组时使用的游戏环境
+#[derive(Debug, Clone, Default)]
+pub struct ModEnvironment {
+    /// 游戏本体版本号
+    pub minecraft: Option<String>,
+    pub loaders: Vec<ModLoaderInfo>,
+    /// Java 主版本号
+    pub java: Option<String>,
+}
+
+/// 模组问题的类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModIssueKind {
+    /// 缺少前置模组
+    MissingDependency,
+    /// 前置模组版本不满足要求
+    VersionMismatch,
+    /// 与已安装的模组不兼容
+    Incompatible,
+    /// 不推荐与已安装的模组同时使用
+    Discouraged,
+    /// 同一模组安装了多个
+    Duplicate,
+    /// 模组不适用于当前的加载器
+    WrongLoader,
+}
+
+/// 检查模组时发现的问题
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModIssue {
+    pub kind: ModIssueKind,
+    /// 出现问题的模组文件名
+    pub file_name: String,
+    pub mod_id: String,
+    pub mod_name: String,
+    /// 相关的模组 ID
+    pub dependency: Option<String>,
+    /// 版本要求
+    pub required: Vec<String>,
+    /// 实际找到的版本或文件
+    pub found: Option<String>,
+    /// 是否会导致游戏无法启动
+    pub fatal: bool,
+    pub message: String,
+}
+
+/// 模组检查结果
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModCheckReport {
+    /// 检查时使用的加载器，未安装加载器时为空且不做检查
+    pub loader: Option<ModLoaderType>,
+    pub issues: Vec<ModIssue>,
+}
+
+impl ModCheckReport {
+    /// 会导致游戏无法启动的问题
+    pub fn fatal_issues(&self) -> impl Iterator<Item = &ModIssue> {
+        self.issues.iter().filter(|issue| issue.fatal)
+    }
+}
@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+
+use super::model::{
+    DependencyKind, ModCheckReport, ModDependency, ModEnvironment, ModFile, ModIssue, ModIssueKind,
+    ModMetadata,
+};
+use super::version::{matches_fabric_versions, matches_maven_range};
+use crate::game::version::model::ModLoaderType;
+
+/// 已安装的模组或游戏环境提供的模组 ID
+struct Provider<'a> {
+    /// 版本未知时视为满足任意要求
+    version: Option<&'a str>,
+    file_name: &'a str,
+}
+
+fn provide<'a>(
+    providers: &mut HashMap<String, Vec<Provider<'a>>>,
+    id: &str,
+    version: Option<&'a str>,
+    file_name: &'a str,
+) {
+    providers
+        .entry(id.to_lowercase())
+        .or_default()
+        .push(Provider { version, file_name });
+}
+
+/// 环境中实际生效的加载器，以及该加载器能读取的元数据（按优先级排列）
+fn active_loader(
+    environment: &ModEnvironment,
+) -> Option<(ModLoaderType, &'static [ModLoaderType])> {
+    environment
+        .loaders
+        .iter()
+        .find_map(|info| match info.loader {
+            ModLoaderType::Fabric => Some((info.loader, &[ModLoaderType::Fabric][..])),
+            ModLoaderType::Quilt => Some((
+                info.loader,
+                &[ModLoaderType::Quilt, ModLoaderType::Fabric][..],
+            )),
+            ModLoaderType::Forge => Some((info.loader, &[ModLoaderType::Forge][..])),
+            ModLoaderType::NeoForge => Some((
+                info.loader,
+                &[ModLoaderType::NeoForge, ModLoaderType::Forge][..],
+            )),
+            _ => None,
+        })
+}
+
+/// 加载器自身提供的模组 ID
+fn builtin_ids(loader: ModLoaderType) -> &'static [&'static str] {
+    match loader {
+        ModLoaderType::Fabric => &["fabricloader"],
+        ModLoaderType::Quilt => &["quilt_loader"],
+        ModLoaderType::Forge => &["forge", "fml"],
+        ModLoaderType::NeoForge => &["neoforge", "forge", "fml"],
+        _ => &[],
+    }
+}
+
+/// 选出文件中当前加载器会读取的元数据
+fn select_metadata<'a>(file: &'a ModFile, accepted: &[ModLoaderType]) -> Vec<&'a ModMetadata> {
+    accepted
+        .iter()
+        .map(|loader| {
+            file.mods
+                .iter()
+                .filter(|m| m.loader == *loader)
+                .collect::<Vec<_>>()
+        })
+        .find(|mods| !mods.is_empty())
+        .unwrap_or_default()
+}
+
+fn matches_versions(loader: ModLoaderType, version: &str, versions: &[String]) -> bool {
+    match loader {
+        ModLoaderType::Fabric | ModLoaderType::Quilt => matches_fabric_versions(version, versions),
+        _ => versions
+            .iter()
+            .all(|range| matches_maven_range(version, range)),
+    }
+}
+
+fn describe_requirement(dependency: &ModDependency) -> String {
+    if dependency.versions.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", dependency.versions.join(" 或 "))
+    }
+}
+
+/// 检查单个依赖，返回问题的类型、是否致命与描述
+fn check_dependency(
+    metadata: &ModMetadata,
+    dependency: &ModDependency,
+    providers: &[Provider],
+) -> Option<(ModIssueKind, bool, Option<String>, String)> {
+    let matched = providers.iter().find(|p| {
+        p.version
+            .is_none_or(|v| matches_versions(metadata.loader, v, &dependency.versions))
+    });
+    let found = providers
+        .first()
+        .map(|p| p.version.unwrap_or(p.file_name).to_string());
+    let name = &metadata.name;
+    let requirement = describe_requirement(dependency);
+    match dependency.kind {
+        DependencyKind::Required if providers.is_empty() => Some((
+            ModIssueKind::MissingDependency,
+            true,
+            None,
+            format!(
+                "{} 需要前置模组 {}{}，但未安装",
+                name, dependency.id, requirement
+            ),
+        )),
+        DependencyKind::Required | DependencyKind::Optional if matched.is_none() => {
+            let found = found?;
+            // Fabric 的 recommends/suggests 版本不符时只会警告，Forge 的可选依赖则会阻止启动
+            let fatal = dependency.kind == DependencyKind::Required
+                || matches!(
+                    metadata.loader,
+                    ModLoaderType::Forge | ModLoaderType::NeoForge
+                );
+            Some((
+                ModIssueKind::VersionMismatch,
+                fatal,
+                Some(found.clone()),
+                format!(
+                    "{} 需要 {}{}，当前为 {}",
+                    name, dependency.id, requirement, found
+                ),
+            ))
+        }
+        DependencyKind::Incompatible => matched.map(|p| {
+            let found = p.version.unwrap_or(p.file_name).to_string();
+            (
+                ModIssueKind::Incompatible,
+                true,
+                Some(found.clone()),
+                format!("{} 与 {} {} 不兼容", name, dependency.id, found),
+            )
+        }),
+        DependencyKind::Discouraged => matched.map(|p| {
+            let found = p.version.unwrap_or(p.file_name).to_string();
+            (
+                ModIssueKind::Discouraged,
+                false,
+                Some(found.clone()),
+                format!("{} 不推荐与 {} {} 同时使用", name, dependency.id, found),
+            )
+        }),
+        _ => None,
+    }
+}
+
+/// 在启动前检查已启用模组的前置、版本要求、冲突与重复
+///
+/// 只检查当前加载器会读取的元数据；内嵌的模组只作为提供者，不检查其自身的依赖。
+/// 未安装模组加载器时不做检查。
+pub fn check_mods(files: &[ModFile], environment: &ModEnvironment) -> ModCheckReport {
+    let Some((loader, accepted)) = active_loader(environment) else {
+        return ModCheckReport::default();
+    };
+    let loader_version = environment
+        .loaders
+        .iter()
+        .find(|l| l.loader == loader)
+        .map(|l| l.version.as_str());
+
+    let mut issues = Vec::new();
+    let mut providers: HashMap<String, Vec<Provider>> = HashMap::new();
+    let mut builtins: Vec<(&str, Option<&str>)> = Vec::new();
+    if let Some(minecraft) = environment.minecraft.as_deref() {
+        builtins.push(("minecraft", Some(minecraft)));
+    }
+    if let Some(java) = environment.java.as_deref() {
+        builtins.push(("java", Some(java)));
+    }
+    for id in builtin_ids(loader) {
+        builtins.push((id, loader_version));
+    }
+    if loader == ModLoaderType::Quilt {
+        // Quilt 以兼容的版本号提供 Fabric Loader，具体版本不可知
+        builtins.push(("fabricloader", None));
+    }
+    for (id, version) in builtins {
+        provide(&mut providers, id, version, "");
+    }
+
+    let mut selected: Vec<(&ModFile, Vec<&ModMetadata>)> = Vec::new();
+    for file in files.iter().filter(|f| f.enabled && f.error.is_none()) {
+        let mods = select_metadata(file, accepted);
+        if mods.is_empty() {
+            if let Some(other) = file.mods.first() {
+                issues.push(ModIssue {
+                    kind: ModIssueKind::WrongLoader,
+                    file_name: file.file_name.clone(),
+                    mod_id: other.id.clone(),
+                    mod_name: other.name.clone(),
+                    dependency: None,
+                    required: Vec::new(),
+                    found: None,
+                    fatal: false,
+                    message: format!(
+                        "{} 是 {:?} 模组，不会被当前的 {:?} 加载",
+                        file.file_name, other.loader, loader
+                    ),
+                });
+            }
+            continue;
+        }
+        for metadata in mods.iter().copied().chain(&file.bundled) {
+            for id in std::iter::once(&metadata.id).chain(&metadata.provides) {
+                provide(&mut providers, id, Some(&metadata.version), &file.file_name);
+            }
+        }
+        selected.push((file, mods));
+    }
+
+    // 同一模组 ID 由多个文件直接声明时视为重复安装
+    let mut owners: HashMap<String, Vec<&str>> = HashMap::new();
+    for (file, mods) in &selected {
+        for metadata in mods {
+            let files = owners.entry(metadata.id.to_lowercase()).or_default();
+            if !files.contains(&file.file_name.as_str()) {
+                files.push(&file.file_name);
+            }
+        }
+    }
+    for (file, mods) in &selected {
+        for metadata in mods {
+            let files = &owners[&metadata.id.to_lowercase()];
+            if files.len() > 1 && files[0] == file.file_name {
+                let others = files[1..].join("、");
+                issues.push(ModIssue {
+                    kind: ModIssueKind::Duplicate,
+                    file_name: file.file_name.clone(),
+                    mod_id: metadata.id.clone(),
+                    mod_name: metadata.name.clone(),
+                    dependency: None,
+                    required: Vec::new(),
+                    message: format!(
+                        "模组 {} 重复安装: {}、{}",
+                        metadata.name, file.file_name, others
+                    ),
+                    found: Some(others),
+                    fatal: true,
+                });
+            }
+        }
+    }
+
+    for (file, mods) in &selected {
+        for metadata in mods {
+            for dependency in &metadata.dependencies {
+                let id = dependency.id.to_lowercase();
+                if id == metadata.id.to_lowercase() {
+                    continue;
+                }
+                let candidates = providers.get(&id).map(Vec::as_slice).unwrap_or_default();
+                if let Some((kind, fatal, found, message)) =
+                    check_dependency(metadata, dependency, candidates)
+                {
+                    issues.push(ModIssue {
+                        kind,
+                        file_name: file.file_name.clone(),
+                        mod_id: metadata.id.clone(),
+                        mod_name: metadata.name.clone(),
+                        dependency: Some(dependency.id.clone()),
+                        required: dependency.versions.clone(),
+                        found,
+                        fatal,
+                        message,
+                    });
+                }
+            }
+        }
+    }
+
+    ModCheckReport {
+        loader: Some(loader),
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::version::model::ModLoaderInfo;
+
+    fn metadata(id: &str, version: &str, loader: ModLoaderType) -> ModMetadata {
+        ModMetadata {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: version.to_string(),
+            description: None,
+            authors: Vec::new(),
+            icon: None,
+            loader,
+            dependencies: Vec::new(),
+            provides: Vec::new(),
+        }
+    }
+
+    fn dependency(id: &str, kind: DependencyKind, versions: &[&str]) -> ModDependency {
+        ModDependency {
+            id: id.to_string(),
+            kind,
+            versions: versions.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    fn file(name: &str, mods: Vec<ModMetadata>) -> ModFile {
+        ModFile {
+            file_name: name.to_string(),
+            path: name.into(),
+            enabled: true,
+            size: 0,
+            mods,
+            bundled: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn environment(loader: ModLoaderType, version: &str) -> ModEnvironment {
+        ModEnvironment {
+            minecraft: Some("1.20.1".to_string()),
+            loaders: vec![ModLoaderInfo {
+                loader,
+                version: version.to_string(),
+            }],
+            java: Some("17".to_string()),
+        }
+    }
+
+    fn kinds(report: &ModCheckReport) -> Vec<(ModIssueKind, &str, bool)> {
+        report
+            .issues
+            .iter()
+            .map(|i| {
+                (
+                    i.kind,
+                    i.dependency.as_deref().unwrap_or(&i.mod_id),
+                    i.fatal,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check_fabric_mods() {
+        let mut sodium = metadata("sodium", "0.5.8+mc1.20.1", ModLoaderType::Fabric);
+        sodium.dependencies = vec![
+            dependency("minecraft", DependencyKind::Required, &["1.20", "1.20.1"]),
+            dependency("fabricloader", DependencyKind::Required, &[">=0.14.0"]),
+            dependency("java", DependencyKind::Required, &[">=17"]),
+            dependency("optifabric", DependencyKind::Incompatible, &["*"]),
+        ];
+        let mut iris = metadata("iris", "1.6.11", ModLoaderType::Fabric);
+        iris.dependencies = vec![
+            dependency("sodium", DependencyKind::Required, &["0.4.x"]),
+            dependency("fabric-api-base", DependencyKind::Required, &[]),
+            dependency("modmenu", DependencyKind::Optional, &[">=7"]),
+            dependency("indium", DependencyKind::Required, &[]),
+        ];
+        let mut api = file(
+            "fabric-api.jar",
+            vec![metadata("fabric-api", "0.92.0", ModLoaderType::Fabric)],
+        );
+        api.bundled = vec![metadata("fabric-api-base", "0.4.31", ModLoaderType::Fabric)];
+        let files = vec![
+            file("sodium.jar", vec![sodium]),
+            file("iris.jar", vec![iris]),
+            api,
+            file(
+                "optifabric.jar",
+                vec![metadata("optifabric", "1.14.3", ModLoaderType::Fabric)],
+            ),
+            file(
+                "create.jar",
+                vec![metadata("create", "0.5.1", ModLoaderType::Forge)],
+            ),
+            file(
+                "sodium-copy.jar",
+                vec![metadata("sodium", "0.5.3", ModLoaderType::Fabric)],
+            ),
+        ];
+
+        let report = check_mods(&files, &environment(ModLoaderType::Fabric, "0.15.7"));
+        assert_eq!(report.loader, Some(ModLoaderType::Fabric));
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ModIssueKind::WrongLoader, "create", false),
+                (ModIssueKind::Duplicate, "sodium", true),
+                (ModIssueKind::Incompatible, "optifabric", true),
+                (ModIssueKind::VersionMismatch, "sodium", true),
+                (ModIssueKind::MissingDependency, "indium", true),
+            ]
+        );
+        assert_eq!(report.fatal_issues().count(), 4);
+    }
+
+    #[test]
+    fn test_check_forge_mods() {
+        let mut create = metadata("create", "0.5.1.f", ModLoaderType::Forge);
+        create.dependencies = vec![
+            dependency("forge", DependencyKind::Required, &["[47.1.3,)"]),
+            dependency("minecraft", DependencyKind::Required, &["[1.20.1,1.21)"]),
+            dependency("flywheel", DependencyKind::Optional, &["[0.6.10,0.6.11)"]),
+        ];
+        let mut jei = metadata("jei", "15.2.0.27", ModLoaderType::Forge);
+        jei.dependencies = vec![dependency("forge", DependencyKind::Required, &["[48,)"])];
+        let mut disabled = file(
+            "disabled.jar",
+            vec![metadata("flywheel", "0.6.10", ModLoaderType::Forge)],
+        );
+        disabled.enabled = false;
+        let files = vec![
+            file("create.jar", vec![create]),
+            file("jei.jar", vec![jei]),
+            file(
+                "flywheel.jar",
+                vec![metadata("flywheel", "0.6.9", ModLoaderType::Forge)],
+            ),
+            disabled,
+        ];
+
+        let report = check_mods(&files, &environment(ModLoaderType::Forge, "47.2.0"));
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (ModIssueKind::VersionMismatch, "flywheel", true),
+                (ModIssueKind::VersionMismatch, "forge", true),
+            ]
+        );
+        assert_eq!(report.issues[1].found.as_deref(), Some("47.2.0"));
+
+        // 未安装加载器时不检查
+        let vanilla = ModEnvironment::default();
+        assert!(check_mods(&files, &vanilla).issues.is_empty());
+    }
+}
@@ -0,0 +1,640 @@
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use zip::ZipArchive;
+
+use super::model::{
+    DependencyKind, FabricModJson, FabricPerson, ForgeModsToml, JarJarMetadata, McModInfo,
+    ModDependency, ModFile, ModIcon, ModMetadata, QuiltDependency, QuiltModJson, QuiltProvides,
+    StringOrList,
+};
+use crate::game::version::model::ModLoaderType;
+
+/// 禁用的模组在文件名后追加的后缀
+pub const DISABLED_SUFFIX: &str = ".disabled";
+/// 解析内嵌 jar 的最大层数
+const MAX_NESTED_DEPTH: usize = 3;
+/// 读取 jar 条目时预分配的最大容量，避免被伪造的条目大小撑爆内存
+const MAX_PREALLOC_SIZE: u64 = 1024 * 1024;
+
+/// 从 jar 中读取的模组信息：声明的模组与内嵌的模组
+type ArchiveMods = (Vec<ModMetadata>, Vec<ModMetadata>);
+
+/// 是否为模组文件（`.jar` 或 `.jar.disabled`），返回是否启用
+pub fn mod_file_state(path: &Path) -> Option<bool> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name.ends_with(".jar") {
+        Some(true)
+    } else if name.ends_with(".jar.disabled") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = Vec::with_capacity(entry.size().min(MAX_PREALLOC_SIZE) as usize);
+    entry.read_to_end(&mut content).ok()?;
+    Some(content)
+}
+
+/// 解析模组元数据中的 JSON
+///
+/// 不少模组的元数据在字符串中直接包含换行或制表符，严格解析失败时将其替换为空格后重试。
+pub fn parse_lenient_json<T: DeserializeOwned>(content: &[u8]) -> Result<T, String> {
+    let text = String::from_utf8_lossy(content);
+    let text = text.trim_start_matches('\u{feff}');
+    serde_json::from_str(text).or_else(|e| {
+        serde_json::from_str(&text.replace(['\n', '\r', '\t'], " ")).map_err(|_| e.to_string())
+    })
+}
+
+/// 选择尺寸最大的图标
+fn icon_path(icon: ModIcon) -> Option<String> {
+    match icon {
+        ModIcon::Path(path) => Some(path),
+        ModIcon::Sizes(sizes) => sizes
+            .into_iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .map(|(_, path)| path),
+    }
+}
+
+fn dependencies_of(
+    map: std::collections::HashMap<String, StringOrList>,
+    kind: DependencyKind,
+) -> impl Iterator<Item = ModDependency> {
+    map.into_iter().map(move |(id, versions)| ModDependency {
+        id,
+        kind,
+        versions: versions.into_vec(),
+    })
+}
+
+fn sort_dependencies(dependencies: &mut [ModDependency]) {
+    dependencies.sort_by(|a, b| (a.kind as u8, &a.id).cmp(&(b.kind as u8, &b.id)));
+}
+
+/// 解析 `fabric.mod.json`，同时返回内嵌 jar 的路径
+pub fn parse_fabric_mod(content: &[u8]) -> Result<(ModMetadata, Vec<String>), String> {
+    let json: FabricModJson = parse_lenient_json(content)?;
+    let mut dependencies: Vec<ModDependency> =
+        dependencies_of(json.depends, DependencyKind::Required)
+            .chain(dependencies_of(json.recommends, DependencyKind::Optional))
+            .chain(dependencies_of(json.suggests, DependencyKind::Optional))
+            .chain(dependencies_of(json.breaks, DependencyKind::Incompatible))
+            .chain(dependencies_of(json.conflicts, DependencyKind::Discouraged))
+            .collect();
+    sort_dependencies(&mut dependencies);
+    let metadata = ModMetadata {
+        name: json.name.unwrap_or_else(|| json.id.clone()),
+        id: json.id,
+        version: json.version,
+        description: json.description,
+        authors: json
+            .authors
+            .into_iter()
+            .map(|author| match author {
+                FabricPerson::Name(name) | FabricPerson::Object { name } => name,
+            })
+            .collect(),
+        icon: json.icon.and_then(icon_path),
+        loader: ModLoaderType::Fabric,
+        dependencies,
+        provides: json.provides,
+    };
+    let jars = json.jars.into_iter().map(|jar| jar.file).collect();
+    Ok((metadata, jars))
+}
+
+/// Quilt 的版本要求可以是字符串、数组或 `any`/`all` 对象，后者不做解析
+fn quilt_versions(versions: Option<serde_json::Value>) -> Vec<String> {
+    match versions {
+        Some(serde_json::Value::String(version)) => vec![version],
+        Some(serde_json::Value::Array(list)) => list
+            .into_iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn quilt_dependency(dependency: QuiltDependency, kind: DependencyKind) -> ModDependency {
+    match dependency {
+        QuiltDependency::Id(id) => ModDependency {
+            id,
+            kind,
+            versions: Vec::new(),
+        },
+        QuiltDependency::Object {
+            id,
+            versions,
+            optional,
+        } => ModDependency {
+            id,
+            kind: if optional && kind == DependencyKind::Required {
+                DependencyKind::Optional
+            } else {
+                kind
+            },
+            versions: quilt_versions(versions),
+        },
+    }
+}
+
+/// 解析 `quilt.mod.json`，同时返回内嵌 jar 的路径
+pub fn parse_quilt_mod(content: &[u8]) -> Result<(ModMetadata, Vec<String>), String> {
+    let loader = parse_lenient_json::<QuiltModJson>(content)?.quilt_loader;
+    let mut dependencies: Vec<ModDependency> = loader
+        .depends
+        .into_iter()
+        .map(|d| quilt_dependency(d, DependencyKind::Required))
+        .chain(
+            loader
+                .breaks
+                .into_iter()
+                .map(|d| quilt_dependency(d, DependencyKind::Incompatible)),
+        )
+        .collect();
+    sort_dependencies(&mut dependencies);
+    let mut authors: Vec<String> = loader.metadata.contributors.into_keys().collect();
+    authors.sort();
+    let metadata = ModMetadata {
+        name: loader.metadata.name.unwrap_or_else(|| loader.id.clone()),
+        id: loader.id,
+        version: loader.version,
+        description: loader.metadata.description,
+        authors,
+        icon: loader.metadata.icon.and_then(icon_path),
+        loader: ModLoaderType::Quilt,
+        dependencies,
+        provides: loader
+            .provides
+            .into_iter()
+            .map(|p| match p {
+                QuiltProvides::Id(id) | QuiltProvides::Object { id } => id,
+            })
+            .collect(),
+    };
+    Ok((metadata, loader.jars))
+}
+
+fn forge_dependency_kind(dependency_type: Option<&str>, mandatory: Option<bool>) -> DependencyKind {
+    match dependency_type.map(str::to_ascii_lowercase).as_deref() {
+        Some("optional") => DependencyKind::Optional,
+        Some("incompatible") => DependencyKind::Incompatible,
+        Some("discouraged") => DependencyKind::Discouraged,
+        Some(_) => DependencyKind::Required,
+        None if mandatory == Some(false) => DependencyKind::Optional,
+        None => DependencyKind::Required,
+    }
+}
+
+/// 解析 `mods.toml` 或 `neoforge.mods.toml`
+///
+/// `jar_version` 为 `MANIFEST.MF` 中的 `Implementation-Version`，用于替换 `${file.jarVersion}`。
+/// `mods.toml` 同时被 Forge 与早期 NeoForge 使用，依赖中包含 `neoforge` 时视为 NeoForge。
+pub fn parse_forge_mods_toml(
+    content: &[u8],
+    neoforge: bool,
+    jar_version: Option<&str>,
+) -> Result<Vec<ModMetadata>, String> {
+    let text = String::from_utf8_lossy(content);
+    let mut toml: ForgeModsToml = toml::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("解析 mods.toml 失败: {}", e))?;
+    let neoforge = neoforge
+        || toml
+            .dependencies
+            .values()
+            .flatten()
+            .any(|d| d.mod_id == "neoforge");
+    let mods = toml
+        .mods
+        .into_iter()
+        .map(|entry| {
+            let mut dependencies: Vec<ModDependency> = toml
+                .dependencies
+                .remove(&entry.mod_id)
+                .unwrap_or_default()
+                .into_iter()
+                // 只在服务端生效的依赖与客户端无关
+                .filter(|d| {
+                    !d.side
+                        .as_deref()
+                        .is_some_and(|s| s.eq_ignore_ascii_case("server"))
+                })
+                .map(|d| ModDependency {
+                    kind: forge_dependency_kind(d.dependency_type.as_deref(), d.mandatory),
+                    id: d.mod_id,
+                    versions: d.version_range.into_iter().collect(),
+                })
+                .collect();
+            sort_dependencies(&mut dependencies);
+            let version = match entry.version {
+                Some(v) if v.contains("${file.jarVersion}") => {
+                    v.replace("${file.jarVersion}", jar_version.unwrap_or("?"))
+                }
+                Some(v) => v,
+                None => jar_version.unwrap_or("?").to_string(),
+            };
+            ModMetadata {
+                name: entry.display_name.unwrap_or_else(|| entry.mod_id.clone()),
+                id: entry.mod_id,
+                version,
+                description: entry.description.map(|d| d.trim().to_string()),
+                authors: entry
+                    .authors
+                    .map(|a| {
+                        a.into_vec()
+                            .iter()
+                            .flat_map(|s| s.split(','))
+                            .map(|s| s.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                icon: entry.logo_file,
+                loader: if neoforge {
+                    ModLoaderType::NeoForge
+                } else {
+                    ModLoaderType::Forge
+                },
+                dependencies,
+                provides: Vec::new(),
+            }
+        })
+        .collect();
+    Ok(mods)
+}
+
+/// 解析旧版 Forge 的 `mcmod.info`
+pub fn parse_mcmod_info(content: &[u8]) -> Result<Vec<ModMetadata>, String> {
+    let entries = match parse_lenient_json::<McModInfo>(content)? {
+        McModInfo::List(list) | McModInfo::Versioned { mod_list: list } => list,
+    };
+    Ok(entries
+        .into_iter()
+        .map(|entry| ModMetadata {
+            name: entry.name.unwrap_or_else(|| entry.modid.clone()),
+            id: entry.modid,
+            version: entry.version.unwrap_or_else(|| "?".to_string()),
+            description: entry.description,
+            authors: entry.author_list,
+            icon: entry.logo_file.filter(|f| !f.is_empty()),
+            loader: ModLoaderType::Forge,
+            dependencies: entry
+                .required_mods
+                .into_iter()
+                .map(|required| {
+                    let (id, range) = match required.split_once('@') {
+                        Some((id, range)) => (id.to_string(), vec![range.to_string()]),
+                        None => (required, Vec::new()),
+                    };
+                    ModDependency {
+                        id,
+                        kind: DependencyKind::Required,
+                        versions: range,
+                    }
+                })
+                .collect(),
+            provides: Vec::new(),
+        })
+        .collect())
+}
+
+/// 读取 `META-INF/MANIFEST.MF` 中的 `Implementation-Version`
+fn read_jar_version<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+    let manifest = read_entry(archive, "META-INF/MANIFEST.MF")?;
+    String::from_utf8_lossy(&manifest).lines().find_map(|line| {
+        line.strip_prefix("Implementation-Version:")
+            .map(|v| v.trim().to_string())
+    })
+}
+
+/// 读取 jar 中所有元数据文件声明的模组，并递归读取内嵌的 jar
+fn read_archive_mods<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    depth: usize,
+) -> Result<ArchiveMods, String> {
+    let mut mods = Vec::new();
+    let mut nested = Vec::new();
+    if let Some(content) = read_entry(archive, "quilt.mod.json") {
+        let (metadata, jars) =
+            parse_quilt_mod(&content).map_err(|e| format!("解析 quilt.mod.json 失败: {}", e))?;
+        mods.push(metadata);
+        nested.extend(jars);
+    }
+    if let Some(content) = read_entry(archive, "fabric.mod.json") {
+        let (metadata, jars) =
+            parse_fabric_mod(&content).map_err(|e| format!("解析 fabric.mod.json 失败: {}", e))?;
+        mods.push(metadata);
+        nested.extend(jars);
+    }
+    let jar_version = read_jar_version(archive);
+    if let Some(content) = read_entry(archive, "META-INF/neoforge.mods.toml") {
+        mods.extend(parse_forge_mods_toml(
+            &content,
+            true,
+            jar_version.as_deref(),
+        )?);
+    } else if let Some(content) = read_entry(archive, "META-INF/mods.toml") {
+        mods.extend(parse_forge_mods_toml(
+            &content,
+            false,
+            jar_version.as_deref(),
+        )?);
+    }
+    if mods.is_empty() {
+        if let Some(content) = read_entry(archive, "mcmod.info") {
+            mods.extend(
+                parse_mcmod_info(&content).map_err(|e| format!("解析 mcmod.info 失败: {}", e))?,
+            );
+        }
+    }
+    if let Some(content) = read_entry(archive, "META-INF/jarjar/metadata.json") {
+        if let Ok(metadata) = parse_lenient_json::<JarJarMetadata>(&content) {
+            nested.extend(metadata.jars.into_iter().map(|jar| jar.path));
+        }
+    }
+
+    let mut bundled = Vec::new();
+    if depth < MAX_NESTED_DEPTH {
+        nested.sort();
+        nested.dedup();
+        for path in nested {
+            let Some(content) = read_entry(archive, &path) else {
+                continue;
+            };
+            // 内嵌 jar 解析失败不影响外层模组
+            let Ok(mut inner) = ZipArchive::new(Cursor::new(content)) else {
+                continue;
+            };
+            if let Ok((inner_mods, inner_bundled)) = read_archive_mods(&mut inner, depth + 1) {
+                bundled.extend(inner_mods);
+                bundled.extend(inner_bundled);
+            }
+        }
+    }
+    Ok((mods, bundled))
+}
+
+/// 读取单个模组文件，解析失败时记录在 `error` 中
+pub fn read_mod_file(path: &Path) -> ModFile {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut mod_file = ModFile {
+        enabled: mod_file_state(path).unwrap_or(false),
+        size: path.metadata().map(|m| m.len()).unwrap_or(0),
+        file_name,
+        path: path.to_path_buf(),
+        mods: Vec::new(),
+        bundled: Vec::new(),
+        error: None,
+    };
+    let result = File::open(path)
+        .map_err(|e| format!("打开文件失败: {}", e))
+        .and_then(|file| ZipArchive::new(file).map_err(|e| format!("无法读取 jar: {}", e)))
+        .and_then(|mut archive| read_archive_mods(&mut archive, 0));
+    match result {
+        Ok((mods, bundled)) => {
+            mod_file.mods = mods;
+            mod_file.bundled = bundled;
+        }
+        Err(e) => mod_file.error = Some(e),
+    }
+    mod_file
+}
+
+/// 扫描模组目录，包括已禁用的模组，按文件名排序
+///
+/// 多个 jar 在多个线程中并行解析。目录不存在时返回空列表。
+pub fn scan_mods(mods_dir: &Path) -> Result<Vec<ModFile>, String> {
+    if !mods_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(mods_dir)
+        .map_err(|e| format!("读取模组目录失败: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && mod_file_state(path).is_some())
+        .collect();
+    paths.sort_by_key(|path| path.file_name().map(|n| n.to_string_lossy().to_lowercase()));
+
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8);
+    let chunk_size = paths.len().div_ceil(workers).max(1);
+    let mods = thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(|p| read_mod_file(p)).collect()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|_| Vec::new()))
+            .collect()
+    });
+    Ok(mods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn build_jar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_parse_fabric_mod() {
+        // 字符串中包含原始换行
+        let content = br#"{
+            "schemaVersion": 1,
+            "id": "sodium",
+            "version": "0.5.8+mc1.20.1",
+            "name": "Sodium",
+            "description": "A modern rendering engine
+for Minecraft",
+            "authors": ["JellySquid", { "name": "IMS" }],
+            "icon": { "16": "icon16.png", "128": "icon128.png" },
+            "depends": { "minecraft": ["1.20", "1.20.1"], "fabricloader": ">=0.12.0" },
+            "breaks": { "optifabric": "*" },
+            "jars": [{ "file": "META-INF/jars/api.jar" }]
+        }"#;
+        let (metadata, jars) = parse_fabric_mod(content).unwrap();
+        assert_eq!(metadata.id, "sodium");
+        assert_eq!(metadata.name, "Sodium");
+        assert_eq!(metadata.authors, vec!["JellySquid", "IMS"]);
+        assert_eq!(metadata.icon.as_deref(), Some("icon128.png"));
+        assert_eq!(jars, vec!["META-INF/jars/api.jar"]);
+        assert_eq!(
+            metadata.dependencies[1],
+            ModDependency {
+                id: "minecraft".to_string(),
+                kind: DependencyKind::Required,
+                versions: vec!["1.20".to_string(), "1.20.1".to_string()],
+            }
+        );
+        assert_eq!(metadata.dependencies[2].kind, DependencyKind::Incompatible);
+    }
+
+    #[test]
+    fn test_parse_quilt_mod() {
+        let content = br#"{
+            "schema_version": 1,
+            "quilt_loader": {
+                "id": "qsl",
+                "version": "6.1.2",
+                "metadata": { "name": "QSL", "contributors": { "Ennui": "Owner" }, "icon": "icon.png" },
+                "depends": ["quilt_loader", { "id": "minecraft", "versions": ">=1.20" }, { "id": "modmenu", "optional": true }],
+                "provides": ["quilted_fabric_api", { "id": "fabric-api" }]
+            }
+        }"#;
+        let (metadata, _) = parse_quilt_mod(content).unwrap();
+        assert_eq!(metadata.loader, ModLoaderType::Quilt);
+        assert_eq!(metadata.authors, vec!["Ennui"]);
+        assert_eq!(metadata.provides, vec!["quilted_fabric_api", "fabric-api"]);
+        let modmenu = metadata
+            .dependencies
+            .iter()
+            .find(|d| d.id == "modmenu")
+            .unwrap();
+        assert_eq!(modmenu.kind, DependencyKind::Optional);
+    }
+
+    #[test]
+    fn test_parse_forge_mods_toml() {
+        let content = br#"
+modLoader = "javafml"
+loaderVersion = "[47,)"
+license = "MIT"
+
+[[mods]]
+modId = "create"
+version = "${file.jarVersion}"
+displayName = "Create"
+authors = "simibubi, Eriksonn"
+logoFile = "logo.png"
+description = '''
+Technology that empowers the player.
+'''
+
+[[dependencies.create]]
+modId = "forge"
+mandatory = true
+versionRange = "[47.1.3,)"
+ordering = "NONE"
+side = "BOTH"
+
+[[dependencies.create]]
+modId = "flywheel"
+mandatory = false
+versionRange = "[0.6.10,0.6.11)"
+ordering = "AFTER"
+side = "CLIENT"
+"#;
+        let mods = parse_forge_mods_toml(content, false, Some("0.5.1.f")).unwrap();
+        assert_eq!(mods.len(), 1);
+        let create = &mods[0];
+        assert_eq!(create.version, "0.5.1.f");
+        assert_eq!(create.loader, ModLoaderType::Forge);
+        assert_eq!(create.authors, vec!["simibubi", "Eriksonn"]);
+        assert_eq!(
+            create.description.as_deref(),
+            Some("Technology that empowers the player.")
+        );
+        assert_eq!(create.dependencies[0].id, "forge");
+        assert_eq!(create.dependencies[1].kind, DependencyKind::Optional);
+
+        let neoforge = br#"
+modLoader = "javafml"
+loaderVersion = "[1,)"
+[[mods]]
+modId = "example"
+version = "1.0"
+[[dependencies.example]]
+modId = "neoforge"
+type = "required"
+versionRange = "[20.4,)"
+[[dependencies.example]]
+modId = "oldmod"
+type = "incompatible"
+[[dependencies.example]]
+modId = "serveronly"
+type = "required"
+side = "SERVER"
+"#;
+        let mods = parse_forge_mods_toml(neoforge, false, None).unwrap();
+        assert_eq!(mods[0].loader, ModLoaderType::NeoForge);
+        assert_eq!(mods[0].dependencies[1].kind, DependencyKind::Incompatible);
+        assert_eq!(mods[0].dependencies.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_mcmod_info() {
+        let legacy = br#"[{ "modid": "journeymap", "name": "JourneyMap", "version": "5.4.7", "authorList": ["techbrew"], "requiredMods": ["Forge@[10.13,)"] }]"#;
+        let mods = parse_mcmod_info(legacy).unwrap();
+        assert_eq!(mods[0].id, "journeymap");
+        assert_eq!(mods[0].dependencies[0].id, "Forge");
+        assert_eq!(mods[0].dependencies[0].versions, vec!["[10.13,)"]);
+
+        let versioned =
+            br#"{ "modListVersion": 2, "modList": [{ "modid": "ic2", "authors": ["Player"] }] }"#;
+        let mods = parse_mcmod_info(versioned).unwrap();
+        assert_eq!(mods[0].authors, vec!["Player"]);
+        assert_eq!(mods[0].version, "?");
+    }
+
+    #[test]
+    fn test_scan_mods() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let nested = build_jar(&[(
+            "fabric.mod.json",
+            br#"{ "id": "fabric-api-base", "version": "0.4.31" }"#,
+        )]);
+        let outer = build_jar(&[
+            (
+                "fabric.mod.json",
+                br#"{ "id": "fabric-api", "version": "0.92.0", "jars": [{ "file": "META-INF/jars/base.jar" }] }"#,
+            ),
+            ("META-INF/jars/base.jar", &nested),
+        ]);
+        fs::write(dir.join("fabric-api.jar"), outer).unwrap();
+        fs::write(
+            dir.join("Old.jar.disabled"),
+            build_jar(&[("mcmod.info", br#"[{ "modid": "old" }]"#)]),
+        )
+        .unwrap();
+        fs::write(dir.join("broken.jar"), b"not a zip").unwrap();
+        fs::write(dir.join("readme.txt"), b"").unwrap();
+
+        let mods = scan_mods(dir).unwrap();
+        let names: Vec<_> = mods.iter().map(|m| m.file_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["broken.jar", "fabric-api.jar", "Old.jar.disabled"]
+        );
+        assert!(mods[0].error.is_some());
+        assert_eq!(mods[1].mods[0].id, "fabric-api");
+        assert_eq!(mods[1].bundled[0].id, "fabric-api-base");
+        assert!(!mods[2].enabled);
+        assert_eq!(mods[2].mods[0].id, "old");
+    }
+}
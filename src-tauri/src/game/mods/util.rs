@@ -0,0 +1,152 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use zip::ZipArchive;
+
+use super::model::ModEnvironment;
+use super::scanner::{mod_file_state, DISABLED_SUFFIX};
+use crate::game::profile::util::{get_profile, resolve_game_directory};
+use crate::game::version::util::{detect_game_version, detect_mod_loaders, ResolvedVersion};
+
+/// 模组图标的最大大小，超过时不读取
+const MAX_ICON_SIZE: u64 = 1024 * 1024;
+
+/// 配置实际使用的模组目录
+pub fn get_profile_mods_dir(game_path: &Path, profile_id: &str) -> Result<PathBuf, String> {
+    let profile = get_profile(game_path, profile_id)?;
+    Ok(resolve_game_directory(game_path, &profile).join("mods"))
+}
+
+/// 根据版本与所选 Java 生成检查模组时使用的环境
+pub fn mod_environment(resolved: &ResolvedVersion, java_major: Option<u32>) -> ModEnvironment {
+    ModEnvironment {
+        minecraft: detect_game_version(resolved),
+        loaders: detect_mod_loaders(&resolved.json),
+        java: java_major.map(|major| major.to_string()),
+    }
+}
+
+/// 启用或禁用模组，通过追加或去掉 `.disabled` 后缀实现，返回新的路径
+pub fn set_mod_enabled(path: &Path, enabled: bool) -> Result<PathBuf, String> {
+    let current =
+        mod_file_state(path).ok_or_else(|| format!("不是模组文件: {}", path.display()))?;
+    if current == enabled {
+        return Ok(path.to_path_buf());
+    }
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("无效的文件名: {}", path.display()))?;
+    let new_name = if enabled {
+        file_name[..file_name.len() - DISABLED_SUFFIX.len()].to_string()
+    } else {
+        format!("{}{}", file_name, DISABLED_SUFFIX)
+    };
+    let target = path.with_file_name(new_name);
+    if target.exists() {
+        return Err(format!("目标文件已存在: {}", target.display()));
+    }
+    fs::rename(path, &target).map_err(|e| format!("重命名模组失败 {}: {}", path.display(), e))?;
+    Ok(target)
+}
+
+/// 批量启用或禁用模组，任一失败时撤销已完成的重命名，返回重命名后的路径
+pub fn set_mods_enabled(paths: &[PathBuf], enabled: bool) -> Result<Vec<PathBuf>, String> {
+    let mut renamed: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(paths.len());
+    for path in paths {
+        match set_mod_enabled(path, enabled) {
+            Ok(target) => renamed.push((path.clone(), target)),
+            Err(e) => {
+                for (original, target) in renamed.iter().rev() {
+                    if original != target {
+                        let _ = fs::rename(target, original);
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(renamed.into_iter().map(|(_, target)| target).collect())
+}
+
+/// 读取模组 jar 中的图标，返回 data URL
+pub fn read_mod_icon(path: &Path, icon: &str) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法读取 jar: {}", e))?;
+    let Ok(mut entry) = archive.by_name(icon.trim_start_matches('/')) else {
+        return Ok(None);
+    };
+    if entry.size() > MAX_ICON_SIZE {
+        return Ok(None);
+    }
+    let mut content = Vec::with_capacity(entry.size() as usize);
+    entry
+        .read_to_end(&mut content)
+        .map_err(|e| format!("读取图标失败: {}", e))?;
+    let mime = match Path::new(icon)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        _ => "image/png",
+    };
+    Ok(Some(format!(
+        "data:{};base64,{}",
+        mime,
+        STANDARD.encode(content)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_mod_enabled() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let jar = dir.join("sodium.jar");
+        fs::write(&jar, b"").unwrap();
+
+        let disabled = set_mod_enabled(&jar, false).unwrap();
+        assert_eq!(disabled, dir.join("sodium.jar.disabled"));
+        assert!(disabled.exists() && !jar.exists());
+        assert_eq!(set_mod_enabled(&disabled, false).unwrap(), disabled);
+
+        assert_eq!(set_mod_enabled(&disabled, true).unwrap(), jar);
+        assert!(jar.exists());
+        assert!(set_mod_enabled(&dir.join("readme.txt"), true).is_err());
+    }
+
+    #[test]
+    fn test_set_mods_enabled_rolls_back() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let sodium = dir.join("sodium.jar");
+        let lithium = dir.join("lithium.jar");
+        fs::write(&sodium, b"").unwrap();
+        fs::write(&lithium, b"").unwrap();
+        // 目标文件已存在，禁用 lithium 会失败
+        fs::write(dir.join("lithium.jar.disabled"), b"").unwrap();
+
+        let result = set_mods_enabled(&[sodium.clone(), lithium.clone()], false);
+        assert!(result.is_err());
+        assert!(sodium.exists() && !dir.join("sodium.jar.disabled").exists());
+        assert!(lithium.exists());
+
+        fs::remove_file(dir.join("lithium.jar.disabled")).unwrap();
+        let renamed = set_mods_enabled(&[sodium, lithium], false).unwrap();
+        assert_eq!(
+            renamed,
+            vec![
+                dir.join("sodium.jar.disabled"),
+                dir.join("lithium.jar.disabled")
+            ]
+        );
+    }
+}
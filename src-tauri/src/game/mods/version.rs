@@ -0,0 +1,433 @@
+use std::cmp::Ordering;
+
+/// Fabric 的语义化版本，组件数量不限，末尾可以是通配符 `x`、`X` 或 `*`
+#[derive(Debug, Clone)]
+pub struct SemanticVersion {
+    pub components: Vec<u32>,
+    /// 通配符所在的组件位置
+    pub wildcard: Option<usize>,
+    pub pre: Option<String>,
+}
+
+impl SemanticVersion {
+    /// 解析版本号，忽略 `+` 之后的构建信息
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.split('+').next()?.trim();
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            Some(_) => return None,
+            None => (version, None),
+        };
+        let mut components = Vec::new();
+        let mut wildcard = None;
+        for (i, part) in core.split('.').enumerate() {
+            if wildcard.is_some() {
+                // 通配符之后只能是通配符
+                if !matches!(part, "x" | "X" | "*") {
+                    return None;
+                }
+                continue;
+            }
+            match part {
+                "x" | "X" | "*" if i > 0 && pre.is_none() => wildcard = Some(i),
+                _ => components.push(part.parse().ok()?),
+            }
+        }
+        Some(SemanticVersion {
+            components,
+            wildcard,
+            pre,
+        })
+    }
+
+    fn component(&self, i: usize) -> u32 {
+        self.components.get(i).copied().unwrap_or(0)
+    }
+}
+
+/// 比较预览版标识，按 `.` 分段，数字段按数值比较且低于非数字段
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for SemanticVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemanticVersion {}
+
+impl Ord for SemanticVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.components.len().max(other.components.len());
+        (0..len)
+            .map(|i| self.component(i).cmp(&other.component(i)))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or_else(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+impl PartialOrd for SemanticVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 判断版本是否满足单个 Fabric 版本谓词，如 `>=1.20`、`~0.14.21`、`1.20.x`、`*`
+///
+/// 多个谓词以空格分隔时需要全部满足。无法按语义化版本解析时退化为字符串比较，
+/// 对非相等比较视为满足，以免误报。
+pub fn matches_fabric_predicate(version: &str, predicate: &str) -> bool {
+    predicate
+        .split_whitespace()
+        .all(|term| matches_fabric_term(version, term))
+}
+
+fn matches_fabric_term(version: &str, term: &str) -> bool {
+    if term == "*" {
+        return true;
+    }
+    let (operator, target) = ["<=", ">=", "<", ">", "=", "~", "^"]
+        .iter()
+        .find_map(|op| term.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("=", term));
+    let (Some(current), Some(target)) = (
+        SemanticVersion::parse(version),
+        SemanticVersion::parse(target),
+    ) else {
+        return operator != "=" || version == target;
+    };
+    match operator {
+        ">=" => current >= target,
+        "<=" => current <= target,
+        ">" => current > target,
+        "<" => current < target,
+        "~" => {
+            current >= target
+                && current.component(0) == target.component(0)
+                && current.component(1) == target.component(1)
+        }
+        "^" => current >= target && current.component(0) == target.component(0),
+        _ => match target.wildcard {
+            Some(n) => (0..n).all(|i| current.component(i) == target.component(i)),
+            None => current == target,
+        },
+    }
+}
+
+/// 判断版本是否满足 Fabric 依赖声明，多个谓词中满足任意一个即可
+pub fn matches_fabric_versions(version: &str, predicates: &[String]) -> bool {
+    predicates.is_empty()
+        || predicates
+            .iter()
+            .any(|p| matches_fabric_predicate(version, p))
+}
+
+/// Maven 版本号中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MavenItem {
+    Number(u64),
+    Qualifier(String),
+}
+
+/// 已知限定符的排序，未知限定符排在最后并按字典序比较
+fn qualifier_rank(qualifier: &str) -> usize {
+    match qualifier {
+        "alpha" | "a" => 0,
+        "beta" | "b" => 1,
+        "milestone" | "m" => 2,
+        "rc" | "cr" | "pre" => 3,
+        "snapshot" => 4,
+        "" | "ga" | "final" | "release" => 5,
+        "sp" => 6,
+        _ => 7,
+    }
+}
+
+/// 与缺失的段比较：数字 0 与正式版限定符视为相等
+fn compare_with_missing(item: &MavenItem) -> Ordering {
+    match item {
+        MavenItem::Number(n) => n.cmp(&0),
+        MavenItem::Qualifier(q) => qualifier_rank(q).cmp(&5),
+    }
+}
+
+fn compare_items(a: &MavenItem, b: &MavenItem) -> Ordering {
+    match (a, b) {
+        (MavenItem::Number(x), MavenItem::Number(y)) => x.cmp(y),
+        (MavenItem::Number(_), MavenItem::Qualifier(_)) => Ordering::Greater,
+        (MavenItem::Qualifier(_), MavenItem::Number(_)) => Ordering::Less,
+        (MavenItem::Qualifier(x), MavenItem::Qualifier(y)) => {
+            qualifier_rank(x).cmp(&qualifier_rank(y)).then_with(|| {
+                if qualifier_rank(x) == 7 {
+                    x.cmp(y)
+                } else {
+                    Ordering::Equal
+                }
+            })
+        }
+    }
+}
+
+/// Maven 版本号，按 Maven `ComparableVersion` 的规则比较（简化实现）
+#[derive(Debug, Clone)]
+pub struct MavenVersion {
+    items: Vec<MavenItem>,
+}
+
+impl MavenVersion {
+    pub fn parse(version: &str) -> Self {
+        let mut items = Vec::new();
+        let mut current = String::new();
+        let flush = |current: &mut String, items: &mut Vec<MavenItem>| {
+            if !current.is_empty() {
+                items.push(match current.parse() {
+                    Ok(n) => MavenItem::Number(n),
+                    Err(_) => MavenItem::Qualifier(current.clone()),
+                });
+                current.clear();
+            }
+        };
+        for c in version.trim().to_ascii_lowercase().chars() {
+            if matches!(c, '.' | '-' | '_' | '+') {
+                flush(&mut current, &mut items);
+                continue;
+            }
+            // 数字与字母之间的切换也视为分隔
+            if current
+                .chars()
+                .last()
+                .is_some_and(|last| last.is_ascii_digit() != c.is_ascii_digit())
+            {
+                flush(&mut current, &mut items);
+            }
+            current.push(c);
+        }
+        flush(&mut current, &mut items);
+        MavenVersion { items }
+    }
+}
+
+impl PartialEq for MavenVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MavenVersion {}
+
+impl Ord for MavenVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.items.len().max(other.items.len());
+        (0..len)
+            .map(|i| match (self.items.get(i), other.items.get(i)) {
+                (Some(a), Some(b)) => compare_items(a, b),
+                (Some(a), None) => compare_with_missing(a),
+                (None, Some(b)) => compare_with_missing(b).reverse(),
+                (None, None) => Ordering::Equal,
+            })
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for MavenVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Maven 版本范围中的一个区间，`None` 表示无界
+#[derive(Debug, Clone)]
+struct Restriction {
+    lower: Option<(MavenVersion, bool)>,
+    upper: Option<(MavenVersion, bool)>,
+}
+
+impl Restriction {
+    fn contains(&self, version: &MavenVersion) -> bool {
+        let above = match &self.lower {
+            Some((lower, inclusive)) => version > lower || (*inclusive && version == lower),
+            None => true,
+        };
+        let below = match &self.upper {
+            Some((upper, inclusive)) => version < upper || (*inclusive && version == upper),
+            None => true,
+        };
+        above && below
+    }
+}
+
+/// Maven 版本范围，如 `[1.0,2.0)`、`(,1.0]`、`[1.0]`、`[1.0,2.0),[3.0,)`
+///
+/// 不带括号的版本号在 Maven 中只是推荐版本，视为任意版本。
+#[derive(Debug, Clone)]
+pub struct MavenRange {
+    /// 为空表示任意版本
+    restrictions: Vec<Restriction>,
+}
+
+impl MavenRange {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let mut restrictions = Vec::new();
+        if !spec.starts_with(['[', '(']) {
+            return Ok(MavenRange { restrictions });
+        }
+        let mut rest = spec;
+        while !rest.is_empty() {
+            let lower_inclusive = match rest.chars().next() {
+                Some('[') => true,
+                Some('(') => false,
+                _ => return Err(format!("无效的版本范围: {}", spec)),
+            };
+            let end = rest
+                .find([']', ')'])
+                .ok_or_else(|| format!("版本范围缺少结束括号: {}", spec))?;
+            let upper_inclusive = rest[end..].starts_with(']');
+            let inner = &rest[1..end];
+            let bound = |s: &str, inclusive: bool| {
+                let s = s.trim();
+                (!s.is_empty()).then(|| (MavenVersion::parse(s), inclusive))
+            };
+            let restriction = match inner.split_once(',') {
+                Some((lower, upper)) => Restriction {
+                    lower: bound(lower, lower_inclusive),
+                    upper: bound(upper, upper_inclusive),
+                },
+                None => {
+                    if !lower_inclusive || !upper_inclusive {
+                        return Err(format!("单个版本必须使用方括号: {}", spec));
+                    }
+                    Restriction {
+                        lower: bound(inner, true),
+                        upper: bound(inner, true),
+                    }
+                }
+            };
+            restrictions.push(restriction);
+            rest = rest[end + 1..].trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+        }
+        Ok(MavenRange { restrictions })
+    }
+
+    pub fn contains(&self, version: &str) -> bool {
+        let version = MavenVersion::parse(version);
+        self.restrictions.is_empty() || self.restrictions.iter().any(|r| r.contains(&version))
+    }
+}
+
+/// 判断版本是否满足 Forge 依赖声明的 Maven 版本范围，范围无法解析时视为满足
+pub fn matches_maven_range(version: &str, spec: &str) -> bool {
+    MavenRange::parse(spec).map_or(true, |range| range.contains(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_version() {
+        let parse = |s| SemanticVersion::parse(s).unwrap();
+        assert!(parse("1.20.1") > parse("1.20"));
+        assert!(parse("1.20") == parse("1.20.0"));
+        assert!(parse("0.15.0") > parse("0.14.21"));
+        assert!(parse("1.0.0-beta.2") < parse("1.0.0-beta.11"));
+        assert!(parse("1.0.0-alpha") < parse("1.0.0-alpha.1"));
+        assert!(parse("1.0.0-rc.1") < parse("1.0.0"));
+        assert_eq!(parse("0.5.8+mc1.20.1"), parse("0.5.8"));
+        assert_eq!(parse("1.20.x").wildcard, Some(2));
+        assert!(SemanticVersion::parse("23w13a").is_none());
+        assert!(SemanticVersion::parse("1.x.2").is_none());
+    }
+
+    #[test]
+    fn test_fabric_predicates() {
+        assert!(matches_fabric_predicate("1.20.1", ">=1.20"));
+        assert!(!matches_fabric_predicate("1.19.4", ">=1.20"));
+        assert!(matches_fabric_predicate("1.20.1", ">=1.20 <1.21"));
+        assert!(!matches_fabric_predicate("1.21", ">=1.20 <1.21"));
+        assert!(matches_fabric_predicate("0.14.25", "~0.14.21"));
+        assert!(!matches_fabric_predicate("0.15.0", "~0.14.21"));
+        assert!(matches_fabric_predicate("1.9.0", "^1.2.3"));
+        assert!(!matches_fabric_predicate("2.0.0", "^1.2.3"));
+        assert!(matches_fabric_predicate("1.20.4", "1.20.x"));
+        assert!(!matches_fabric_predicate("1.21", "1.20.x"));
+        assert!(matches_fabric_predicate("1.20.1", "1.20.1"));
+        assert!(matches_fabric_predicate("anything", "*"));
+        assert!(matches_fabric_predicate("1.20.1-pre1", ">1.20"));
+        // 快照版本无法按语义化版本比较，只做字符串相等判断
+        assert!(matches_fabric_predicate("23w13a", "23w13a"));
+        assert!(!matches_fabric_predicate("23w13a", "1.20"));
+        assert!(matches_fabric_predicate("23w13a", ">=1.19.4"));
+
+        let any_of = vec!["1.20".to_string(), "1.20.1".to_string()];
+        assert!(matches_fabric_versions("1.20.1", &any_of));
+        assert!(!matches_fabric_versions("1.20.2", &any_of));
+        assert!(matches_fabric_versions("1.20.2", &[]));
+    }
+
+    #[test]
+    fn test_maven_version() {
+        let parse = MavenVersion::parse;
+        assert!(parse("1.0") == parse("1"));
+        assert!(parse("1.0.0") == parse("1"));
+        assert!(parse("47.2.0") > parse("47.1.3"));
+        assert!(parse("1.0-alpha") < parse("1.0-beta"));
+        assert!(parse("1.0-beta") < parse("1.0-rc1"));
+        assert!(parse("1.0-rc1") < parse("1.0"));
+        assert!(parse("1.0-SNAPSHOT") < parse("1.0"));
+        assert!(parse("1.0") < parse("1.0-sp"));
+        assert!(parse("1.0.1") > parse("1.0-sp"));
+        assert!(parse("1.20.1") < parse("1.21"));
+        assert!(parse("0.5.1.f") > parse("0.5.1.e"));
+    }
+
+    #[test]
+    fn test_maven_range() {
+        let contains =
+            |spec: &str, version: &str| MavenRange::parse(spec).unwrap().contains(version);
+        assert!(contains("[47,)", "47.2.0"));
+        assert!(!contains("[47,)", "46.0.14"));
+        assert!(contains("[1.20.1,1.21)", "1.20.1"));
+        assert!(contains("[1.20.1,1.21)", "1.20.6"));
+        assert!(!contains("[1.20.1,1.21)", "1.21"));
+        assert!(contains("(,1.0]", "1.0"));
+        assert!(!contains("(,1.0)", "1.0"));
+        assert!(contains("[1.0]", "1.0.0"));
+        assert!(!contains("[1.0]", "1.0.1"));
+        assert!(contains("[1.0,2.0),[3.0,)", "3.5"));
+        assert!(!contains("[1.0,2.0),[3.0,)", "2.5"));
+        assert!(contains("1.0", "0.1"));
+        assert!(contains("*", "0.1"));
+        assert!(MavenRange::parse("[1.0").is_err());
+        assert!(MavenRange::parse("(1.0)").is_err());
+        assert!(matches_maven_range("1.0", "[1.0"));
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use crate::game::packs::{
+    model::{PackInfo, PackKind},
+    util::{list_datapacks, list_packs, pack_formats, read_pack_icon_data_url, set_resource_packs},
+};
+use crate::game::profile::util::{get_profile, get_profile_game_dir};
+use crate::game::version::util::resolve_client_json;
+
+/// 列出配置中的资源包或光影包，并标记与游戏版本不兼容的包
+#[tauri::command]
+pub async fn list_packs_command(
+    game_path: PathBuf,
+    profile_id: String,
+    kind: PackKind,
+) -> Result<Vec<PackInfo>, String> {
+    if kind == PackKind::Data {
+        return Err("数据包需要按存档列出".to_string());
+    }
+    let profile = get_profile(&game_path, &profile_id)?;
+    let resolved = resolve_client_json(&game_path, &profile.version_name)?;
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || list_packs(&game_dir, kind, pack_formats(&resolved)))
+        .await
+        .map_err(|e| format!("扫描资源包失败: {}", e))
+}
+
+/// 列出配置中某个存档的数据包
+#[tauri::command]
+pub async fn list_datapacks_command(
+    game_path: PathBuf,
+    profile_id: String,
+    world: String,
+) -> Result<Vec<PackInfo>, String> {
+    let profile = get_profile(&game_path, &profile_id)?;
+    let resolved = resolve_client_json(&game_path, &profile.version_name)?;
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || list_datapacks(&game_dir, &world, pack_formats(&resolved)))
+        .await
+        .map_err(|e| format!("扫描数据包失败: {}", e))?
+}
+
+/// 设置启用的资源包及其顺序，`packs` 为文件名，优先级高的在前
+#[tauri::command]
+pub fn set_resource_packs_command(
+    game_path: PathBuf,
+    profile_id: String,
+    packs: Vec<String>,
+) -> Result<(), String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    set_resource_packs(&game_dir, &packs)
+}
+
+/// 读取包的 pack.png，返回 data URL
+#[tauri::command]
+pub fn get_pack_icon_command(path: PathBuf) -> Option<String> {
+    read_pack_icon_data_url(&path)
+}
@@ -0,0 +1,4 @@
+pub mod command;
+pub mod model;
+pub mod scanner;
+pub mod util;
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// 资源包类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PackKind {
+    Resource,
+    Shader,
+    Data,
+}
+
+impl PackKind {
+    /// 资源包与光影包所在的文件夹，数据包位于各个存档中
+    pub fn folder(self) -> &'static str {
+        match self {
+            PackKind::Resource => "resourcepacks",
+            PackKind::Shader => "shaderpacks",
+            PackKind::Data => "datapacks",
+        }
+    }
+}
+
+/// 支持的格式范围（含两端）
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// 资源包、光影包或数据包
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackInfo {
+    pub kind: PackKind,
+    pub file_name: String,
+    pub path: PathBuf,
+    /// 是否为文件夹形式
+    pub directory: bool,
+    /// 去掉格式化后的描述
+    pub description: Option<String>,
+    pub pack_format: Option<u32>,
+    pub supported_formats: Option<FormatRange>,
+    /// 是否包含 pack.png
+    pub has_icon: bool,
+    /// 资源包是否在 options.txt 中启用，数据包是否未被存档禁用
+    pub enabled: bool,
+    /// 与当前版本的格式不一致，无法确定时为 false
+    pub incompatible: bool,
+    /// 读取 pack.mcmeta 失败的原因
+    pub error: Option<String>,
+}
+
+/// 从 pack.mcmeta 中读取的信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackMeta {
+    pub description: Option<String>,
+    pub pack_format: Option<u32>,
+    pub supported_formats: Option<FormatRange>,
+}
+
+/// 版本对应的资源包与数据包格式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackFormats {
+    pub resource: Option<u32>,
+    pub data: Option<u32>,
+}
+
+/// `supported_formats` 可为单个数字、`[min, max]` 或对象
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum RawFormatRange {
+    Single(u32),
+    Pair(Vec<u32>),
+    Object {
+        min_inclusive: u32,
+        max_inclusive: u32,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RawPackSection {
+    pub pack_format: Option<u32>,
+    pub description: Option<serde_json::Value>,
+    pub supported_formats: Option<RawFormatRange>,
+    /// 1.21.9 起使用的新字段
+    pub min_format: Option<serde_json::Value>,
+    pub max_format: Option<serde_json::Value>,
+}
+
+/// `pack.mcmeta`
+#[derive(Deserialize, Debug)]
+pub struct PackMcmeta {
+    pub pack: RawPackSection,
+}
@@ -0,0 +1,289 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde_json::Value;
+use zip::ZipArchive;
+
+use super::model::{
+    FormatRange, PackFormats, PackInfo, PackKind, PackMcmeta, PackMeta, RawFormatRange,
+};
+use crate::game::mods::scanner::parse_lenient_json;
+
+const MCMETA_FILE: &str = "pack.mcmeta";
+const ICON_FILE: &str = "pack.png";
+
+/// 将文本组件展开为纯文本，并去掉 `§` 格式代码
+pub fn flatten_text_component(value: &Value) -> String {
+    fn collect(value: &Value, out: &mut String) {
+        match value {
+            Value::String(s) => out.push_str(s),
+            Value::Number(n) => out.push_str(&n.to_string()),
+            Value::Bool(b) => out.push_str(&b.to_string()),
+            Value::Array(items) => items.iter().for_each(|item| collect(item, out)),
+            Value::Object(map) => {
+                if let Some(text) = map.get("text").or_else(|| map.get("translate")) {
+                    collect(text, out);
+                }
+                if let Some(extra) = map.get("extra") {
+                    collect(extra, out);
+                }
+            }
+            Value::Null => {}
+        }
+    }
+    let mut text = String::new();
+    collect(value, &mut text);
+    strip_formatting(&text)
+}
+
+/// 去掉 `§` 与其后的一个字符
+pub fn strip_formatting(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// 取 1.21.9 起 `[主版本, 次版本]` 形式格式号中的主版本
+fn format_major(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(n) => n.as_u64().map(|n| n as u32),
+        Value::Array(items) => items.first()?.as_u64().map(|n| n as u32),
+        _ => None,
+    }
+}
+
+/// 解析 `pack.mcmeta`
+pub fn parse_pack_mcmeta(content: &[u8]) -> Result<PackMeta, String> {
+    let meta: PackMcmeta = parse_lenient_json(content)?;
+    let pack = meta.pack;
+    let description = pack.description.as_ref().map(flatten_text_component);
+    let range = match pack.supported_formats {
+        Some(RawFormatRange::Single(format)) => Some(FormatRange {
+            min: format,
+            max: format,
+        }),
+        Some(RawFormatRange::Pair(formats)) if formats.len() == 2 => Some(FormatRange {
+            min: formats[0],
+            max: formats[1],
+        }),
+        Some(RawFormatRange::Object {
+            min_inclusive,
+            max_inclusive,
+        }) => Some(FormatRange {
+            min: min_inclusive,
+            max: max_inclusive,
+        }),
+        _ => match (
+            pack.min_format.as_ref().and_then(format_major),
+            pack.max_format.as_ref().and_then(format_major),
+        ) {
+            (Some(min), Some(max)) => Some(FormatRange { min, max }),
+            _ => None,
+        },
+    };
+    Ok(PackMeta {
+        description,
+        pack_format: pack.pack_format.or(range.map(|r| r.max)),
+        supported_formats: range,
+    })
+}
+
+/// 读取压缩包或文件夹中的文件
+fn read_pack_entry(path: &Path, name: &str, limit: u64) -> Option<Vec<u8>> {
+    if path.is_dir() {
+        let file = path.join(name);
+        if fs::metadata(&file).ok()?.len() > limit {
+            return None;
+        }
+        return fs::read(file).ok();
+    }
+    let mut archive = ZipArchive::new(File::open(path).ok()?).ok()?;
+    let mut entry = archive.by_name(name).ok()?;
+    if entry.size() > limit {
+        return None;
+    }
+    let mut content = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut content).ok()?;
+    Some(content)
+}
+
+fn has_pack_entry(path: &Path, name: &str) -> bool {
+    if path.is_dir() {
+        return path.join(name).is_file();
+    }
+    File::open(path)
+        .ok()
+        .and_then(|f| ZipArchive::new(f).ok())
+        .is_some_and(|mut archive| archive.by_name(name).is_ok())
+}
+
+/// 读取 `pack.png`，返回 PNG 数据
+pub fn read_pack_icon(path: &Path) -> Option<Vec<u8>> {
+    read_pack_entry(path, ICON_FILE, 1024 * 1024)
+}
+
+/// 格式号是否适用于当前版本
+fn is_compatible(format: Option<u32>, range: Option<FormatRange>, current: u32) -> Option<bool> {
+    match (range, format) {
+        (Some(range), _) => Some(range.min <= current && current <= range.max),
+        (None, Some(format)) => Some(format == current),
+        (None, None) => None,
+    }
+}
+
+/// 读取单个资源包、光影包或数据包
+pub fn read_pack(path: &Path, kind: PackKind, formats: PackFormats) -> PackInfo {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut info = PackInfo {
+        kind,
+        file_name,
+        path: path.to_path_buf(),
+        directory: path.is_dir(),
+        description: None,
+        pack_format: None,
+        supported_formats: None,
+        has_icon: has_pack_entry(path, ICON_FILE),
+        enabled: false,
+        incompatible: false,
+        error: None,
+    };
+    match read_pack_entry(path, MCMETA_FILE, 1024 * 1024) {
+        Some(content) => match parse_pack_mcmeta(&content) {
+            Ok(meta) => {
+                info.description = meta.description;
+                info.pack_format = meta.pack_format;
+                info.supported_formats = meta.supported_formats;
+            }
+            Err(e) => info.error = Some(e),
+        },
+        // 光影包通常没有 pack.mcmeta
+        None if kind != PackKind::Shader => {
+            info.error = Some(format!("缺少 {}", MCMETA_FILE));
+        }
+        None => {}
+    }
+    let current = match kind {
+        PackKind::Resource => formats.resource,
+        PackKind::Data => formats.data,
+        PackKind::Shader => None,
+    };
+    if let Some(current) = current {
+        info.incompatible =
+            is_compatible(info.pack_format, info.supported_formats, current) == Some(false);
+    }
+    info
+}
+
+/// 列出目录中的压缩包与文件夹形式的包，按名称排序
+pub fn scan_packs(dir: &Path, kind: PackKind, formats: PackFormats) -> Vec<PackInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<PackInfo> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                || path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+        })
+        .map(|path| read_pack(&path, kind, formats))
+        .collect();
+    packs.sort_by_key(|p| p.file_name.to_lowercase());
+    packs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    #[test]
+    fn test_parse_pack_mcmeta() {
+        let content = r#"{
+            "pack": {
+                "pack_format": 15,
+                "description": [{ "text": "§6Faithful", "extra": [" 32x"] }],
+                "supported_formats": { "min_inclusive": 15, "max_inclusive": 22 }
+            }
+        }"#;
+        let meta = parse_pack_mcmeta(content.as_bytes()).unwrap();
+        assert_eq!(meta.description.as_deref(), Some("Faithful 32x"));
+        assert_eq!(meta.pack_format, Some(15));
+        assert_eq!(
+            meta.supported_formats,
+            Some(FormatRange { min: 15, max: 22 })
+        );
+
+        let meta = parse_pack_mcmeta(
+            br#"{ "pack": { "pack_format": 34, "supported_formats": [34, 42], "description": "a" } }"#,
+        )
+        .unwrap();
+        assert_eq!(meta.pack_format, Some(34));
+        assert_eq!(
+            meta.supported_formats,
+            Some(FormatRange { min: 34, max: 42 })
+        );
+
+        let meta = parse_pack_mcmeta(
+            br#"{ "pack": { "min_format": [64, 0], "max_format": 69, "description": "" } }"#,
+        )
+        .unwrap();
+        assert_eq!(meta.pack_format, Some(69));
+        assert_eq!(
+            meta.supported_formats,
+            Some(FormatRange { min: 64, max: 69 })
+        );
+    }
+
+    #[test]
+    fn test_scan_packs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("Folder Pack")).unwrap();
+        fs::write(
+            dir.join("Folder Pack/pack.mcmeta"),
+            r#"{ "pack": { "pack_format": 15, "description": "folder" } }"#,
+        )
+        .unwrap();
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            (
+                "pack.mcmeta",
+                &br#"{ "pack": { "pack_format": 8, "description": "old" } }"#[..],
+            ),
+            ("pack.png", &b"\x89PNG"[..]),
+        ] {
+            writer
+                .start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        fs::write(dir.join("old.zip"), writer.finish().unwrap().into_inner()).unwrap();
+        fs::write(dir.join("notes.txt"), b"").unwrap();
+
+        let formats = PackFormats {
+            resource: Some(15),
+            data: Some(15),
+        };
+        let packs = scan_packs(dir, PackKind::Resource, formats);
+        assert_eq!(packs.len(), 2);
+        assert_eq!(packs[0].file_name, "Folder Pack");
+        assert!(packs[0].directory && !packs[0].incompatible && !packs[0].has_icon);
+        assert_eq!(packs[1].description.as_deref(), Some("old"));
+        assert!(packs[1].incompatible && packs[1].has_icon);
+        assert_eq!(read_pack_icon(&packs[1].path).unwrap(), b"\x89PNG");
+    }
+}
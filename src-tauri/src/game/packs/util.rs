@@ -0,0 +1,291 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+use zip::ZipArchive;
+
+use super::model::{PackFormats, PackInfo, PackKind};
+use super::scanner::{read_pack_icon, scan_packs};
+use crate::game::mods::version::SemanticVersion;
+use crate::game::version::util::{detect_game_version, ResolvedVersion};
+use crate::game::worlds::util::{disabled_datapacks, world_dir};
+
+const OPTIONS_FILE: &str = "options.txt";
+const RESOURCE_PACKS_KEY: &str = "resourcePacks";
+/// options.txt 中资源包文件的前缀
+const FILE_PREFIX: &str = "file/";
+
+/// 各正式版开始使用的资源包格式
+const RESOURCE_FORMATS: &[(&str, u32)] = &[
+    ("1.6.1", 1),
+    ("1.9", 2),
+    ("1.11", 3),
+    ("1.13", 4),
+    ("1.15", 5),
+    ("1.16.2", 6),
+    ("1.17", 7),
+    ("1.18", 8),
+    ("1.19", 9),
+    ("1.19.3", 12),
+    ("1.19.4", 13),
+    ("1.20", 15),
+    ("1.20.2", 18),
+    ("1.20.3", 22),
+    ("1.20.5", 32),
+    ("1.21", 34),
+    ("1.21.2", 42),
+    ("1.21.4", 46),
+    ("1.21.5", 55),
+    ("1.21.6", 63),
+    ("1.21.7", 64),
+];
+
+/// 各正式版开始使用的数据包格式
+const DATA_FORMATS: &[(&str, u32)] = &[
+    ("1.13", 4),
+    ("1.15", 5),
+    ("1.16.2", 6),
+    ("1.17", 7),
+    ("1.18", 8),
+    ("1.18.2", 9),
+    ("1.19", 10),
+    ("1.19.4", 12),
+    ("1.20", 15),
+    ("1.20.2", 18),
+    ("1.20.3", 26),
+    ("1.20.5", 41),
+    ("1.21", 48),
+    ("1.21.2", 57),
+    ("1.21.4", 61),
+    ("1.21.5", 71),
+    ("1.21.6", 80),
+    ("1.21.7", 81),
+];
+
+fn lookup_format(table: &[(&str, u32)], version: &SemanticVersion) -> Option<u32> {
+    table
+        .iter()
+        .rev()
+        .find(|(since, _)| SemanticVersion::parse(since).is_some_and(|since| *version >= since))
+        .map(|(_, format)| *format)
+}
+
+/// 根据正式版版本号查表得到包格式，快照等无法解析的版本返回空
+pub fn pack_formats_for_release(minecraft: &str) -> PackFormats {
+    let Some(version) = SemanticVersion::parse(minecraft).filter(|v| v.pre.is_none()) else {
+        return PackFormats::default();
+    };
+    PackFormats {
+        resource: lookup_format(RESOURCE_FORMATS, &version),
+        data: lookup_format(DATA_FORMATS, &version),
+    }
+}
+
+/// 解析客户端 jar 内 `version.json` 的 `pack_version`
+pub fn parse_pack_version(version_json: &Value) -> Option<PackFormats> {
+    let major = |value: Option<&Value>| value.and_then(Value::as_u64).map(|n| n as u32);
+    match version_json.get("pack_version")? {
+        Value::Number(n) => {
+            let format = n.as_u64().map(|n| n as u32);
+            Some(PackFormats {
+                resource: format,
+                data: format,
+            })
+        }
+        Value::Object(map) => Some(PackFormats {
+            resource: major(map.get("resource")).or(major(map.get("resource_major"))),
+            data: major(map.get("data")).or(major(map.get("data_major"))),
+        }),
+        _ => None,
+    }
+}
+
+fn read_jar_pack_version(jar_path: &Path) -> Option<PackFormats> {
+    let mut archive = ZipArchive::new(File::open(jar_path).ok()?).ok()?;
+    let mut entry = archive.by_name("version.json").ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    parse_pack_version(&serde_json::from_str(&content).ok()?)
+}
+
+/// 版本使用的包格式，优先读取客户端 jar，缺失时按版本号查表
+pub fn pack_formats(resolved: &ResolvedVersion) -> PackFormats {
+    if let Some(formats) = read_jar_pack_version(&resolved.jar_path) {
+        return formats;
+    }
+    detect_game_version(resolved)
+        .map(|version| pack_formats_for_release(&version))
+        .unwrap_or_default()
+}
+
+/// 解析 options.txt 中的列表值，如 `["vanilla","file/a.zip"]`
+fn parse_option_list(value: &str) -> Vec<String> {
+    serde_json::from_str(value).unwrap_or_default()
+}
+
+/// 读取 options.txt 中的 `resourcePacks`，按加载顺序排列（后者优先级更高）
+pub fn read_resource_pack_order(game_dir: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(game_dir.join(OPTIONS_FILE)) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(RESOURCE_PACKS_KEY)?.strip_prefix(':'))
+        .map(parse_option_list)
+        .unwrap_or_default()
+}
+
+/// 已启用的资源包文件名，优先级高的在前
+pub fn enabled_resource_packs(game_dir: &Path) -> Vec<String> {
+    read_resource_pack_order(game_dir)
+        .iter()
+        .rev()
+        .filter_map(|entry| entry.strip_prefix(FILE_PREFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 写入启用的资源包，`packs` 为文件名，优先级高的在前
+///
+/// 保留 vanilla 等内置包，并保留 options.txt 中的其他选项。
+pub fn set_resource_packs(game_dir: &Path, packs: &[String]) -> Result<(), String> {
+    let path = game_dir.join(OPTIONS_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("读取 {} 失败: {}", OPTIONS_FILE, e)),
+    };
+    let mut entries: Vec<String> = read_resource_pack_order(game_dir)
+        .into_iter()
+        .filter(|entry| !entry.starts_with(FILE_PREFIX))
+        .collect();
+    if entries.is_empty() {
+        entries.push("vanilla".to_string());
+    }
+    entries.extend(
+        packs
+            .iter()
+            .rev()
+            .map(|name| format!("{}{}", FILE_PREFIX, name)),
+    );
+    let line = format!(
+        "{}:{}",
+        RESOURCE_PACKS_KEY,
+        serde_json::to_string(&entries).map_err(|e| e.to_string())?
+    );
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    for existing in content.lines() {
+        if existing.starts_with(&format!("{}:", RESOURCE_PACKS_KEY)) {
+            if !replaced {
+                lines.push(line.clone());
+                replaced = true;
+            }
+        } else {
+            lines.push(existing.to_string());
+        }
+    }
+    if !replaced {
+        lines.push(line);
+    }
+    fs::create_dir_all(game_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    fs::write(&path, lines.join("\n") + "\n")
+        .map_err(|e| format!("写入 {} 失败: {}", OPTIONS_FILE, e))
+}
+
+/// 列出游戏目录中的资源包或光影包，资源包按 options.txt 标记启用状态
+pub fn list_packs(game_dir: &Path, kind: PackKind, formats: PackFormats) -> Vec<PackInfo> {
+    let mut packs = scan_packs(&game_dir.join(kind.folder()), kind, formats);
+    if kind == PackKind::Resource {
+        let enabled = enabled_resource_packs(game_dir);
+        for pack in &mut packs {
+            pack.enabled = enabled.contains(&pack.file_name);
+        }
+    }
+    packs
+}
+
+/// 列出存档中的数据包
+pub fn list_datapacks(
+    game_dir: &Path,
+    world: &str,
+    formats: PackFormats,
+) -> Result<Vec<PackInfo>, String> {
+    let world_dir = world_dir(game_dir, world)?;
+    let mut packs = scan_packs(&world_dir.join("datapacks"), PackKind::Data, formats);
+    let disabled = disabled_datapacks(&world_dir);
+    for pack in &mut packs {
+        pack.enabled = !disabled.contains(&pack.file_name);
+    }
+    Ok(packs)
+}
+
+/// 读取包的 pack.png，返回 data URL
+pub fn read_pack_icon_data_url(path: &Path) -> Option<String> {
+    read_pack_icon(path).map(|png| format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_formats_for_release() {
+        let formats = pack_formats_for_release("1.20.1");
+        assert_eq!(formats.resource, Some(15));
+        assert_eq!(formats.data, Some(15));
+        assert_eq!(pack_formats_for_release("1.21.1").resource, Some(34));
+        assert_eq!(pack_formats_for_release("1.18.2").data, Some(9));
+        assert_eq!(pack_formats_for_release("1.12.2").data, None);
+        assert_eq!(pack_formats_for_release("23w31a"), PackFormats::default());
+    }
+
+    #[test]
+    fn test_parse_pack_version() {
+        let formats = parse_pack_version(
+            &serde_json::json!({ "pack_version": { "resource": 15, "data": 15 } }),
+        )
+        .unwrap();
+        assert_eq!(formats.resource, Some(15));
+        let formats = parse_pack_version(
+            &serde_json::json!({ "pack_version": { "resource_major": 69, "data_major": 88 } }),
+        )
+        .unwrap();
+        assert_eq!(formats.data, Some(88));
+        assert_eq!(
+            parse_pack_version(&serde_json::json!({ "pack_version": 4 }))
+                .unwrap()
+                .resource,
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_set_resource_packs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        set_resource_packs(dir, &["a.zip".to_string()]).unwrap();
+        assert_eq!(
+            read_resource_pack_order(dir),
+            vec!["vanilla".to_string(), "file/a.zip".to_string()]
+        );
+
+        fs::write(
+            dir.join(OPTIONS_FILE),
+            "version:3465\nresourcePacks:[\"vanilla\",\"fabric\",\"file/old.zip\"]\nlang:zh_cn\n",
+        )
+        .unwrap();
+        set_resource_packs(dir, &["top.zip".to_string(), "Folder Pack".to_string()]).unwrap();
+        let content = fs::read_to_string(dir.join(OPTIONS_FILE)).unwrap();
+        assert_eq!(
+            content,
+            "version:3465\nresourcePacks:[\"vanilla\",\"fabric\",\"file/Folder Pack\",\"file/top.zip\"]\nlang:zh_cn\n"
+        );
+        assert_eq!(
+            enabled_resource_packs(dir),
+            vec!["top.zip".to_string(), "Folder Pack".to_string()]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use crate::game::profile::import::import_launcher_profiles;
+use crate::game::profile::model::{
+    ImportReport, MigrationReport, Profile, ProfileJson, ProfileSummary,
+};
+use crate::game::profile::util::{
+    clone_profile, create_profile, delete_profile, list_profiles, load_profiles,
+    migrate_profile_to_isolated, rename_profile, set_last_profile, update_profile,
+};
+
+/// 列出游戏目录下的所有配置
+#[tauri::command]
+pub fn list_profiles_command(game_path: PathBuf) -> Result<Vec<ProfileSummary>, String> {
+    list_profiles(&game_path)
+}
+
+#[tauri::command]
+pub fn get_profiles_command(game_path: PathBuf) -> Result<ProfileJson, String> {
+    load_profiles(&game_path)
+}
+
+/// 新建配置，返回配置 ID
+#[tauri::command]
+pub fn create_profile_command(game_path: PathBuf, profile: Profile) -> Result<String, String> {
+    create_profile(&game_path, profile)
+}
+
+#[tauri::command]
+pub fn update_profile_command(
+    game_path: PathBuf,
+    id: String,
+    profile: Profile,
+) -> Result<(), String> {
+    update_profile(&game_path, &id, profile)
+}
+
+/// 复制配置，返回新配置的 ID
+#[tauri::command]
+pub fn clone_profile_command(
+    game_path: PathBuf,
+    id: String,
+    name: String,
+) -> Result<String, String> {
+    clone_profile(&game_path, &id, name)
+}
+
+#[tauri::command]
+pub fn rename_profile_command(game_path: PathBuf, id: String, name: String) -> Result<(), String> {
+    rename_profile(&game_path, &id, name)
+}
+
+#[tauri::command]
+pub fn delete_profile_command(game_path: PathBuf, id: String) -> Result<(), String> {
+    delete_profile(&game_path, &id)
+}
+
+#[tauri::command]
+pub fn set_last_profile_command(game_path: PathBuf, id: String) -> Result<(), String> {
+    set_last_profile(&game_path, &id)
+}
+
+/// 将使用公共目录的配置迁移为版本隔离
+#[tauri::command]
+pub async fn migrate_profile_to_isolated_command(
+    game_path: PathBuf,
+    id: String,
+) -> Result<MigrationReport, String> {
+    tokio::task::spawn_blocking(move || migrate_profile_to_isolated(&game_path, &id))
+        .await
+        .map_err(|e| format!("迁移配置失败: {}", e))?
+}
+
+/// 导入官方启动器的 launcher_profiles.json 以及 HMCL / PCL 的版本设置
+#[tauri::command]
+pub async fn import_launcher_profiles_command(
+    game_path: PathBuf,
+    source: Option<PathBuf>,
+) -> Result<ImportReport, String> {
+    import_launcher_profiles(&game_path, source).await
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::game::profile::model::{
+    AdvancedProfile, GameDir, ImportReport, JvmMemory, Profile, ProfileIcon, Resolution,
+};
+use crate::game::profile::util::{modify_profiles, new_profile_id};
+use crate::game::version::model::LatestVersion;
+use crate::game::version::util::get_local_versions;
+use crate::mojang::api::get_version_manifest;
+use crate::util::mirror::current_source;
+
+/// 官方启动器 `launcher_profiles.json` 中的单个配置
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct OfficialProfile {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    profile_type: Option<String>,
+    icon: Option<String>,
+    last_version_id: Option<String>,
+    game_dir: Option<String>,
+    /// Java 可执行文件路径，如 `.../bin/javaw.exe`
+    java_dir: Option<String>,
+    java_args: Option<String>,
+    resolution: Option<OfficialResolution>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OfficialResolution {
+    width: u32,
+    height: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct OfficialLauncherProfiles {
+    #[serde(default)]
+    profiles: HashMap<String, OfficialProfile>,
+}
+
+/// HMCL 版本目录下的 `hmclversion.cfg`
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct HmclVersionSetting {
+    uses_global: bool,
+    java_args: Option<String>,
+    minecraft_args: Option<String>,
+    max_memory: Option<u32>,
+    auto_memory: Option<bool>,
+    width: Option<u32>,
+    height: Option<u32>,
+    fullscreen: Option<bool>,
+    server_ip: Option<String>,
+    /// 0 为公共目录，1 为版本隔离，2 为自定义目录
+    game_dir_type: Option<u32>,
+    game_dir: Option<String>,
+    java_dir: Option<String>,
+    wrapper: Option<String>,
+    not_check_game: Option<bool>,
+    show_logs: Option<bool>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// 解析 `-Xmx` 等内存参数的值，返回 MB
+pub fn parse_memory_size(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+    let number: u64 = number.parse().ok()?;
+    let mb = match unit.to_ascii_lowercase().as_str() {
+        "g" => number * 1024,
+        "m" => number,
+        "k" => number / 1024,
+        _ => return None,
+    };
+    u32::try_from(mb).ok()
+}
+
+/// 从 JVM 参数中取出最大内存，其余参数原样返回
+fn split_memory_args(args: &str) -> (Option<u32>, Option<String>) {
+    let mut memory = None;
+    let mut rest = Vec::new();
+    for arg in args.split_whitespace() {
+        if let Some(value) = arg.strip_prefix("-Xmx") {
+            memory = parse_memory_size(value);
+        } else if !arg.starts_with("-Xms") {
+            rest.push(arg);
+        }
+    }
+    let rest = (!rest.is_empty()).then(|| rest.join(" "));
+    (memory, rest)
+}
+
+/// 官方启动器保存的是 Java 可执行文件，转换为 JRE 目录
+pub fn java_home_from_executable(path: &str) -> String {
+    let path = PathBuf::from(path);
+    let is_executable = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|s| s.eq_ignore_ascii_case("java") || s.eq_ignore_ascii_case("javaw"));
+    match path.parent().and_then(|bin| bin.parent()) {
+        Some(home) if is_executable => home.to_string_lossy().into_owned(),
+        _ => path.to_string_lossy().into_owned(),
+    }
+}
+
+fn game_dir_from_path(game_path: &Path, version_name: &str, dir: Option<&str>) -> GameDir {
+    let Some(dir) = dir.filter(|d| !d.trim().is_empty()) else {
+        return GameDir::Default;
+    };
+    let dir = PathBuf::from(dir);
+    if dir == game_path {
+        GameDir::Default
+    } else if dir == game_path.join("versions").join(version_name) {
+        GameDir::Isolated
+    } else {
+        GameDir::Custom(dir.to_string_lossy().into_owned())
+    }
+}
+
+fn official_icon(icon: Option<&str>) -> ProfileIcon {
+    match icon {
+        Some(data) if data.starts_with("data:") => ProfileIcon::Custom(data.to_string()),
+        Some("Grass") => ProfileIcon::GrassBlock,
+        Some("Chest") => ProfileIcon::Chest,
+        Some("Dirt") => ProfileIcon::Dirt,
+        Some("Cobblestone") => ProfileIcon::CobbleStone,
+        Some("Anvil") => ProfileIcon::Anvil,
+        _ => ProfileIcon::Furnace,
+    }
+}
+
+fn empty_profile(name: String, version_name: String) -> Profile {
+    Profile {
+        icon: ProfileIcon::Furnace,
+        name,
+        version_name,
+        game_dir: GameDir::Default,
+        jre_path: None,
+        jvm_memory: None,
+        resolution: None,
+        fullscreen: None,
+        show_logs: None,
+        server_address: None,
+        advanced: None,
+        requirement: None,
+    }
+}
+
+/// 将官方启动器的配置转换为本启动器的配置
+///
+/// `latest-release` / `latest-snapshot` 类型的配置需要 `latest` 才能确定版本，为空时跳过。
+fn convert_official_profile(
+    game_path: &Path,
+    profile: OfficialProfile,
+    latest: Option<&LatestVersion>,
+) -> Result<Profile, String> {
+    let version_name = match (profile.profile_type.as_deref(), profile.last_version_id) {
+        (Some("latest-release"), _) => latest.map(|l| l.release.clone()),
+        (Some("latest-snapshot"), _) => latest.map(|l| l.snapshot.clone()),
+        (_, Some(id)) if id == "latest-release" => latest.map(|l| l.release.clone()),
+        (_, Some(id)) if id == "latest-snapshot" => latest.map(|l| l.snapshot.clone()),
+        (_, id) => id,
+    }
+    .ok_or_else(|| "无法确定配置使用的版本".to_string())?;
+    let name = non_empty(profile.name).unwrap_or_else(|| version_name.clone());
+    let mut result = empty_profile(name, version_name);
+    result.icon = official_icon(profile.icon.as_deref());
+    result.game_dir =
+        game_dir_from_path(game_path, &result.version_name, profile.game_dir.as_deref());
+    result.jre_path = non_empty(profile.java_dir).map(|p| java_home_from_executable(&p));
+    if let Some(java_args) = non_empty(profile.java_args) {
+        let (memory, rest) = split_memory_args(&java_args);
+        result.jvm_memory = memory.map(JvmMemory::Custom);
+        if rest.is_some() {
+            result.advanced = Some(AdvancedProfile {
+                jvm_args: rest,
+                ..Default::default()
+            });
+        }
+    }
+    result.resolution = profile.resolution.map(|r| Resolution {
+        width: r.width,
+        height: r.height,
+    });
+    Ok(result)
+}
+
+/// 读取 HMCL 的版本设置，使用全局设置的版本返回 None
+fn read_hmcl_version_setting(game_path: &Path, version_name: &str) -> Option<Profile> {
+    let path = game_path
+        .join("versions")
+        .join(version_name)
+        .join("hmclversion.cfg");
+    let content = fs::read_to_string(path).ok()?;
+    let setting: HmclVersionSetting = serde_json::from_str(&content).ok()?;
+    if setting.uses_global {
+        return None;
+    }
+    let mut profile = empty_profile(version_name.to_string(), version_name.to_string());
+    profile.game_dir = match setting.game_dir_type {
+        Some(1) => GameDir::Isolated,
+        Some(2) => non_empty(setting.game_dir).map_or(GameDir::Default, GameDir::Custom),
+        _ => GameDir::Default,
+    };
+    profile.jre_path = non_empty(setting.java_dir).map(|p| java_home_from_executable(&p));
+    if !setting.auto_memory.unwrap_or(true) {
+        profile.jvm_memory = setting.max_memory.map(JvmMemory::Custom);
+    }
+    if let (Some(width), Some(height)) = (setting.width, setting.height) {
+        profile.resolution = Some(Resolution { width, height });
+    }
+    profile.fullscreen = setting.fullscreen;
+    profile.show_logs = setting.show_logs;
+    profile.server_address = non_empty(setting.server_ip);
+    profile.advanced = Some(AdvancedProfile {
+        jvm_args: non_empty(setting.java_args),
+        game_args: non_empty(setting.minecraft_args),
+        wrap_command: non_empty(setting.wrapper),
+        skip_game_integrity_check: setting.not_check_game,
+        skip_mod_check: None,
+    });
+    Some(profile)
+}
+
+/// 读取 PCL 的版本设置（`PCL/Setup.ini`，每行为 `键:值`）
+fn read_pcl_version_setting(game_path: &Path, version_name: &str) -> Option<Profile> {
+    let path = game_path
+        .join("versions")
+        .join(version_name)
+        .join("PCL")
+        .join("Setup.ini");
+    let content = fs::read_to_string(path).ok()?;
+    let values: HashMap<&str, &str> = content
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+    let get = |key: &str| {
+        values
+            .get(key)
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string())
+    };
+    let mut profile = empty_profile(version_name.to_string(), version_name.to_string());
+    let isolated = match get("VersionArgumentIndieV2") {
+        Some(v) => v.eq_ignore_ascii_case("true"),
+        None => get("VersionArgumentIndie").as_deref() == Some("1"),
+    };
+    if isolated {
+        profile.game_dir = GameDir::Isolated;
+    }
+    profile.server_address = get("VersionServerEnter");
+    let jvm_args = get("VersionAdvanceJvm");
+    let game_args = get("VersionAdvanceGame");
+    if jvm_args.is_some() || game_args.is_some() {
+        profile.advanced = Some(AdvancedProfile {
+            jvm_args,
+            game_args,
+            ..Default::default()
+        });
+    }
+    Some(profile)
+}
+
+/// 导入官方启动器配置与 HMCL / PCL 的版本设置
+///
+/// `source` 为 `launcher_profiles.json` 的路径，为空时使用游戏目录下的文件。
+/// 与已有配置同名且版本相同的条目会跳过，因此可以重复导入。
+pub async fn import_launcher_profiles(
+    game_path: &Path,
+    source: Option<PathBuf>,
+) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+    let mut imported = Vec::new();
+
+    let source = source.unwrap_or_else(|| game_path.join("launcher_profiles.json"));
+    if source.exists() {
+        let content = fs::read_to_string(&source)
+            .map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+        let official: OfficialLauncherProfiles = serde_json::from_str(&content)
+            .map_err(|e| format!("解析 {} 失败: {}", source.display(), e))?;
+        let needs_latest = official.profiles.values().any(|p| {
+            p.profile_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with("latest-"))
+                || p.last_version_id
+                    .as_deref()
+                    .is_some_and(|v| v.starts_with("latest-"))
+        });
+        let latest = if needs_latest {
+            get_version_manifest(current_source())
+                .await
+                .map(|m| m.latest)
+                .ok()
+        } else {
+            None
+        };
+        for (id, profile) in official.profiles {
+            let label = profile.name.clone().filter(|n| !n.is_empty()).unwrap_or(id);
+            match convert_official_profile(game_path, profile, latest.as_ref()) {
+                Ok(profile) => imported.push(profile),
+                Err(e) => report.skipped.push(format!("{}: {}", label, e)),
+            }
+        }
+    }
+
+    if let Ok(versions) = get_local_versions(game_path.to_path_buf()).await {
+        for version in versions {
+            if let Some(profile) = read_hmcl_version_setting(game_path, &version.name)
+                .or_else(|| read_pcl_version_setting(game_path, &version.name))
+            {
+                imported.push(profile);
+            }
+        }
+    }
+
+    modify_profiles(game_path, |profiles| {
+        for profile in imported {
+            let duplicate = profiles
+                .profiles
+                .values()
+                .any(|p| p.name == profile.name && p.version_name == profile.version_name);
+            if duplicate {
+                report
+                    .skipped
+                    .push(format!("{}: 已存在同名配置", profile.name));
+                continue;
+            }
+            report.imported.push(profile.name.clone());
+            profiles.profiles.insert(new_profile_id(), profile);
+        }
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_size() {
+        assert_eq!(parse_memory_size("2G"), Some(2048));
+        assert_eq!(parse_memory_size("4096m"), Some(4096));
+        assert_eq!(parse_memory_size("524288k"), Some(512));
+        assert_eq!(parse_memory_size("2"), None);
+        assert_eq!(parse_memory_size("G"), None);
+    }
+
+    #[test]
+    fn test_convert_official_profile() {
+        let game_path = PathBuf::from("/games/.minecraft");
+        let json = r#"{
+            "profiles": {
+                "a": {
+                    "name": "Modded",
+                    "type": "custom",
+                    "icon": "Grass",
+                    "lastVersionId": "fabric-loader-0.16.14-1.21.5",
+                    "gameDir": "/games/.minecraft/versions/fabric-loader-0.16.14-1.21.5",
+                    "javaDir": "/opt/jdk-21/bin/java",
+                    "javaArgs": "-Xmx4G -XX:+UseG1GC -Xms1G",
+                    "resolution": { "width": 1280, "height": 720 }
+                },
+                "b": { "name": "", "type": "latest-release", "lastVersionId": "latest-release" }
+            }
+        }"#;
+        let mut official: OfficialLauncherProfiles = serde_json::from_str(json).unwrap();
+
+        let profile =
+            convert_official_profile(&game_path, official.profiles.remove("a").unwrap(), None)
+                .unwrap();
+        assert_eq!(profile.name, "Modded");
+        assert!(matches!(profile.icon, ProfileIcon::GrassBlock));
+        assert!(matches!(profile.game_dir, GameDir::Isolated));
+        assert_eq!(profile.jre_path.as_deref(), Some("/opt/jdk-21"));
+        assert!(matches!(profile.jvm_memory, Some(JvmMemory::Custom(4096))));
+        assert_eq!(
+            profile.advanced.unwrap().jvm_args.as_deref(),
+            Some("-XX:+UseG1GC")
+        );
+        assert_eq!(profile.resolution.unwrap().width, 1280);
+
+        let latest = LatestVersion {
+            release: "1.21.5".to_string(),
+            snapshot: "25w21a".to_string(),
+        };
+        let b = official.profiles.remove("b").unwrap();
+        assert!(convert_official_profile(&game_path, b, None).is_err());
+        let b = OfficialProfile {
+            profile_type: Some("latest-release".to_string()),
+            ..Default::default()
+        };
+        let profile = convert_official_profile(&game_path, b, Some(&latest)).unwrap();
+        assert_eq!(profile.version_name, "1.21.5");
+        assert_eq!(profile.name, "1.21.5");
+    }
+
+    #[test]
+    fn test_read_version_settings() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let hmcl_dir = game_path.join("versions").join("hmcl");
+        fs::create_dir_all(&hmcl_dir).unwrap();
+        fs::write(
+            hmcl_dir.join("hmclversion.cfg"),
+            r#"{"usesGlobal":false,"javaArgs":"-Dfoo=bar","maxMemory":3072,"autoMemory":false,
+                "width":854,"height":480,"gameDirType":1,"serverIp":"mc.example.com"}"#,
+        )
+        .unwrap();
+        let profile = read_hmcl_version_setting(game_path, "hmcl").unwrap();
+        assert!(matches!(profile.game_dir, GameDir::Isolated));
+        assert!(matches!(profile.jvm_memory, Some(JvmMemory::Custom(3072))));
+        assert_eq!(profile.server_address.as_deref(), Some("mc.example.com"));
+
+        let pcl_dir = game_path.join("versions").join("pcl").join("PCL");
+        fs::create_dir_all(&pcl_dir).unwrap();
+        fs::write(
+            pcl_dir.join("Setup.ini"),
+            "VersionArgumentIndieV2:True\nVersionAdvanceJvm:-Dfoo=bar\nVersionServerEnter:\n",
+        )
+        .unwrap();
+        let profile = read_pcl_version_setting(game_path, "pcl").unwrap();
+        assert!(matches!(profile.game_dir, GameDir::Isolated));
+        assert_eq!(profile.server_address, None);
+        assert_eq!(
+            profile.advanced.unwrap().jvm_args.as_deref(),
+            Some("-Dfoo=bar")
+        );
+    }
+}
@@ -1 +1,4 @@
+pub mod command;
+pub mod import;
 pub mod model;
+pub mod util;
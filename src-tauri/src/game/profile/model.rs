@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::game::version::model::ModLoaderInfo;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum ProfileIcon {
     Furnace,
@@ -20,7 +23,7 @@ pub enum ProfileIcon {
     Custom(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum GameDir {
     Default,
@@ -28,19 +31,19 @@ pub enum GameDir {
     Custom(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Resolution {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum JvmMemory {
     Auto,
     Custom(u32),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AdvancedProfile {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jvm_args: Option<String>,
@@ -50,9 +53,19 @@ pub struct AdvancedProfile {
     pub wrap_command: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub skip_game_integrity_check: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_mod_check: Option<bool>,
+}
+
+/// 配置需要的游戏版本与加载器，版本未安装时用于提示用户
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionRequirement {
+    pub minecraft: String,
+    pub loaders: Vec<ModLoaderInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Profile {
     #[serde(default = "default_icon")]
@@ -75,21 +88,65 @@ pub struct Profile {
     pub server_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub advanced: Option<AdvancedProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirement: Option<VersionRequirement>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ProfileSettings {
     pub game_dir: GameDir,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ProfileJson {
+    #[serde(default)]
     pub last_profile: String,
+    #[serde(default)]
     pub profiles: HashMap<String, Profile>,
 }
 
+/// 列出配置时返回的条目，附带解析后的版本信息
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub id: String,
+    pub profile: Profile,
+    /// 对应的版本是否已安装
+    pub installed: bool,
+    /// 实际使用的游戏目录
+    pub game_directory: PathBuf,
+    /// 游戏本体版本号
+    pub game_version: Option<String>,
+    /// 版本使用的模组加载器
+    pub loaders: Vec<ModLoaderInfo>,
+    /// 是否为上次启动的配置
+    pub last_used: bool,
+}
+
+/// 迁移为版本隔离的结果
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// 已移动到版本目录的条目
+    pub moved: Vec<String>,
+    /// 公共目录仍被其他配置使用，因此复制到版本目录的条目
+    pub copied: Vec<String>,
+    /// 版本目录中已存在而跳过的条目
+    pub skipped: Vec<String>,
+}
+
+/// 从其他启动器导入配置的结果
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// 已导入的配置名
+    pub imported: Vec<String>,
+    /// 跳过的配置及原因
+    pub skipped: Vec<String>,
+}
+
 fn default_icon() -> ProfileIcon {
     ProfileIcon::Furnace
 }
@@ -0,0 +1,520 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use uuid::Uuid;
+
+use crate::game::profile::model::{
+    GameDir, MigrationReport, Profile, ProfileJson, ProfileSummary, VersionRequirement,
+};
+use crate::game::version::util::{detect_game_version, detect_mod_loaders, resolve_client_json};
+use crate::util::utils::{copy_dir_all, write_atomic};
+
+/// 游戏目录下保存配置的文件名
+const PROFILES_FILE_NAME: &str = "asanmcl_profiles.json";
+
+/// 串行化配置文件的读取-修改-写回，避免并发的命令互相覆盖
+static PROFILES_LOCK: Mutex<()> = Mutex::new(());
+
+/// 版本隔离时从公共目录迁移到版本目录的文件与文件夹
+const ISOLATED_ENTRIES: &[&str] = &[
+    "mods",
+    "config",
+    "saves",
+    "resourcepacks",
+    "shaderpacks",
+    "screenshots",
+    "logs",
+    "crash-reports",
+    "options.txt",
+    "optionsof.txt",
+    "optionsshaders.txt",
+    "servers.dat",
+];
+
+fn get_profiles_file_path(game_path: &Path) -> PathBuf {
+    game_path.join(PROFILES_FILE_NAME)
+}
+
+/// 读取游戏目录下的配置文件，文件不存在时返回空配置
+pub fn load_profiles(game_path: &Path) -> Result<ProfileJson, String> {
+    let path = get_profiles_file_path(game_path);
+    if !path.exists() {
+        return Ok(ProfileJson::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("读取配置文件失败 {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析配置文件失败 {}: {}", path.display(), e))
+}
+
+fn save_profiles(game_path: &Path, profiles: &ProfileJson) -> Result<(), String> {
+    fs::create_dir_all(game_path).map_err(|e| format!("创建游戏目录失败: {}", e))?;
+    let content =
+        serde_json::to_string_pretty(profiles).map_err(|e| format!("序列化配置失败: {}", e))?;
+    write_atomic(&get_profiles_file_path(game_path), content.as_bytes())
+        .map_err(|e| format!("保存配置文件失败: {}", e))
+}
+
+/// 读取配置、修改并写回，整个过程持有进程内的锁
+pub fn modify_profiles<T>(
+    game_path: &Path,
+    f: impl FnOnce(&mut ProfileJson) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = PROFILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut profiles = load_profiles(game_path)?;
+    let result = f(&mut profiles)?;
+    save_profiles(game_path, &profiles)?;
+    Ok(result)
+}
+
+pub fn new_profile_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+pub fn get_profile(game_path: &Path, id: &str) -> Result<Profile, String> {
+    load_profiles(game_path)?
+        .profiles
+        .remove(id)
+        .ok_or_else(|| format!("配置 {} 不存在", id))
+}
+
+/// 新建配置，返回配置 ID
+pub fn create_profile(game_path: &Path, profile: Profile) -> Result<String, String> {
+    modify_profiles(game_path, |profiles| {
+        let id = new_profile_id();
+        if profiles.last_profile.is_empty() {
+            profiles.last_profile = id.clone();
+        }
+        profiles.profiles.insert(id.clone(), profile);
+        Ok(id)
+    })
+}
+
+/// 覆盖已有配置的全部设置
+pub fn update_profile(game_path: &Path, id: &str, profile: Profile) -> Result<(), String> {
+    modify_profiles(game_path, |profiles| {
+        let entry = profiles
+            .profiles
+            .get_mut(id)
+            .ok_or_else(|| format!("配置 {} 不存在", id))?;
+        *entry = profile;
+        Ok(())
+    })
+}
+
+/// 复制配置，返回新配置的 ID
+pub fn clone_profile(game_path: &Path, id: &str, name: String) -> Result<String, String> {
+    modify_profiles(game_path, |profiles| {
+        let mut profile = profiles
+            .profiles
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("配置 {} 不存在", id))?;
+        profile.name = name;
+        let new_id = new_profile_id();
+        profiles.profiles.insert(new_id.clone(), profile);
+        Ok(new_id)
+    })
+}
+
+pub fn rename_profile(game_path: &Path, id: &str, name: String) -> Result<(), String> {
+    modify_profiles(game_path, |profiles| {
+        profiles
+            .profiles
+            .get_mut(id)
+            .ok_or_else(|| format!("配置 {} 不存在", id))?
+            .name = name;
+        Ok(())
+    })
+}
+
+/// 删除配置，删除的是上次启动的配置时清空 `last_profile`
+pub fn delete_profile(game_path: &Path, id: &str) -> Result<(), String> {
+    modify_profiles(game_path, |profiles| {
+        profiles
+            .profiles
+            .remove(id)
+            .ok_or_else(|| format!("配置 {} 不存在", id))?;
+        if profiles.last_profile == id {
+            profiles.last_profile.clear();
+        }
+        Ok(())
+    })
+}
+
+pub fn set_last_profile(game_path: &Path, id: &str) -> Result<(), String> {
+    modify_profiles(game_path, |profiles| {
+        if !profiles.profiles.contains_key(id) {
+            return Err(format!("配置 {} 不存在", id));
+        }
+        profiles.last_profile = id.to_string();
+        Ok(())
+    })
+}
+
+/// 配置实际使用的游戏目录（即 `${game_directory}`）
+///
+/// 隔离时为 `versions/<版本名>/`，自定义的相对路径以游戏目录为基准。
+/// libraries 与 assets 始终位于 `game_path` 下，不受影响。
+pub fn resolve_game_directory(game_path: &Path, profile: &Profile) -> PathBuf {
+    match &profile.game_dir {
+        GameDir::Default => game_path.to_path_buf(),
+        GameDir::Isolated => game_path.join("versions").join(&profile.version_name),
+        GameDir::Custom(dir) => game_path.join(dir),
+    }
+}
+
+/// 按配置 ID 获取实际使用的游戏目录
+pub fn get_profile_game_dir(game_path: &Path, profile_id: &str) -> Result<PathBuf, String> {
+    let profile = get_profile(game_path, profile_id)?;
+    Ok(resolve_game_directory(game_path, &profile))
+}
+
+/// 描述配置需要安装的游戏版本与加载器，如 `Minecraft 1.20.1 + Fabric 0.15.11`
+pub fn describe_requirement(requirement: &VersionRequirement) -> String {
+    std::iter::once(format!("Minecraft {}", requirement.minecraft))
+        .chain(
+            requirement
+                .loaders
+                .iter()
+                .map(|l| format!("{:?} {}", l.loader, l.version)),
+        )
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// 版本未安装时的提示，记录了需要安装的内容时一并说明
+pub fn missing_version_message(
+    version_name: &str,
+    requirement: Option<&VersionRequirement>,
+) -> String {
+    match requirement {
+        Some(requirement) => format!(
+            "版本 {} 尚未安装，请先安装 {} 并命名为 {}",
+            version_name,
+            describe_requirement(requirement),
+            version_name
+        ),
+        None => format!("版本 {} 尚未安装，请先安装该版本", version_name),
+    }
+}
+
+/// 将使用公共目录的配置迁移为版本隔离
+///
+/// 把 mods、config、saves 等移动到 `versions/<版本名>/`，目标已存在的条目会跳过并保留原处。
+/// 仍有其他配置使用公共目录时改为复制，避免这些配置丢失模组与存档。
+/// 版本目录已被其他配置使用时拒绝迁移；中途失败会撤销已完成的移动与复制。
+pub fn migrate_profile_to_isolated(game_path: &Path, id: &str) -> Result<MigrationReport, String> {
+    let _guard = PROFILES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut profiles = load_profiles(game_path)?;
+    let profile = profiles
+        .profiles
+        .get(id)
+        .ok_or_else(|| format!("配置 {} 不存在", id))?;
+    if !matches!(profile.game_dir, GameDir::Default) {
+        return Err(format!(
+            "配置 {} 未使用公共游戏目录，无需迁移",
+            profile.name
+        ));
+    }
+    let target_dir = game_path.join("versions").join(&profile.version_name);
+    if let Some((_, other)) = profiles
+        .profiles
+        .iter()
+        .find(|(other, p)| *other != id && resolve_game_directory(game_path, p) == target_dir)
+    {
+        return Err(format!(
+            "版本目录 {} 已被配置 {} 使用，无法迁移",
+            target_dir.display(),
+            other.name
+        ));
+    }
+    let shared = profiles
+        .profiles
+        .iter()
+        .any(|(other, p)| other != id && matches!(p.game_dir, GameDir::Default));
+
+    let created = !target_dir.exists();
+    fs::create_dir_all(&target_dir).map_err(|e| format!("创建版本目录失败: {}", e))?;
+    let mut report = MigrationReport::default();
+    let result = migrate_entries(game_path, &target_dir, shared, &mut report).and_then(|()| {
+        if let Some(profile) = profiles.profiles.get_mut(id) {
+            profile.game_dir = GameDir::Isolated;
+        }
+        save_profiles(game_path, &profiles)
+    });
+    if let Err(e) = result {
+        rollback_migration(game_path, &target_dir, &report);
+        if created {
+            let _ = fs::remove_dir(&target_dir);
+        }
+        return Err(e);
+    }
+    Ok(report)
+}
+
+/// 逐个移动或复制隔离条目，已完成的条目记录在 `report` 中供失败时撤销
+fn migrate_entries(
+    game_path: &Path,
+    target_dir: &Path,
+    shared: bool,
+    report: &mut MigrationReport,
+) -> Result<(), String> {
+    for entry in ISOLATED_ENTRIES {
+        let source = game_path.join(entry);
+        if !source.exists() {
+            continue;
+        }
+        let target = target_dir.join(entry);
+        if target.exists() {
+            report.skipped.push(entry.to_string());
+            continue;
+        }
+        if shared {
+            let copied = if source.is_dir() {
+                copy_dir_all(&source, &target).map(|_| ())
+            } else {
+                fs::copy(&source, &target)
+                    .map(|_| ())
+                    .map_err(|e| format!("复制 {} 失败: {}", entry, e))
+            };
+            if let Err(e) = copied {
+                // 复制了一半的目标同样需要清理
+                remove_entry(&target);
+                return Err(e);
+            }
+            report.copied.push(entry.to_string());
+        } else {
+            fs::rename(&source, &target).map_err(|e| format!("移动 {} 失败: {}", entry, e))?;
+            report.moved.push(entry.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 撤销迁移：移回已移动的条目并删除已复制的副本
+fn rollback_migration(game_path: &Path, target_dir: &Path, report: &MigrationReport) {
+    for entry in report.moved.iter().rev() {
+        if let Err(e) = fs::rename(target_dir.join(entry), game_path.join(entry)) {
+            eprintln!("撤销迁移 {} 失败: {}", entry, e);
+        }
+    }
+    for entry in &report.copied {
+        remove_entry(&target_dir.join(entry));
+    }
+}
+
+fn remove_entry(path: &Path) {
+    let _ = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+}
+
+/// 列出所有配置及其对应版本的游戏版本号和模组加载器，按名称排序
+pub fn list_profiles(game_path: &Path) -> Result<Vec<ProfileSummary>, String> {
+    let profiles = load_profiles(game_path)?;
+    let mut summaries: Vec<ProfileSummary> = profiles
+        .profiles
+        .into_iter()
+        .map(|(id, profile)| {
+            let resolved = resolve_client_json(game_path, &profile.version_name).ok();
+            ProfileSummary {
+                game_directory: resolve_game_directory(game_path, &profile),
+                last_used: id == profiles.last_profile,
+                id,
+                installed: resolved.is_some(),
+                game_version: resolved.as_ref().and_then(detect_game_version),
+                loaders: resolved
+                    .as_ref()
+                    .map(|r| detect_mod_loaders(&r.json))
+                    .unwrap_or_default(),
+                profile,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.profile.name.cmp(&b.profile.name));
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::profile::model::ProfileIcon;
+
+    fn test_profile(name: &str) -> Profile {
+        Profile {
+            icon: ProfileIcon::Furnace,
+            name: name.to_string(),
+            version_name: "1.21.5".to_string(),
+            game_dir: GameDir::Default,
+            jre_path: None,
+            jvm_memory: None,
+            resolution: None,
+            fullscreen: None,
+            show_logs: None,
+            server_address: None,
+            advanced: None,
+            requirement: None,
+        }
+    }
+
+    #[test]
+    fn test_describe_requirement() {
+        use crate::game::version::model::{ModLoaderInfo, ModLoaderType};
+        let mut requirement = VersionRequirement {
+            minecraft: "1.20.1".to_string(),
+            loaders: Vec::new(),
+        };
+        assert_eq!(describe_requirement(&requirement), "Minecraft 1.20.1");
+        requirement.loaders.push(ModLoaderInfo {
+            loader: ModLoaderType::NeoForge,
+            version: "47.1.106".to_string(),
+        });
+        assert_eq!(
+            describe_requirement(&requirement),
+            "Minecraft 1.20.1 + NeoForge 47.1.106"
+        );
+    }
+
+    #[test]
+    fn test_profile_crud() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        assert!(list_profiles(game_path).unwrap().is_empty());
+
+        let id = create_profile(game_path, test_profile("Vanilla")).unwrap();
+        assert_eq!(load_profiles(game_path).unwrap().last_profile, id);
+
+        let cloned = clone_profile(game_path, &id, "Copy".to_string()).unwrap();
+        rename_profile(game_path, &id, "Main".to_string()).unwrap();
+        set_last_profile(game_path, &cloned).unwrap();
+
+        let list = list_profiles(game_path).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].profile.name, "Copy");
+        assert!(list[0].last_used);
+        assert!(!list[0].installed);
+        assert_eq!(list[1].profile.name, "Main");
+
+        delete_profile(game_path, &cloned).unwrap();
+        let profiles = load_profiles(game_path).unwrap();
+        assert!(profiles.last_profile.is_empty());
+        assert_eq!(profiles.profiles.len(), 1);
+        assert!(delete_profile(game_path, &cloned).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_modify_profiles() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                scope.spawn(move || {
+                    create_profile(game_path, test_profile(&format!("P{}", i))).unwrap();
+                });
+            }
+        });
+        assert_eq!(load_profiles(game_path).unwrap().profiles.len(), 8);
+    }
+
+    #[test]
+    fn test_migrate_profile_to_isolated() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let mut profile = test_profile("Vanilla");
+        assert_eq!(resolve_game_directory(game_path, &profile), game_path);
+        profile.game_dir = GameDir::Custom("instances/a".to_string());
+        assert_eq!(
+            resolve_game_directory(game_path, &profile),
+            game_path.join("instances/a")
+        );
+
+        let id = create_profile(game_path, test_profile("Vanilla")).unwrap();
+        fs::create_dir_all(game_path.join("mods")).unwrap();
+        fs::write(game_path.join("options.txt"), "lang:en_us").unwrap();
+        let isolated = game_path.join("versions").join("1.21.5");
+        fs::create_dir_all(isolated.join("saves")).unwrap();
+        fs::create_dir_all(game_path.join("saves")).unwrap();
+
+        let report = migrate_profile_to_isolated(game_path, &id).unwrap();
+        assert_eq!(report.moved, vec!["mods", "options.txt"]);
+        assert_eq!(report.skipped, vec!["saves"]);
+        assert!(isolated.join("mods").is_dir());
+        assert!(!game_path.join("options.txt").exists());
+
+        let profile = get_profile(game_path, &id).unwrap();
+        assert!(matches!(profile.game_dir, GameDir::Isolated));
+        assert_eq!(resolve_game_directory(game_path, &profile), isolated);
+        assert!(migrate_profile_to_isolated(game_path, &id).is_err());
+    }
+
+    #[test]
+    fn test_migrate_shared_game_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let first = create_profile(game_path, test_profile("First")).unwrap();
+        let mut other = test_profile("Second");
+        other.version_name = "1.20.1".to_string();
+        let second = create_profile(game_path, other).unwrap();
+        fs::create_dir_all(game_path.join("mods")).unwrap();
+        fs::write(game_path.join("mods/a.jar"), "a").unwrap();
+        fs::write(game_path.join("options.txt"), "lang:en_us").unwrap();
+
+        // 另一个配置仍使用公共目录，只复制不移动
+        let report = migrate_profile_to_isolated(game_path, &first).unwrap();
+        assert!(report.moved.is_empty());
+        assert_eq!(report.copied, vec!["mods", "options.txt"]);
+        let isolated = game_path.join("versions/1.21.5");
+        assert!(isolated.join("mods/a.jar").is_file());
+        assert!(game_path.join("mods/a.jar").is_file());
+        assert!(game_path.join("options.txt").is_file());
+
+        // 最后一个使用公共目录的配置迁移时直接移动
+        let report = migrate_profile_to_isolated(game_path, &second).unwrap();
+        assert_eq!(report.moved, vec!["mods", "options.txt"]);
+        assert!(report.copied.is_empty());
+        assert!(game_path.join("versions/1.20.1/mods/a.jar").is_file());
+        assert!(!game_path.join("mods").exists());
+        assert!(isolated.join("mods/a.jar").is_file());
+    }
+
+    #[test]
+    fn test_migrate_refuses_used_version_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let id = create_profile(game_path, test_profile("Shared")).unwrap();
+        let mut other = test_profile("Isolated");
+        other.game_dir = GameDir::Isolated;
+        create_profile(game_path, other).unwrap();
+        fs::create_dir_all(game_path.join("mods")).unwrap();
+
+        assert!(migrate_profile_to_isolated(game_path, &id).is_err());
+        assert!(game_path.join("mods").is_dir());
+        let profile = get_profile(game_path, &id).unwrap();
+        assert!(matches!(profile.game_dir, GameDir::Default));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_migrate_rolls_back_on_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let id = create_profile(game_path, test_profile("Vanilla")).unwrap();
+        fs::create_dir_all(game_path.join("mods")).unwrap();
+        fs::write(game_path.join("mods/a.jar"), "a").unwrap();
+        fs::create_dir_all(game_path.join("config")).unwrap();
+        // 目标处失效的符号链接不算已存在，但目录无法移动到它上面
+        let isolated = game_path.join("versions/1.21.5");
+        fs::create_dir_all(&isolated).unwrap();
+        std::os::unix::fs::symlink(game_path.join("missing"), isolated.join("config")).unwrap();
+
+        assert!(migrate_profile_to_isolated(game_path, &id).is_err());
+        assert!(game_path.join("mods/a.jar").is_file());
+        assert!(!isolated.join("mods").exists());
+        let profile = get_profile(game_path, &id).unwrap();
+        assert!(matches!(profile.game_dir, GameDir::Default));
+    }
+}
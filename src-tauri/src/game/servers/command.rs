@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use crate::game::profile::util::get_profile_game_dir;
+use crate::game::servers::{
+    model::{ServerEntry, ServerStatus},
+    ping::{is_local_address, ping_server},
+    util::{read_servers, write_servers},
+};
+use crate::util::network::ensure_online;
+
+/// 读取配置游戏目录中的服务器列表
+#[tauri::command]
+pub fn list_servers_command(
+    game_path: PathBuf,
+    profile_id: String,
+) -> Result<Vec<ServerEntry>, String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    read_servers(&game_dir)
+}
+
+/// 保存服务器列表，顺序即游戏中显示的顺序
+#[tauri::command]
+pub fn save_servers_command(
+    game_path: PathBuf,
+    profile_id: String,
+    servers: Vec<ServerEntry>,
+) -> Result<(), String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    write_servers(&game_dir, &servers)
+}
+
+/// 查询服务器的 MOTD、版本、在线人数与延迟，局域网服务器在离线时同样可以查询
+#[tauri::command]
+pub async fn ping_server_command(address: String) -> Result<ServerStatus, String> {
+    if !is_local_address(&address) {
+        ensure_online("查询服务器状态").await?;
+    }
+    ping_server(&address).await
+}
@@ -0,0 +1,5 @@
+pub mod command;
+pub mod model;
+pub mod motd;
+pub mod ping;
+pub mod util;
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// `servers.dat` 中的服务器
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerEntry {
+    pub name: String,
+    /// 服务器地址，可带端口
+    pub ip: String,
+    /// Base64 编码的 PNG 图标，由游戏在刷新服务器列表时写入
+    pub icon: Option<String>,
+    /// 是否接受服务器资源包，未设置时由游戏询问
+    pub accept_textures: Option<bool>,
+}
+
+/// MOTD 中样式相同的一段文本
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MotdSegment {
+    pub text: String,
+    /// 颜色名称（如 gold）或 `#RRGGBB`
+    pub color: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+    pub obfuscated: bool,
+}
+
+/// 服务器状态中列出的部分在线玩家
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlayerSample {
+    pub name: String,
+    pub id: String,
+}
+
+/// Server List Ping 的结果
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    /// 实际连接的主机与端口，经过 SRV 解析
+    pub host: String,
+    pub port: u16,
+    /// 服务器版本名称，如 Paper 1.20.1
+    pub version: Option<String>,
+    pub protocol: Option<i32>,
+    pub players_online: Option<i64>,
+    pub players_max: Option<i64>,
+    pub players_sample: Vec<PlayerSample>,
+    pub motd: Vec<MotdSegment>,
+    /// 去掉格式后的 MOTD
+    pub motd_text: String,
+    /// 服务器图标的 data URL
+    pub favicon: Option<String>,
+    /// 延迟，毫秒
+    pub latency: u64,
+    /// 是否通过 1.6 以前的旧协议获取
+    pub legacy: bool,
+}
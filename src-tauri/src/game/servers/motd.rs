@@ -0,0 +1,230 @@
+//! 解析 MOTD 的文本组件与 `§` 格式代码
+
+use serde_json::Value;
+
+use super::model::MotdSegment;
+
+/// `§0` 至 `§f` 对应的颜色
+const COLORS: [&str; 16] = [
+    "black",
+    "dark_blue",
+    "dark_green",
+    "dark_aqua",
+    "dark_red",
+    "dark_purple",
+    "gold",
+    "gray",
+    "dark_gray",
+    "blue",
+    "green",
+    "aqua",
+    "red",
+    "light_purple",
+    "yellow",
+    "white",
+];
+
+fn same_style(a: &MotdSegment, b: &MotdSegment) -> bool {
+    a.color == b.color
+        && a.bold == b.bold
+        && a.italic == b.italic
+        && a.underlined == b.underlined
+        && a.strikethrough == b.strikethrough
+        && a.obfuscated == b.obfuscated
+}
+
+fn push_segment(out: &mut Vec<MotdSegment>, text: &str, style: &MotdSegment) {
+    if text.is_empty() {
+        return;
+    }
+    match out.last_mut() {
+        Some(last) if same_style(last, style) => last.text.push_str(text),
+        _ => out.push(MotdSegment {
+            text: text.to_string(),
+            ..style.clone()
+        }),
+    }
+}
+
+/// 应用一个格式代码，颜色代码会清除之前的格式，`§r` 恢复为组件本身的样式
+fn apply_code(style: &mut MotdSegment, base: &MotdSegment, code: char) {
+    match code.to_ascii_lowercase() {
+        c @ ('0'..='9' | 'a'..='f') => {
+            *style = MotdSegment {
+                color: Some(COLORS[c.to_digit(16).unwrap() as usize].to_string()),
+                ..Default::default()
+            };
+        }
+        'k' => style.obfuscated = true,
+        'l' => style.bold = true,
+        'm' => style.strikethrough = true,
+        'n' => style.underlined = true,
+        'o' => style.italic = true,
+        'r' => *style = base.clone(),
+        _ => {}
+    }
+}
+
+/// 解析带 `§` 格式代码的文本
+fn push_formatted(out: &mut Vec<MotdSegment>, text: &str, base: &MotdSegment) {
+    let mut style = base.clone();
+    let mut current = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '§' {
+            current.push(c);
+            continue;
+        }
+        push_segment(out, &current, &style);
+        current.clear();
+        if let Some(code) = chars.next() {
+            apply_code(&mut style, base, code);
+        }
+    }
+    push_segment(out, &current, &style);
+}
+
+/// 组件的样式，未设置的属性继承自父组件
+fn component_style(
+    component: &serde_json::Map<String, Value>,
+    parent: &MotdSegment,
+) -> MotdSegment {
+    let flag = |name: &str, inherited: bool| {
+        component
+            .get(name)
+            .and_then(Value::as_bool)
+            .unwrap_or(inherited)
+    };
+    MotdSegment {
+        text: String::new(),
+        color: component
+            .get("color")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| parent.color.clone()),
+        bold: flag("bold", parent.bold),
+        italic: flag("italic", parent.italic),
+        underlined: flag("underlined", parent.underlined),
+        strikethrough: flag("strikethrough", parent.strikethrough),
+        obfuscated: flag("obfuscated", parent.obfuscated),
+    }
+}
+
+fn walk(value: &Value, parent: &MotdSegment, out: &mut Vec<MotdSegment>) {
+    match value {
+        Value::String(text) => push_formatted(out, text, parent),
+        Value::Number(n) => push_formatted(out, &n.to_string(), parent),
+        Value::Bool(b) => push_formatted(out, &b.to_string(), parent),
+        // 数组中的后续元素相当于第一个元素的 extra
+        Value::Array(items) => {
+            let Some((first, rest)) = items.split_first() else {
+                return;
+            };
+            walk(first, parent, out);
+            let style = match first {
+                Value::Object(component) => component_style(component, parent),
+                _ => parent.clone(),
+            };
+            rest.iter().for_each(|item| walk(item, &style, out));
+        }
+        Value::Object(component) => {
+            let style = component_style(component, parent);
+            if let Some(text) = component.get("text").or_else(|| component.get("translate")) {
+                walk(text, &style, out);
+            }
+            if let Some(Value::Array(extra)) = component.get("extra") {
+                extra.iter().for_each(|item| walk(item, &style, out));
+            }
+        }
+        Value::Null => {}
+    }
+}
+
+/// 将状态中的 `description` 解析为样式分段
+pub fn parse_motd(description: &Value) -> Vec<MotdSegment> {
+    let mut out = Vec::new();
+    walk(description, &MotdSegment::default(), &mut out);
+    out
+}
+
+/// 解析旧协议返回的带格式代码的 MOTD
+pub fn parse_legacy_motd(text: &str) -> Vec<MotdSegment> {
+    let mut out = Vec::new();
+    push_formatted(&mut out, text, &MotdSegment::default());
+    out
+}
+
+/// 去掉格式后的文本
+pub fn plain_text(segments: &[MotdSegment]) -> String {
+    segments.iter().map(|s| s.text.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, color: Option<&str>) -> MotdSegment {
+        MotdSegment {
+            text: text.to_string(),
+            color: color.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_legacy_motd() {
+        let segments = parse_legacy_motd("§6§lHypixel §r§7Network §cHi");
+        assert_eq!(
+            segments,
+            vec![
+                MotdSegment {
+                    bold: true,
+                    ..segment("Hypixel ", Some("gold"))
+                },
+                segment("Network ", Some("gray")),
+                segment("Hi", Some("red")),
+            ]
+        );
+        assert_eq!(plain_text(&segments), "Hypixel Network Hi");
+        // 颜色代码清除之前的格式
+        let segments = parse_legacy_motd("§lA§aB");
+        assert!(segments[0].bold && !segments[1].bold);
+        assert_eq!(parse_legacy_motd("plain"), vec![segment("plain", None)]);
+    }
+
+    #[test]
+    fn test_parse_motd() {
+        let description = serde_json::json!({
+            "text": "",
+            "extra": [
+                { "text": "A Minecraft ", "color": "aqua", "bold": true },
+                { "text": "Server", "color": "#FF8800", "extra": [{ "text": "!", "bold": false }] },
+                "\n§eyellow"
+            ]
+        });
+        let segments = parse_motd(&description);
+        assert_eq!(
+            segments,
+            vec![
+                MotdSegment {
+                    bold: true,
+                    ..segment("A Minecraft ", Some("aqua"))
+                },
+                segment("Server!", Some("#FF8800")),
+                segment("\n", None),
+                segment("yellow", Some("yellow")),
+            ]
+        );
+        assert_eq!(plain_text(&segments), "A Minecraft Server!\nyellow");
+
+        // 数组的后续元素继承第一个元素的样式
+        let segments = parse_motd(&serde_json::json!([{ "text": "a", "italic": true }, "b"]));
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text, "ab");
+        assert!(segments[0].italic);
+        assert_eq!(
+            plain_text(&parse_motd(&serde_json::json!("§aHello"))),
+            "Hello"
+        );
+    }
+}
@@ -0,0 +1,598 @@
+//! Server List Ping：1.7 起的状态协议与 1.6 以前的旧协议
+
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use hickory_resolver::TokioAsyncResolver;
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use super::model::ServerStatus;
+use super::motd::{parse_legacy_motd, parse_motd, plain_text};
+use crate::game::launch::args::split_server_address;
+
+pub const DEFAULT_PORT: u16 = 25565;
+/// 连接并获取状态的超时，两种协议各自计算
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// 获取状态后等待 pong 的超时，超时则以状态请求的耗时作为延迟
+const PONG_TIMEOUT: Duration = Duration::from_secs(2);
+/// 状态响应的最大长度，图标较大时也足够
+const MAX_PACKET_SIZE: usize = 4 * 1024 * 1024;
+/// 握手时的协议版本，-1 表示仅查询状态
+const STATUS_PROTOCOL: i32 = -1;
+
+pub fn write_varint(out: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7f == 0 {
+            out.push(value as u8);
+            return;
+        }
+        out.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+pub async fn read_varint<R: AsyncRead + Unpin>(reader: &mut R) -> Result<i32, String> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = reader
+            .read_u8()
+            .await
+            .map_err(|e| format!("读取数据失败: {}", e))?;
+        value |= ((byte & 0x7f) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err("VarInt 过长".to_string())
+}
+
+fn write_string(out: &mut Vec<u8>, text: &str) {
+    write_varint(out, text.len() as i32);
+    out.extend(text.as_bytes());
+}
+
+/// 加上长度前缀
+fn frame(body: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    write_varint(&mut packet, body.len() as i32);
+    packet.extend(body);
+    packet
+}
+
+pub async fn write_packet<W: tokio::io::AsyncWrite + Unpin>(
+    writer: &mut W,
+    body: &[u8],
+) -> Result<(), String> {
+    writer
+        .write_all(&frame(body))
+        .await
+        .map_err(|e| format!("发送数据失败: {}", e))
+}
+
+/// 读取一个数据包，返回数据包 ID 与内容
+pub async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> Result<(i32, Vec<u8>), String> {
+    let len = read_varint(reader).await?;
+    if len <= 0 || len as usize > MAX_PACKET_SIZE {
+        return Err(format!("数据包长度无效: {}", len));
+    }
+    let mut body = vec![0; len as usize];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| format!("读取数据失败: {}", e))?;
+    let mut cursor = body.as_slice();
+    let id = read_varint(&mut cursor).await?;
+    Ok((id, cursor.to_vec()))
+}
+
+/// 握手（下一状态为 status）与状态请求
+pub fn status_request(host: &str, port: u16) -> Vec<u8> {
+    let mut handshake = vec![0x00];
+    write_varint(&mut handshake, STATUS_PROTOCOL);
+    write_string(&mut handshake, host);
+    handshake.extend(port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    let mut data = frame(&handshake);
+    data.extend(frame(&[0x00]));
+    data
+}
+
+async fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    let stream = TcpStream::connect((host, port))
+        .await
+        .map_err(|e| format!("连接 {}:{} 失败: {}", host, port, e))?;
+    let _ = stream.set_nodelay(true);
+    Ok(stream)
+}
+
+/// 1.7 起的状态协议，连接 `host:port`，握手中发送用户输入的地址，返回状态 JSON 与延迟
+async fn modern_ping(
+    host: &str,
+    port: u16,
+    server_host: &str,
+    server_port: u16,
+) -> Result<(Value, u64), String> {
+    let status = async {
+        let mut stream = connect(host, port).await?;
+        let start = Instant::now();
+        stream
+            .write_all(&status_request(server_host, server_port))
+            .await
+            .map_err(|e| format!("发送数据失败: {}", e))?;
+        let (id, body) = read_packet(&mut stream).await?;
+        let latency = start.elapsed().as_millis() as u64;
+        if id != 0x00 {
+            return Err(format!("意外的数据包: {}", id));
+        }
+        let mut cursor = body.as_slice();
+        let len = read_varint(&mut cursor).await?;
+        let json = cursor
+            .get(..len.max(0) as usize)
+            .ok_or_else(|| "状态响应不完整".to_string())?;
+        let status: Value =
+            serde_json::from_slice(json).map_err(|e| format!("解析服务器状态失败: {}", e))?;
+        Ok((stream, status, latency))
+    };
+    let (mut stream, status, status_latency) = timeout(PING_TIMEOUT, status)
+        .await
+        .map_err(|_| "连接超时".to_string())??;
+
+    // 部分服务器不响应 ping，此时以状态请求的耗时作为延迟
+    let payload = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default();
+    let mut ping = vec![0x01];
+    ping.extend(payload.to_be_bytes());
+    let start = Instant::now();
+    let pong = async {
+        write_packet(&mut stream, &ping).await?;
+        read_packet(&mut stream).await
+    };
+    let latency = match timeout(PONG_TIMEOUT, pong).await {
+        Ok(Ok((0x01, body))) if body == payload.to_be_bytes() => start.elapsed().as_millis() as u64,
+        _ => status_latency,
+    };
+    Ok((status, latency))
+}
+
+/// 旧协议的响应：协议版本、版本名称、MOTD、在线人数与最大人数
+#[derive(Debug, PartialEq)]
+pub struct LegacyResponse {
+    pub protocol: Option<i32>,
+    pub version: Option<String>,
+    pub motd: String,
+    pub online: Option<i64>,
+    pub max: Option<i64>,
+}
+
+/// 解析旧协议的踢出消息，1.4 起以 `§1\0` 开头，更早的版本为 `MOTD§在线§最大`
+pub fn parse_legacy_response(text: &str) -> Result<LegacyResponse, String> {
+    if let Some(rest) = text.strip_prefix("§1\0") {
+        let fields: Vec<&str> = rest.split('\0').collect();
+        let [protocol, version, motd, online, max] = fields[..] else {
+            return Err("旧协议响应格式无效".to_string());
+        };
+        return Ok(LegacyResponse {
+            protocol: protocol.parse().ok(),
+            version: Some(version.to_string()),
+            motd: motd.to_string(),
+            online: online.parse().ok(),
+            max: max.parse().ok(),
+        });
+    }
+    let mut fields = text.rsplitn(3, '§');
+    let (Some(max), Some(online), Some(motd)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err("旧协议响应格式无效".to_string());
+    };
+    Ok(LegacyResponse {
+        protocol: None,
+        version: None,
+        motd: motd.to_string(),
+        online: online.parse().ok(),
+        max: max.parse().ok(),
+    })
+}
+
+fn write_utf16(out: &mut Vec<u8>, text: &str) {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    out.extend((units.len() as u16).to_be_bytes());
+    units.iter().for_each(|u| out.extend(u.to_be_bytes()));
+}
+
+/// 1.6 客户端发送的旧协议请求，带有 `MC|PingHost` 以便代理服务器转发
+pub fn legacy_request(host: &str, port: u16) -> Vec<u8> {
+    let mut data = vec![0xfe, 0x01, 0xfa];
+    write_utf16(&mut data, "MC|PingHost");
+    let mut rest = vec![74];
+    write_utf16(&mut rest, host);
+    rest.extend((port as i32).to_be_bytes());
+    data.extend((rest.len() as u16).to_be_bytes());
+    data.extend(rest);
+    data
+}
+
+async fn legacy_ping(
+    host: &str,
+    port: u16,
+    server_host: &str,
+    server_port: u16,
+) -> Result<(LegacyResponse, u64), String> {
+    let mut stream = connect(host, port).await?;
+    let start = Instant::now();
+    stream
+        .write_all(&legacy_request(server_host, server_port))
+        .await
+        .map_err(|e| format!("发送数据失败: {}", e))?;
+    let read_err = |e: std::io::Error| format!("读取数据失败: {}", e);
+    if stream.read_u8().await.map_err(read_err)? != 0xff {
+        return Err("旧协议响应格式无效".to_string());
+    }
+    let len = stream.read_u16().await.map_err(read_err)? as usize;
+    let mut bytes = vec![0; len * 2];
+    stream.read_exact(&mut bytes).await.map_err(read_err)?;
+    let latency = start.elapsed().as_millis() as u64;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect();
+    let text = String::from_utf16(&units).map_err(|_| "旧协议响应编码无效".to_string())?;
+    Ok((parse_legacy_response(&text)?, latency))
+}
+
+/// 将状态 JSON 转换为服务器状态
+pub fn status_from_json(host: &str, port: u16, status: &Value, latency: u64) -> ServerStatus {
+    let players = status.get("players");
+    let count = |name: &str| players.and_then(|p| p.get(name)).and_then(Value::as_i64);
+    let players_sample = players
+        .and_then(|p| p.get("sample"))
+        .and_then(|s| serde_json::from_value(s.clone()).ok())
+        .unwrap_or_default();
+    let motd = status
+        .get("description")
+        .map(parse_motd)
+        .unwrap_or_default();
+    ServerStatus {
+        host: host.to_string(),
+        port,
+        version: status
+            .pointer("/version/name")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        protocol: status
+            .pointer("/version/protocol")
+            .and_then(Value::as_i64)
+            .map(|p| p as i32),
+        players_online: count("online"),
+        players_max: count("max"),
+        players_sample,
+        motd_text: plain_text(&motd),
+        motd,
+        // 部分旧服务端会在 Base64 中插入换行
+        favicon: status
+            .get("favicon")
+            .and_then(Value::as_str)
+            .map(|f| f.replace(['\n', '\r'], "")),
+        latency,
+        legacy: false,
+    }
+}
+
+fn status_from_legacy(
+    host: &str,
+    port: u16,
+    response: LegacyResponse,
+    latency: u64,
+) -> ServerStatus {
+    let motd = parse_legacy_motd(&response.motd);
+    ServerStatus {
+        host: host.to_string(),
+        port,
+        version: response.version,
+        protocol: response.protocol,
+        players_online: response.online,
+        players_max: response.max,
+        players_sample: Vec::new(),
+        motd_text: plain_text(&motd),
+        motd,
+        favicon: None,
+        latency,
+        legacy: true,
+    }
+}
+
+/// 连接指定主机与端口查询服务器状态，新协议失败时改用旧协议
+///
+/// `server_host`、`server_port` 为用户输入的地址，在握手中发送，
+/// 与 SRV 记录解析出的连接目标可能不同。
+pub async fn ping_host(
+    host: &str,
+    port: u16,
+    server_host: &str,
+    server_port: u16,
+) -> Result<ServerStatus, String> {
+    let modern_error = match modern_ping(host, port, server_host, server_port).await {
+        Ok((status, latency)) => return Ok(status_from_json(host, port, &status, latency)),
+        Err(e) => e,
+    };
+    match timeout(
+        PING_TIMEOUT,
+        legacy_ping(host, port, server_host, server_port),
+    )
+    .await
+    {
+        Ok(Ok((response, latency))) => Ok(status_from_legacy(host, port, response, latency)),
+        _ => Err(format!(
+            "无法获取服务器 {}:{} 的状态: {}",
+            host, port, modern_error
+        )),
+    }
+}
+
+/// 查询 `_minecraft._tcp` SRV 记录，返回优先级最高的目标
+async fn resolve_srv(host: &str) -> Option<(String, u16)> {
+    let resolver = TokioAsyncResolver::tokio_from_system_conf().ok()?;
+    let lookup = resolver
+        .srv_lookup(format!("_minecraft._tcp.{}", host))
+        .await
+        .ok()?;
+    let record = lookup
+        .iter()
+        .min_by_key(|r| (r.priority(), std::cmp::Reverse(r.weight())))?;
+    let target = record.target().to_utf8();
+    Some((target.trim_end_matches('.').to_string(), record.port()))
+}
+
+/// 解析服务器地址，未指定端口的域名会先查询 SRV 记录
+pub async fn resolve_address(address: &str) -> Result<(String, u16), String> {
+    let (host, port) = split_server_address(address);
+    if host.is_empty() {
+        return Err("服务器地址为空".to_string());
+    }
+    if let Some(port) = port {
+        return Ok((host, port));
+    }
+    if host.parse::<IpAddr>().is_ok() {
+        return Ok((host, DEFAULT_PORT));
+    }
+    Ok(resolve_srv(&host).await.unwrap_or((host, DEFAULT_PORT)))
+}
+
+/// 地址是否指向本机或局域网：`localhost`、回环、私有与链路本地地址
+pub fn is_local_address(address: &str) -> bool {
+    let (host, _) = split_server_address(address);
+    if host.eq_ignore_ascii_case("localhost") {
+        return true;
+    }
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        Ok(IpAddr::V6(ip)) => {
+            let first = ip.segments()[0];
+            // fc00::/7 为唯一本地地址，fe80::/10 为链路本地地址
+            ip.is_loopback() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+        }
+        Err(_) => false,
+    }
+}
+
+/// 按服务器列表中的地址查询服务器状态
+pub async fn ping_server(address: &str) -> Result<ServerStatus, String> {
+    let (host, port) = resolve_address(address).await?;
+    let (server_host, server_port) = split_server_address(address);
+    ping_host(
+        &host,
+        port,
+        &server_host,
+        server_port.unwrap_or(DEFAULT_PORT),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    const STATUS: &str = r#"{
+        "version": { "name": "Paper 1.20.1", "protocol": 763 },
+        "players": {
+            "max": 100, "online": 5,
+            "sample": [{ "name": "Notch", "id": "069a79f4-44e9-4726-a5be-fca90e38aaf5" }]
+        },
+        "description": { "text": "A ", "extra": [{ "text": "Server", "color": "gold" }] },
+        "favicon": "data:image/png;base64,iVBO\nRw0K"
+    }"#;
+
+    #[tokio::test]
+    async fn test_varint() {
+        for (value, bytes) in [
+            (0, vec![0x00]),
+            (1, vec![0x01]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (25565, vec![0xdd, 0xc7, 0x01]),
+            (2097151, vec![0xff, 0xff, 0x7f]),
+            (i32::MAX, vec![0xff, 0xff, 0xff, 0xff, 0x07]),
+            (-1, vec![0xff, 0xff, 0xff, 0xff, 0x0f]),
+        ] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            assert_eq!(out, bytes);
+            assert_eq!(read_varint(&mut bytes.as_slice()).await, Ok(value));
+        }
+        assert!(read_varint(&mut [0xff; 6].as_slice()).await.is_err());
+    }
+
+    #[test]
+    fn test_is_local_address() {
+        assert!(is_local_address("localhost:25565"));
+        assert!(is_local_address("127.0.0.1"));
+        assert!(is_local_address("192.168.1.20:25566"));
+        assert!(is_local_address("10.0.0.5"));
+        assert!(is_local_address("[::1]:25565"));
+        assert!(is_local_address("fd12:3456::1"));
+        assert!(is_local_address("[fe80::1]"));
+        assert!(!is_local_address("mc.hypixel.net"));
+        assert!(!is_local_address("8.8.8.8:25565"));
+        assert!(!is_local_address("2001:db8::1"));
+    }
+
+    #[test]
+    fn test_parse_legacy_response() {
+        assert_eq!(
+            parse_legacy_response("§1\u{0}127\u{0}1.6.4\u{0}§aOld Server\u{0}3\u{0}20").unwrap(),
+            LegacyResponse {
+                protocol: Some(127),
+                version: Some("1.6.4".to_string()),
+                motd: "§aOld Server".to_string(),
+                online: Some(3),
+                max: Some(20),
+            }
+        );
+        let beta = parse_legacy_response("A Beta Server§1§8").unwrap();
+        assert_eq!(beta.motd, "A Beta Server");
+        assert_eq!((beta.online, beta.max), (Some(1), Some(8)));
+        assert!(parse_legacy_response("garbage").is_err());
+    }
+
+    /// 协议版本 -1，下一状态为 1 的握手内容
+    fn handshake_body(host: &str, port: u16) -> Vec<u8> {
+        let mut body = vec![0xff, 0xff, 0xff, 0xff, 0x0f];
+        write_string(&mut body, host);
+        body.extend(port.to_be_bytes());
+        body.push(0x01);
+        body
+    }
+
+    /// 只支持新协议的服务器，`pong` 为 false 时不响应 ping，返回端口与收到的握手内容
+    async fn modern_server(pong: bool) -> (u16, tokio::task::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let (id, handshake) = read_packet(&mut stream).await.unwrap();
+            assert_eq!(id, 0x00);
+            assert_eq!(read_packet(&mut stream).await.unwrap(), (0x00, Vec::new()));
+
+            let mut response = vec![0x00];
+            write_string(&mut response, STATUS);
+            write_packet(&mut stream, &response).await.unwrap();
+            let (id, payload) = read_packet(&mut stream).await.unwrap();
+            assert_eq!(id, 0x01);
+            if pong {
+                let mut pong = vec![0x01];
+                pong.extend(payload);
+                write_packet(&mut stream, &pong).await.unwrap();
+            } else {
+                // 保持连接但不响应
+                let _ = stream.read_u8().await;
+            }
+            handshake
+        });
+        (port, handle)
+    }
+
+    /// 1.6 服务器，收到新协议的握手后直接断开
+    async fn legacy_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 3];
+                stream.read_exact(&mut request).await.unwrap();
+                if request != [0xfe, 0x01, 0xfa] {
+                    continue;
+                }
+                let text = "§1\u{0}78\u{0}1.6.4\u{0}§cLegacy\u{0}0\u{0}10";
+                let mut response = vec![0xff];
+                write_utf16(&mut response, text);
+                stream.write_all(&response).await.unwrap();
+                return;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_ping_modern_server() {
+        let (port, handshake) = modern_server(true).await;
+        let status = ping_server(&format!("127.0.0.1:{}", port)).await.unwrap();
+        assert_eq!(handshake.await.unwrap(), handshake_body("127.0.0.1", port));
+        assert!(!status.legacy);
+        assert_eq!((status.host.as_str(), status.port), ("127.0.0.1", port));
+        assert_eq!(status.version.as_deref(), Some("Paper 1.20.1"));
+        assert_eq!(status.protocol, Some(763));
+        assert_eq!(
+            (status.players_online, status.players_max),
+            (Some(5), Some(100))
+        );
+        assert_eq!(status.players_sample[0].name, "Notch");
+        assert_eq!(status.motd_text, "A Server");
+        assert_eq!(status.motd[1].color.as_deref(), Some("gold"));
+        assert_eq!(
+            status.favicon.as_deref(),
+            Some("data:image/png;base64,iVBORw0K")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ping_legacy_server() {
+        let port = legacy_server().await;
+        let status = ping_host("127.0.0.1", port, "127.0.0.1", port)
+            .await
+            .unwrap();
+        assert!(status.legacy);
+        assert_eq!(status.version.as_deref(), Some("1.6.4"));
+        assert_eq!(status.protocol, Some(78));
+        assert_eq!(
+            (status.players_online, status.players_max),
+            (Some(0), Some(10))
+        );
+        assert_eq!(status.motd_text, "Legacy");
+        assert_eq!(status.motd[0].color.as_deref(), Some("red"));
+    }
+
+    #[tokio::test]
+    async fn test_ping_without_pong() {
+        let (port, handshake) = modern_server(false).await;
+        let start = Instant::now();
+        // 握手中发送用户输入的地址，而非实际连接的地址
+        let status = ping_host("127.0.0.1", port, "mc.example.com", DEFAULT_PORT)
+            .await
+            .unwrap();
+        assert!(!status.legacy);
+        assert_eq!(status.version.as_deref(), Some("Paper 1.20.1"));
+        assert!(start.elapsed() < PING_TIMEOUT);
+        assert_eq!(
+            handshake.await.unwrap(),
+            handshake_body("mc.example.com", DEFAULT_PORT)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_address() {
+        assert_eq!(
+            resolve_address("127.0.0.1").await.unwrap(),
+            ("127.0.0.1".to_string(), DEFAULT_PORT)
+        );
+        assert_eq!(
+            resolve_address("[::1]:25566").await.unwrap(),
+            ("::1".to_string(), 25566)
+        );
+        assert_eq!(
+            resolve_address("::1").await.unwrap(),
+            ("::1".to_string(), DEFAULT_PORT)
+        );
+        assert_eq!(
+            resolve_address("mc.example.com:25570").await.unwrap(),
+            ("mc.example.com".to_string(), 25570)
+        );
+        assert!(resolve_address(" ").await.is_err());
+    }
+}
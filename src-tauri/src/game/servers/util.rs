@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use super::model::ServerEntry;
+use crate::nbt::model::{Compound, Tag};
+use crate::nbt::reader::read_nbt_file;
+use crate::nbt::writer::write_nbt_file;
+
+const SERVERS_DAT: &str = "servers.dat";
+
+fn read_server_compounds(game_dir: &Path) -> Result<Vec<Compound>, String> {
+    let path = game_dir.join(SERVERS_DAT);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let (_, root) = read_nbt_file(&path)?;
+    Ok(root
+        .get_list("servers")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|tag| tag.as_compound().cloned())
+        .collect())
+}
+
+fn parse_server(compound: &Compound) -> Option<ServerEntry> {
+    Some(ServerEntry {
+        name: compound.get_str("name").unwrap_or_default().to_string(),
+        ip: compound.get_str("ip")?.to_string(),
+        icon: compound.get_str("icon").map(str::to_string),
+        accept_textures: compound.get_bool("acceptTextures"),
+    })
+}
+
+/// 读取游戏目录中的服务器列表，文件不存在时为空
+pub fn read_servers(game_dir: &Path) -> Result<Vec<ServerEntry>, String> {
+    Ok(read_server_compounds(game_dir)?
+        .iter()
+        .filter_map(parse_server)
+        .collect())
+}
+
+/// 写入服务器列表
+///
+/// 地址与名称相同的条目会保留游戏写入的其他字段，如 `hidden`。
+pub fn write_servers(game_dir: &Path, servers: &[ServerEntry]) -> Result<(), String> {
+    let mut existing = read_server_compounds(game_dir).unwrap_or_default();
+    let list = servers
+        .iter()
+        .map(|server| {
+            let index = existing.iter().position(|c| {
+                c.get_str("ip") == Some(server.ip.as_str())
+                    && c.get_str("name") == Some(server.name.as_str())
+            });
+            let mut compound = index.map(|i| existing.remove(i)).unwrap_or_default();
+            compound.insert("name", Tag::String(server.name.clone()));
+            compound.insert("ip", Tag::String(server.ip.clone()));
+            match &server.icon {
+                Some(icon) => compound.insert("icon", Tag::String(icon.clone())),
+                None => {
+                    compound.remove("icon");
+                }
+            }
+            match server.accept_textures {
+                Some(accept) => compound.insert("acceptTextures", Tag::Byte(accept as i8)),
+                None => {
+                    compound.remove("acceptTextures");
+                }
+            }
+            Tag::Compound(compound)
+        })
+        .collect();
+    let mut root = Compound::new();
+    root.insert("servers", Tag::List(list));
+    std::fs::create_dir_all(game_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    write_nbt_file(&game_dir.join(SERVERS_DAT), "", &root, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::writer::write_nbt;
+    use std::fs;
+
+    #[test]
+    fn test_read_write_servers() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        assert!(read_servers(dir).unwrap().is_empty());
+
+        // 游戏写入的 servers.dat，包含额外的 hidden 字段
+        let mut hypixel = Compound::new();
+        hypixel.insert("hidden", Tag::Byte(0));
+        hypixel.insert("ip", Tag::String("mc.hypixel.net".to_string()));
+        hypixel.insert("name", Tag::String("Hypixel".to_string()));
+        hypixel.insert("icon", Tag::String("iVBORw0KGgo=".to_string()));
+        hypixel.insert("acceptTextures", Tag::Byte(1));
+        let mut root = Compound::new();
+        root.insert("servers", Tag::List(vec![Tag::Compound(hypixel)]));
+        fs::write(dir.join(SERVERS_DAT), write_nbt("", &root).unwrap()).unwrap();
+
+        let mut servers = read_servers(dir).unwrap();
+        assert_eq!(
+            servers,
+            vec![ServerEntry {
+                name: "Hypixel".to_string(),
+                ip: "mc.hypixel.net".to_string(),
+                icon: Some("iVBORw0KGgo=".to_string()),
+                accept_textures: Some(true),
+            }]
+        );
+
+        servers[0].accept_textures = None;
+        servers.insert(
+            0,
+            ServerEntry {
+                name: "本地服务器".to_string(),
+                ip: "localhost:25566".to_string(),
+                icon: None,
+                accept_textures: Some(false),
+            },
+        );
+        write_servers(dir, &servers).unwrap();
+        assert_eq!(read_servers(dir).unwrap(), servers);
+
+        // 未压缩，且保留了 hidden
+        let data = fs::read(dir.join(SERVERS_DAT)).unwrap();
+        let (_, root) = crate::nbt::reader::read_nbt(&data).unwrap();
+        let list = root.get_list("servers").unwrap();
+        let hypixel = list[1].as_compound().unwrap();
+        assert_eq!(hypixel.get_i64("hidden"), Some(0));
+        assert_eq!(hypixel.get("acceptTextures"), None);
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::game::asset::model::GameAssetIndex;
+use crate::game::version::model::ArtifactFile;
+use crate::game::version::util::{
+    get_local_versions, library_path, native_artifact, resolve_client_json, rules_allow,
+};
+use crate::util::utils::verify_sha1;
+
+/// 本地版本文件的完整性检查结果
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionIntegrityReport {
+    pub version: String,
+    /// 所有文件都存在（且在校验哈希时哈希一致）
+    pub complete: bool,
+    /// 缺失的文件
+    pub missing: Vec<PathBuf>,
+    /// 哈希不一致的文件
+    pub corrupted: Vec<PathBuf>,
+}
+
+impl VersionIntegrityReport {
+    fn check(&mut self, path: PathBuf, sha1: Option<&str>, verify_hashes: bool) {
+        if !path.is_file() {
+            self.missing.push(path);
+        } else if let (true, Some(sha1)) = (verify_hashes, sha1) {
+            if !verify_sha1(&path, sha1) {
+                self.corrupted.push(path);
+            }
+        }
+    }
+
+    fn check_artifact(&mut self, path: PathBuf, artifact: &ArtifactFile, verify_hashes: bool) {
+        self.check(path, artifact.sha1.as_deref(), verify_hashes);
+    }
+}
+
+/// 仅依据本地文件检查版本是否完整，不访问网络
+///
+/// 检查客户端 jar、当前系统需要的库与原生库、资源索引及其中的资源文件。
+/// `verify_hashes` 为 true 时还会校验文件的 SHA-1。
+pub fn check_version_integrity(
+    game_path: &Path,
+    version_name: &str,
+    verify_hashes: bool,
+) -> Result<VersionIntegrityReport, String> {
+    let resolved = resolve_client_json(game_path, version_name)?;
+    let json = &resolved.json;
+    let mut report = VersionIntegrityReport {
+        version: version_name.to_string(),
+        ..Default::default()
+    };
+
+    let client_sha1 = json
+        .downloads
+        .as_ref()
+        .and_then(|d| d.client.as_ref())
+        .and_then(|c| c.sha1.as_deref());
+    report.check(resolved.jar_path.clone(), client_sha1, verify_hashes);
+
+    let libraries_dir = game_path.join("libraries");
+    let features = HashMap::new();
+    for library in json.libraries.iter().flatten() {
+        if !rules_allow(library.rules.as_deref().unwrap_or_default(), &features) {
+            continue;
+        }
+        if let Some(native) = native_artifact(library) {
+            if let Some(path) = &native.path {
+                report.check_artifact(libraries_dir.join(path), native, verify_hashes);
+            }
+        }
+        let artifact = library.downloads.as_ref().and_then(|d| d.artifact.as_ref());
+        // 只有原生库分类器的旧式条目没有主文件
+        if artifact.is_none() && library.natives.is_some() {
+            continue;
+        }
+        if let Some(path) = library_path(library) {
+            let sha1 = artifact.and_then(|a| a.sha1.as_deref());
+            report.check(libraries_dir.join(path), sha1, verify_hashes);
+        }
+    }
+
+    let assets_dir = game_path.join("assets");
+    if let Some(asset_index) = &json.asset_index {
+        let id = asset_index
+            .id
+            .as_deref()
+            .or(json.assets.as_deref())
+            .unwrap_or("legacy");
+        let index_path = assets_dir.join("indexes").join(format!("{}.json", id));
+        report.check(
+            index_path.clone(),
+            asset_index.sha1.as_deref(),
+            verify_hashes,
+        );
+        if let Ok(content) = fs::read_to_string(&index_path) {
+            let index: GameAssetIndex = serde_json::from_str(&content)
+                .map_err(|e| format!("解析资源索引失败 {}: {}", index_path.display(), e))?;
+            for asset in index.objects.values() {
+                if asset.hash.len() < 2 {
+                    continue;
+                }
+                let path = assets_dir
+                    .join("objects")
+                    .join(&asset.hash[..2])
+                    .join(&asset.hash);
+                report.check(path, Some(&asset.hash), verify_hashes);
+            }
+        }
+    }
+
+    report.complete = report.missing.is_empty() && report.corrupted.is_empty();
+    Ok(report)
+}
+
+/// 列出文件完整、无需联网即可启动的版本
+pub async fn get_launchable_versions(game_path: PathBuf) -> Result<Vec<String>, String> {
+    let versions = get_local_versions(game_path.clone()).await?;
+    // 逐个检查文件是否存在会产生大量文件系统调用，放到阻塞线程执行
+    tokio::task::spawn_blocking(move || {
+        versions
+            .into_iter()
+            .filter(|v| {
+                check_version_integrity(&game_path, &v.name, false)
+                    .map(|r| r.complete)
+                    .unwrap_or(false)
+            })
+            .map(|v| v.name)
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("检查版本完整性失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_version_integrity() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let version_dir = game_path.join("versions").join("1.0");
+        fs::create_dir_all(&version_dir).unwrap();
+        fs::write(
+            version_dir.join("1.0.json"),
+            r#"{
+                "id": "1.0",
+                "libraries": [
+                    {
+                        "name": "com.example:lib:1.0",
+                        "downloads": {
+                            "artifact": {
+                                "path": "com/example/lib/1.0/lib-1.0.jar",
+                                "sha1": "a9993e364706816aba3e25717850c26c9cd0d89d"
+                            }
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = check_version_integrity(game_path, "1.0", false).unwrap();
+        assert!(!report.complete);
+        assert_eq!(report.missing.len(), 2);
+
+        fs::write(version_dir.join("1.0.jar"), b"jar").unwrap();
+        let lib = game_path.join("libraries/com/example/lib/1.0/lib-1.0.jar");
+        fs::create_dir_all(lib.parent().unwrap()).unwrap();
+        fs::write(&lib, b"abd").unwrap();
+        assert!(
+            check_version_integrity(game_path, "1.0", false)
+                .unwrap()
+                .complete
+        );
+
+        let report = check_version_integrity(game_path, "1.0", true).unwrap();
+        assert_eq!(report.corrupted, vec![lib.clone()]);
+        fs::write(&lib, b"abc").unwrap();
+        assert!(
+            check_version_integrity(game_path, "1.0", true)
+                .unwrap()
+                .complete
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub mod integrity;
 pub mod model;
 pub mod util;
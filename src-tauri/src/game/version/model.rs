@@ -32,6 +32,26 @@ pub struct LocalVersionInfo {
     pub info: ClientJson,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModLoaderType {
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+    OptiFine,
+    LiteLoader,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModLoaderInfo {
+    /// 加载器类型
+    pub loader: ModLoaderType,
+    /// 加载器版本号
+    pub version: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct VersionInfo {
     /// 版本号
@@ -127,13 +147,14 @@ pub struct Arguments {
 #[serde(untagged)]
 pub enum Arg {
     Value(String),
-    DividedValue {
-        value: Vec<String>,
-    },
+    // 需在 DividedValue 之前，否则带规则的条目会被当作无条件参数解析
     Conditional {
         rules: Vec<Rule>,
         value: ValueOrList,
     },
+    DividedValue {
+        value: Vec<String>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,6 +1,10 @@
-use crate::game::version::model::{ClientJson, LocalVersionInfo};
+use crate::game::version::model::{
+    Arg, Arguments, ArtifactFile, ClientJson, Library, LocalVersionInfo, ModLoaderInfo,
+    ModLoaderType, OsRule, Rule,
+};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub async fn get_local_versions(game_path: PathBuf) -> Result<Vec<LocalVersionInfo>, String> {
     let versions_dir = game_path.join("versions");
@@ -49,3 +53,428 @@ pub async fn get_local_versions(game_path: PathBuf) -> Result<Vec<LocalVersionIn
 
     Ok(versions)
 }
+
+/// 版本 JSON 是否已存在
+pub fn version_installed(game_path: &Path, version_name: &str) -> bool {
+    game_path
+        .join("versions")
+        .join(version_name)
+        .join(format!("{}.json", version_name))
+        .is_file()
+}
+
+/// 读取 `versions/<name>/<name>.json`
+pub fn load_client_json(game_path: &Path, name: &str) -> Result<ClientJson, String> {
+    let json_path = game_path
+        .join("versions")
+        .join(name)
+        .join(format!("{}.json", name));
+    let content = fs::read_to_string(&json_path)
+        .map_err(|e| format!("读取文件失败 {}: {}", json_path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析 JSON 失败 {}: {}", json_path.display(), e))
+}
+
+/// 合并 `inheritsFrom` 后的版本信息
+#[derive(Debug)]
+pub struct ResolvedVersion {
+    /// 合并后的版本 JSON
+    pub json: ClientJson,
+    /// 客户端 jar 的路径
+    pub jar_path: PathBuf,
+    /// 继承链，从自身到最顶层的父版本
+    pub chain: Vec<String>,
+}
+
+/// 继承链的最大深度，防止循环继承
+const MAX_INHERITANCE_DEPTH: usize = 8;
+
+/// 读取版本 JSON 并沿 `inheritsFrom` 合并父版本
+pub fn resolve_client_json(game_path: &Path, name: &str) -> Result<ResolvedVersion, String> {
+    let mut chain = vec![name.to_string()];
+    let mut json = load_client_json(game_path, name)?;
+    while let Some(parent_name) = json.inherits_from.take() {
+        if chain.contains(&parent_name) || chain.len() >= MAX_INHERITANCE_DEPTH {
+            return Err(format!("版本 {} 的继承关系存在循环或层级过深", name));
+        }
+        let parent = load_client_json(game_path, &parent_name)
+            .map_err(|e| format!("缺少父版本 {}: {}", parent_name, e))?;
+        chain.push(parent_name);
+        json = merge_client_json(json, parent);
+    }
+    // 优先使用自身目录下的 jar，没有时使用父版本的 jar
+    let jar_path = chain
+        .iter()
+        .map(|v| {
+            game_path
+                .join("versions")
+                .join(v)
+                .join(format!("{}.jar", v))
+        })
+        .find(|p| p.exists())
+        .unwrap_or_else(|| {
+            let root = chain.last().unwrap();
+            game_path
+                .join("versions")
+                .join(root)
+                .join(format!("{}.jar", root))
+        });
+    Ok(ResolvedVersion {
+        json,
+        jar_path,
+        chain,
+    })
+}
+
+/// 将父版本合并到子版本中，子版本的字段优先，库与参数列表拼接
+fn merge_client_json(child: ClientJson, parent: ClientJson) -> ClientJson {
+    let libraries = match (child.libraries, parent.libraries) {
+        (Some(mut c), Some(p)) => {
+            c.extend(p);
+            Some(c)
+        }
+        (c, p) => c.or(p),
+    };
+    let arguments = match (child.arguments, parent.arguments) {
+        (Some(c), Some(p)) => Some(Arguments {
+            game: concat_args(p.game, c.game),
+            jvm: concat_args(p.jvm, c.jvm),
+        }),
+        (c, p) => c.or(p),
+    };
+    ClientJson {
+        id: child.id.or(parent.id),
+        inherits_from: parent.inherits_from,
+        release_time: child.release_time.or(parent.release_time),
+        time: child.time.or(parent.time),
+        version_type: child.version_type.or(parent.version_type),
+        minimum_launcher_version: child
+            .minimum_launcher_version
+            .or(parent.minimum_launcher_version),
+        main_class: child.main_class.or(parent.main_class),
+        java_version: child.java_version.or(parent.java_version),
+        compliance_level: child.compliance_level.or(parent.compliance_level),
+        arguments,
+        minecraft_arguments: child.minecraft_arguments.or(parent.minecraft_arguments),
+        asset_index: child.asset_index.or(parent.asset_index),
+        assets: child.assets.or(parent.assets),
+        downloads: child.downloads.or(parent.downloads),
+        libraries,
+        logging: child.logging.or(parent.logging),
+    }
+}
+
+fn concat_args(parent: Option<Vec<Arg>>, child: Option<Vec<Arg>>) -> Option<Vec<Arg>> {
+    match (parent, child) {
+        (Some(mut p), Some(c)) => {
+            p.extend(c);
+            Some(p)
+        }
+        (p, c) => p.or(c),
+    }
+}
+
+/// 当前系统在版本 JSON 规则中的名称
+pub fn current_os_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "osx"
+    } else {
+        "linux"
+    }
+}
+
+fn os_rule_matches(os: &OsRule) -> bool {
+    if let Some(name) = &os.name {
+        if name != current_os_name() {
+            return false;
+        }
+    }
+    if let Some(arch) = &os.arch {
+        // 规则中只出现过 "x86"，表示 32 位系统
+        let is_x86 = cfg!(target_arch = "x86");
+        if (arch == "x86") != is_x86 {
+            return false;
+        }
+    }
+    // os.version 为针对系统版本号的正则，目前只用于区分旧版 Windows，这里视为匹配
+    true
+}
+
+/// 判断规则列表是否允许当前环境，`features` 为启动时启用的功能
+pub fn rules_allow(rules: &[Rule], features: &HashMap<String, bool>) -> bool {
+    if rules.is_empty() {
+        return true;
+    }
+    let mut allowed = false;
+    for rule in rules {
+        let os_matches = rule.os.as_ref().is_none_or(os_rule_matches);
+        let features_match = rule.features.as_ref().is_none_or(|required| {
+            required
+                .iter()
+                .all(|(k, v)| features.get(k).copied().unwrap_or(false) == *v)
+        });
+        if os_matches && features_match {
+            allowed = rule.action.as_deref() != Some("disallow");
+        }
+    }
+    allowed
+}
+
+/// 将 Maven 坐标转换为仓库中的相对路径
+///
+/// 支持 `group:artifact:version[:classifier][@extension]`。
+pub fn maven_path(name: &str) -> Option<PathBuf> {
+    let (coords, extension) = match name.split_once('@') {
+        Some((coords, ext)) => (coords, ext),
+        None => (name, "jar"),
+    };
+    let parts: Vec<&str> = coords.split(':').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+    let (group, artifact, version) = (parts[0], parts[1], parts[2]);
+    let file_name = match parts.get(3) {
+        Some(classifier) => format!("{}-{}-{}.{}", artifact, version, classifier, extension),
+        None => format!("{}-{}.{}", artifact, version, extension),
+    };
+    let mut path = PathBuf::new();
+    for segment in group.split('.') {
+        path.push(segment);
+    }
+    path.push(artifact);
+    path.push(version);
+    path.push(file_name);
+    Some(path)
+}
+
+/// 库在 libraries 目录下的相对路径
+pub fn library_path(library: &Library) -> Option<PathBuf> {
+    library
+        .downloads
+        .as_ref()
+        .and_then(|d| d.artifact.as_ref())
+        .and_then(|a| a.path.clone())
+        .or_else(|| library.name.as_deref().and_then(maven_path))
+}
+
+/// 旧版本原生库在当前系统下的分类器文件
+pub fn native_artifact(library: &Library) -> Option<&ArtifactFile> {
+    let classifier = library.natives.as_ref()?.get(current_os_name())?;
+    let bits = if cfg!(target_pointer_width = "64") {
+        "64"
+    } else {
+        "32"
+    };
+    let classifier = classifier.replace("${arch}", bits);
+    library
+        .downloads
+        .as_ref()?
+        .classifiers
+        .as_ref()?
+        .get(&classifier)
+}
+
+/// 游戏参数中紧跟在 `flag` 之后的值
+fn game_arg_value<'a>(json: &'a ClientJson, flag: &str) -> Option<&'a str> {
+    let mut values: Vec<&str> = Vec::new();
+    if let Some(game) = json.arguments.as_ref().and_then(|a| a.game.as_ref()) {
+        for arg in game {
+            match arg {
+                Arg::Value(v) => values.push(v),
+                Arg::DividedValue { value } => values.extend(value.iter().map(|v| v.as_str())),
+                Arg::Conditional { .. } => {}
+            }
+        }
+    }
+    if let Some(legacy) = &json.minecraft_arguments {
+        values.extend(legacy.split_whitespace());
+    }
+    values
+        .iter()
+        .position(|v| *v == flag)
+        .and_then(|i| values.get(i + 1).copied())
+}
+
+/// 根据库列表与启动参数识别版本使用的模组加载器
+pub fn detect_mod_loaders(json: &ClientJson) -> Vec<ModLoaderInfo> {
+    let mut loaders: Vec<ModLoaderInfo> = Vec::new();
+    let mut push = |loader: ModLoaderType, version: &str| {
+        if !loaders.iter().any(|l| l.loader == loader) {
+            loaders.push(ModLoaderInfo {
+                loader,
+                version: version.to_string(),
+            });
+        }
+    };
+    if let Some(version) = game_arg_value(json, "--fml.neoForgeVersion") {
+        push(ModLoaderType::NeoForge, version);
+    }
+    if let Some(version) = game_arg_value(json, "--fml.forgeVersion") {
+        push(ModLoaderType::Forge, version);
+    }
+    for name in json
+        .libraries
+        .iter()
+        .flatten()
+        .filter_map(|l| l.name.as_deref())
+    {
+        let parts: Vec<&str> = name.split(':').collect();
+        if parts.len() < 3 {
+            continue;
+        }
+        let version = parts[2];
+        // Forge 系的版本号形如 "1.20.1-47.2.0"，取游戏版本之后的部分
+        let forge_version = || version.split('-').nth(1).unwrap_or(version);
+        match (parts[0], parts[1]) {
+            ("net.fabricmc", "fabric-loader") => push(ModLoaderType::Fabric, version),
+            ("org.quiltmc", "quilt-loader") => push(ModLoaderType::Quilt, version),
+            ("net.neoforged", "neoforge") => push(ModLoaderType::NeoForge, version),
+            ("net.neoforged", "forge") => push(ModLoaderType::NeoForge, forge_version()),
+            ("net.minecraftforge", "forge" | "fmlloader") => {
+                push(ModLoaderType::Forge, forge_version())
+            }
+            ("optifine", "OptiFine") => push(
+                ModLoaderType::OptiFine,
+                version.split_once('_').map_or(version, |(_, v)| v),
+            ),
+            ("com.mumfrey", "liteloader") => push(ModLoaderType::LiteLoader, version),
+            _ => {}
+        }
+    }
+    loaders
+}
+
+/// 识别版本对应的游戏本体版本号
+pub fn detect_game_version(resolved: &ResolvedVersion) -> Option<String> {
+    let json = &resolved.json;
+    if let Some(version) = game_arg_value(json, "--fml.mcVersion") {
+        return Some(version.to_string());
+    }
+    for name in json
+        .libraries
+        .iter()
+        .flatten()
+        .filter_map(|l| l.name.as_deref())
+    {
+        let parts: Vec<&str> = name.split(':').collect();
+        if parts.len() >= 3
+            && matches!(
+                (parts[0], parts[1]),
+                ("net.fabricmc", "intermediary") | ("org.quiltmc", "hashed")
+            )
+        {
+            return Some(parts[2].to_string());
+        }
+    }
+    resolved.chain.last().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maven_path() {
+        assert_eq!(
+            maven_path("net.fabricmc:fabric-loader:0.16.14"),
+            Some(PathBuf::from(
+                "net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar"
+            ))
+        );
+        assert_eq!(
+            maven_path("org.lwjgl:lwjgl:3.3.3:natives-linux"),
+            Some(PathBuf::from(
+                "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar"
+            ))
+        );
+        assert_eq!(
+            maven_path("de.oceanlabs.mcp:mcp_config:1.20.1@zip"),
+            Some(PathBuf::from(
+                "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1.zip"
+            ))
+        );
+        assert_eq!(maven_path("invalid"), None);
+    }
+
+    #[test]
+    fn test_detect_mod_loaders() {
+        let json: ClientJson = serde_json::from_str(
+            r#"{
+                "arguments": {
+                    "game": ["--fml.forgeVersion", "47.2.0", "--fml.mcVersion", "1.20.1"]
+                },
+                "libraries": [
+                    { "name": "optifine:OptiFine:1.20.1_HD_U_I6" },
+                    { "name": "net.minecraftforge:fmlloader:1.20.1-47.2.0" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let loaders = detect_mod_loaders(&json);
+        assert_eq!(loaders.len(), 2);
+        assert_eq!(loaders[0].loader, ModLoaderType::Forge);
+        assert_eq!(loaders[0].version, "47.2.0");
+        assert_eq!(loaders[1].loader, ModLoaderType::OptiFine);
+        assert_eq!(loaders[1].version, "HD_U_I6");
+
+        let json: ClientJson = serde_json::from_str(
+            r#"{ "libraries": [{ "name": "net.fabricmc:fabric-loader:0.16.14" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            detect_mod_loaders(&json),
+            vec![ModLoaderInfo {
+                loader: ModLoaderType::Fabric,
+                version: "0.16.14".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rules_allow() {
+        let features = HashMap::new();
+        assert!(rules_allow(&[], &features));
+
+        let other_os = if current_os_name() == "windows" {
+            "linux"
+        } else {
+            "windows"
+        };
+        let allow_other = Rule {
+            action: Some("allow".to_string()),
+            os: Some(OsRule {
+                name: Some(other_os.to_string()),
+                ..Default::default()
+            }),
+            features: None,
+        };
+        assert!(!rules_allow(std::slice::from_ref(&allow_other), &features));
+
+        let rules = [
+            Rule {
+                action: Some("allow".to_string()),
+                ..Default::default()
+            },
+            Rule {
+                action: Some("disallow".to_string()),
+                os: Some(OsRule {
+                    name: Some(current_os_name().to_string()),
+                    ..Default::default()
+                }),
+                features: None,
+            },
+        ];
+        assert!(!rules_allow(&rules, &features));
+
+        let demo = Rule {
+            action: Some("allow".to_string()),
+            os: None,
+            features: Some(HashMap::from([("is_demo_user".to_string(), true)])),
+        };
+        assert!(!rules_allow(std::slice::from_ref(&demo), &features));
+        let features = HashMap::from([("is_demo_user".to_string(), true)]);
+        assert!(rules_allow(std::slice::from_ref(&demo), &features));
+    }
+}
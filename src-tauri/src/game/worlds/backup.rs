@@ -0,0 +1,300 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::model::{WorldBackup, WorldInfo};
+use super::util::{read_world, world_dir, SAVES_DIR, SESSION_LOCK};
+use crate::modpack::util::safe_relative_path;
+
+/// 与游戏的“备份存档”功能使用相同的目录与命名
+const BACKUPS_DIR: &str = "backups";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+/// 按 `TIMESTAMP_FORMAT` 格式化后的长度
+const TIMESTAMP_LEN: usize = "2024-01-01_00-00-00".len();
+
+/// 同一秒内的多个备份在时间后追加 `-<序号>`，从 2 开始
+fn backup_name(timestamp: &str, sequence: u32, folder_name: &str) -> String {
+    if sequence > 1 {
+        format!("{}-{}_{}.zip", timestamp, sequence, folder_name)
+    } else {
+        format!("{}_{}.zip", timestamp, folder_name)
+    }
+}
+
+/// 解析 `<时间>[-<序号>]_<存档>.zip` 形式的备份文件名，返回备份时间与序号
+fn parse_backup_name(file_name: &str, folder_name: &str) -> Option<(i64, u32)> {
+    let stamp = file_name
+        .strip_suffix(".zip")?
+        .strip_suffix(folder_name)?
+        .strip_suffix('_')?;
+    let (timestamp, sequence) = match stamp.get(..TIMESTAMP_LEN).zip(stamp.get(TIMESTAMP_LEN..)) {
+        Some((timestamp, "")) => (timestamp, 1),
+        Some((timestamp, rest)) => (timestamp, rest.strip_prefix('-')?.parse().ok()?),
+        None => return None,
+    };
+    let time = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let created = Local
+        .from_local_datetime(&time)
+        .earliest()?
+        .timestamp_millis();
+    Some((created, sequence))
+}
+
+fn add_dir_to_zip(
+    writer: &mut ZipWriter<File>,
+    dir: &Path,
+    prefix: &str,
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("读取 {} 失败: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if file_name == SESSION_LOCK {
+            continue;
+        }
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, file_name);
+        if path.is_dir() {
+            writer
+                .add_directory(name.as_str(), options)
+                .map_err(|e| e.to_string())?;
+            add_dir_to_zip(writer, &path, &name, options)?;
+        } else {
+            writer
+                .start_file(name.as_str(), options)
+                .map_err(|e| e.to_string())?;
+            let mut file =
+                File::open(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+            io::copy(&mut file, writer)
+                .map_err(|e| format!("写入备份失败 {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 列出存档的备份，最新的在前
+pub fn list_backups(game_dir: &Path, folder_name: &str) -> Vec<WorldBackup> {
+    let Ok(entries) = fs::read_dir(game_dir.join(BACKUPS_DIR)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<(u32, WorldBackup)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let (created, sequence) = parse_backup_name(&file_name, folder_name)?;
+            let size = entry.metadata().ok().filter(|m| m.is_file())?.len();
+            Some((
+                sequence,
+                WorldBackup {
+                    path: entry.path(),
+                    file_name,
+                    world: folder_name.to_string(),
+                    created,
+                    size,
+                },
+            ))
+        })
+        .collect();
+    backups.sort_by_key(|(sequence, b)| std::cmp::Reverse((b.created, *sequence)));
+    backups.into_iter().map(|(_, backup)| backup).collect()
+}
+
+/// 只保留最新的 `keep` 个备份，为 0 时不删除，返回删除的数量
+pub fn prune_backups(game_dir: &Path, folder_name: &str, keep: usize) -> Result<usize, String> {
+    if keep == 0 {
+        return Ok(0);
+    }
+    let backups = list_backups(game_dir, folder_name);
+    let mut removed = 0;
+    for backup in backups.iter().skip(keep) {
+        fs::remove_file(&backup.path)
+            .map_err(|e| format!("删除备份 {} 失败: {}", backup.file_name, e))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// 将存档压缩为 `backups/<时间>_<存档>.zip`，之后按 `keep` 清理旧的备份
+///
+/// 同一秒内已有备份时在时间后追加序号。
+pub fn backup_world(
+    game_dir: &Path,
+    folder_name: &str,
+    keep: usize,
+) -> Result<WorldBackup, String> {
+    let dir = world_dir(game_dir, folder_name)?;
+    let backups_dir = game_dir.join(BACKUPS_DIR);
+    fs::create_dir_all(&backups_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let now = Local::now();
+    let timestamp = now.format(TIMESTAMP_FORMAT).to_string();
+    let mut sequence = 1;
+    let (file_name, path, file) = loop {
+        let file_name = backup_name(&timestamp, sequence, folder_name);
+        let path = backups_dir.join(&file_name);
+        match File::options().write(true).create_new(true).open(&path) {
+            Ok(file) => break (file_name, path, file),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => sequence += 1,
+            Err(e) => return Err(format!("创建备份失败: {}", e)),
+        }
+    };
+
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let result = add_dir_to_zip(&mut writer, &dir, folder_name, options)
+        .and_then(|_| writer.finish().map_err(|e| format!("写入备份失败: {}", e)));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+    prune_backups(game_dir, folder_name, keep)?;
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+    Ok(WorldBackup {
+        file_name,
+        path,
+        world: folder_name.to_string(),
+        // 与文件名中的时间精度一致
+        created: now.timestamp() * 1000,
+        size,
+    })
+}
+
+/// 找到压缩包中 level.dat 所在的目录，作为存档的根目录
+fn find_world_root<R: io::Read + io::Seek>(archive: &mut ZipArchive<R>) -> Option<PathBuf> {
+    archive
+        .file_names()
+        .filter_map(safe_relative_path)
+        .filter(|path| path.file_name().is_some_and(|n| n == "level.dat"))
+        .min_by_key(|path| path.components().count())
+        .map(|path| path.parent().map(Path::to_path_buf).unwrap_or_default())
+}
+
+/// 从备份恢复存档到 `saves/<folder_name>`，已存在的存档会先移动到回收站
+pub fn restore_backup(
+    game_dir: &Path,
+    backup: &Path,
+    folder_name: &str,
+) -> Result<WorldInfo, String> {
+    if folder_name.is_empty() || folder_name.contains(['/', '\\']) || folder_name == ".." {
+        return Err(format!("无效的存档名称: {}", folder_name));
+    }
+    let file = File::open(backup).map_err(|e| format!("打开备份失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("读取备份失败: {}", e))?;
+    let root = find_world_root(&mut archive).ok_or_else(|| "备份中没有 level.dat".to_string())?;
+
+    let saves = game_dir.join(SAVES_DIR);
+    let temp = saves.join(format!(".{}.restore-{}", folder_name, uuid::Uuid::new_v4()));
+    let result = (|| {
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+            let Some(relative) = entry
+                .enclosed_name()
+                .and_then(|p| p.strip_prefix(&root).ok().map(Path::to_path_buf))
+                .filter(|p| !p.as_os_str().is_empty())
+            else {
+                continue;
+            };
+            let out_path = temp.join(relative);
+            if entry.is_dir() {
+                fs::create_dir_all(&out_path).map_err(|e| format!("创建目录失败: {}", e))?;
+                continue;
+            }
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            let mut out = File::create(&out_path)
+                .map_err(|e| format!("写入 {} 失败: {}", out_path.display(), e))?;
+            io::copy(&mut entry, &mut out)
+                .map_err(|e| format!("写入 {} 失败: {}", out_path.display(), e))?;
+        }
+        let target = saves.join(folder_name);
+        if target.exists() {
+            trash::delete(&target).map_err(|e| format!("移动原存档到回收站失败: {}", e))?;
+        }
+        fs::rename(&temp, &target).map_err(|e| format!("恢复存档失败: {}", e))?;
+        Ok(read_world(&target))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::worlds::util::tests::{create_world, sample_level};
+
+    #[test]
+    fn test_parse_backup_name() {
+        assert!(parse_backup_name("2024-05-01_12-30-00_New World.zip", "New World").is_some());
+        assert!(parse_backup_name("2024-05-01_12-30-00_New World.zip", "World").is_none());
+        assert!(parse_backup_name("2024-05-01_12-30-00_a_b.zip", "a_b").is_some());
+        assert!(parse_backup_name("latest_New World.zip", "New World").is_none());
+        assert!(parse_backup_name("New World.zip", "New World").is_none());
+        let (created, sequence) =
+            parse_backup_name("2024-05-01_12-30-00-3_World.zip", "World").unwrap();
+        assert_eq!(sequence, 3);
+        assert_eq!(
+            Some(created),
+            parse_backup_name("2024-05-01_12-30-00_World.zip", "World").map(|(c, _)| c)
+        );
+        assert!(parse_backup_name("2024-05-01_12-30-00-x_World.zip", "World").is_none());
+    }
+
+    #[test]
+    fn test_backup_same_second() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_dir = temp.path();
+        create_world(game_dir, "World", &sample_level("World"));
+        let first = backup_world(game_dir, "World", 0).unwrap();
+        let timestamp = &first.file_name[..TIMESTAMP_LEN];
+        // 模拟同一秒内已有的备份
+        for sequence in [1, 2] {
+            let name = backup_name(timestamp, sequence, "World");
+            if !game_dir.join(BACKUPS_DIR).join(&name).exists() {
+                fs::write(game_dir.join(BACKUPS_DIR).join(name), b"").unwrap();
+            }
+        }
+        let second = backup_world(game_dir, "World", 0).unwrap();
+        assert_ne!(second.file_name, first.file_name);
+        let backups = list_backups(game_dir, "World");
+        assert_eq!(backups[0].file_name, second.file_name);
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_dir = temp.path();
+        create_world(game_dir, "World", &sample_level("Backed Up"));
+        let backups_dir = game_dir.join(BACKUPS_DIR);
+        fs::create_dir_all(&backups_dir).unwrap();
+        for old in ["2020-01-01_00-00-00", "2021-01-01_00-00-00"] {
+            fs::write(backups_dir.join(format!("{}_World.zip", old)), b"old").unwrap();
+        }
+        fs::write(backups_dir.join("2020-01-01_00-00-00_Other.zip"), b"other").unwrap();
+
+        let backup = backup_world(game_dir, "World", 2).unwrap();
+        let backups = list_backups(game_dir, "World");
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0], backup);
+        assert!(backups[1].file_name.starts_with("2021"));
+        assert!(backups_dir.join("2020-01-01_00-00-00_Other.zip").exists());
+
+        let mut archive = ZipArchive::new(File::open(&backup.path).unwrap()).unwrap();
+        assert!(archive.by_name("World/level.dat").is_ok());
+        assert!(archive.by_name("World/region/r.0.0.mca").is_ok());
+        assert!(archive.by_name("World/session.lock").is_err());
+
+        // 恢复为另一个存档，原存档不受影响
+        let restored = restore_backup(game_dir, &backup.path, "Restored").unwrap();
+        assert_eq!(restored.folder_name, "Restored");
+        assert_eq!(restored.level_name, "Backed Up");
+        assert!(restored.path.join("region/r.0.0.mca").is_file());
+        assert!(game_dir.join("saves/World/level.dat").is_file());
+        assert!(restore_backup(game_dir, &backup.path, "../escape").is_err());
+    }
+}
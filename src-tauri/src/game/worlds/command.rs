@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use crate::config::saveload::get_launcher_config;
+use crate::game::profile::util::get_profile_game_dir;
+use crate::game::worlds::{
+    backup::{backup_world, list_backups, restore_backup},
+    model::{WorldBackup, WorldInfo},
+    util::{delete_world, duplicate_world, list_worlds, read_world_icon},
+};
+
+/// 列出配置游戏目录中的存档
+#[tauri::command]
+pub async fn list_worlds_command(
+    game_path: PathBuf,
+    profile_id: String,
+) -> Result<Vec<WorldInfo>, String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || list_worlds(&game_dir))
+        .await
+        .map_err(|e| format!("读取存档失败: {}", e))
+}
+
+/// 读取存档图标，返回 data URL
+#[tauri::command]
+pub fn get_world_icon_command(path: PathBuf) -> Option<String> {
+    read_world_icon(&path)
+}
+
+/// 备份存档，并按启动器设置清理旧的备份
+#[tauri::command]
+pub async fn backup_world_command(
+    game_path: PathBuf,
+    profile_id: String,
+    world: String,
+) -> Result<WorldBackup, String> {
+    let keep = get_launcher_config().await?.max_world_backups;
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || backup_world(&game_dir, &world, keep))
+        .await
+        .map_err(|e| format!("备份存档失败: {}", e))?
+}
+
+/// 列出存档的备份，最新的在前
+#[tauri::command]
+pub fn list_world_backups_command(
+    game_path: PathBuf,
+    profile_id: String,
+    world: String,
+) -> Result<Vec<WorldBackup>, String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    Ok(list_backups(&game_dir, &world))
+}
+
+/// 从备份恢复存档，同名存档会先移动到回收站
+#[tauri::command]
+pub async fn restore_world_backup_command(
+    game_path: PathBuf,
+    profile_id: String,
+    backup: PathBuf,
+    world: String,
+) -> Result<WorldInfo, String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || restore_backup(&game_dir, &backup, &world))
+        .await
+        .map_err(|e| format!("恢复存档失败: {}", e))?
+}
+
+/// 复制存档并重命名
+#[tauri::command]
+pub async fn duplicate_world_command(
+    game_path: PathBuf,
+    profile_id: String,
+    world: String,
+    name: String,
+) -> Result<WorldInfo, String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    tokio::task::spawn_blocking(move || duplicate_world(&game_dir, &world, &name))
+        .await
+        .map_err(|e| format!("复制存档失败: {}", e))?
+}
+
+/// 将存档移动到回收站
+#[tauri::command]
+pub fn delete_world_command(
+    game_path: PathBuf,
+    profile_id: String,
+    world: String,
+) -> Result<(), String> {
+    let game_dir = get_profile_game_dir(&game_path, &profile_id)?;
+    delete_world(&game_dir, &world)
+}
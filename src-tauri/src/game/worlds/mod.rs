@@ -0,0 +1,4 @@
+pub mod backup;
+pub mod command;
+pub mod model;
+pub mod util;
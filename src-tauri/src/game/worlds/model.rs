@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// 游戏模式，对应 level.dat 中的 `GameType`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    pub fn from_id(id: i64) -> Option<Self> {
+        match id {
+            0 => Some(GameMode::Survival),
+            1 => Some(GameMode::Creative),
+            2 => Some(GameMode::Adventure),
+            3 => Some(GameMode::Spectator),
+            _ => None,
+        }
+    }
+}
+
+/// 难度，对应 level.dat 中的 `Difficulty`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn from_id(id: i64) -> Option<Self> {
+        match id {
+            0 => Some(Difficulty::Peaceful),
+            1 => Some(Difficulty::Easy),
+            2 => Some(Difficulty::Normal),
+            3 => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// 新版本的 `difficulty_settings` 中以名称保存
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "peaceful" => Some(Difficulty::Peaceful),
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+/// `saves` 中的存档
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldInfo {
+    /// 存档文件夹名称，用于后续的操作
+    pub folder_name: String,
+    pub path: PathBuf,
+    /// 游戏中显示的名称，读取失败时为文件夹名称
+    pub level_name: String,
+    pub game_mode: Option<GameMode>,
+    pub difficulty: Option<Difficulty>,
+    pub hardcore: bool,
+    /// 是否允许作弊
+    pub allow_commands: bool,
+    pub data_version: Option<i32>,
+    /// 最后游玩的游戏版本，如 1.20.1
+    pub version_name: Option<String>,
+    /// 最后游玩的时间，Unix 毫秒时间戳
+    pub last_played: Option<i64>,
+    pub seed: Option<i64>,
+    /// 是否包含 icon.png
+    pub has_icon: bool,
+    /// 读取 level.dat 失败的原因
+    pub error: Option<String>,
+}
+
+/// `backups` 中的存档备份
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldBackup {
+    pub file_name: String,
+    pub path: PathBuf,
+    /// 备份的存档文件夹名称
+    pub world: String,
+    /// 备份时间，Unix 毫秒时间戳
+    pub created: i64,
+    pub size: u64,
+}
@@ -0,0 +1,325 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::model::{Difficulty, GameMode, WorldInfo};
+use crate::nbt::model::{Compound, Tag};
+use crate::nbt::reader::read_nbt_file;
+use crate::nbt::writer::write_nbt_file;
+
+pub const SAVES_DIR: &str = "saves";
+const LEVEL_DAT: &str = "level.dat";
+const LEVEL_DAT_OLD: &str = "level.dat_old";
+const ICON_FILE: &str = "icon.png";
+/// 游戏运行时锁定的文件，复制与备份时跳过
+pub const SESSION_LOCK: &str = "session.lock";
+
+/// 存档文件夹的路径，拒绝包含路径分隔符的名称
+pub fn world_dir(game_dir: &Path, folder_name: &str) -> Result<PathBuf, String> {
+    let valid = !folder_name.is_empty()
+        && !matches!(folder_name, "." | "..")
+        && !folder_name.contains(['/', '\\']);
+    let dir = game_dir.join(SAVES_DIR).join(folder_name);
+    if !valid || !dir.is_dir() {
+        return Err(format!("存档不存在: {}", folder_name));
+    }
+    Ok(dir)
+}
+
+/// 从 level.dat 的根标签中读取存档信息，`info` 中的其余字段保持不变
+pub fn apply_level_data(info: &mut WorldInfo, root: &Compound) -> Result<(), String> {
+    let data = root
+        .get_compound("Data")
+        .ok_or_else(|| "level.dat 中缺少 Data".to_string())?;
+    if let Some(name) = data.get_str("LevelName").filter(|n| !n.trim().is_empty()) {
+        info.level_name = name.to_string();
+    }
+    info.game_mode = data.get_i64("GameType").and_then(GameMode::from_id);
+    // 新版本将难度与极限模式移入 difficulty_settings
+    let settings = data.get_compound("difficulty_settings");
+    info.difficulty = settings
+        .and_then(|s| s.get_str("difficulty"))
+        .and_then(Difficulty::from_name)
+        .or_else(|| data.get_i64("Difficulty").and_then(Difficulty::from_id));
+    info.hardcore = settings
+        .and_then(|s| s.get_bool("hardcore"))
+        .or_else(|| data.get_bool("hardcore"))
+        .unwrap_or(false);
+    info.allow_commands = data.get_bool("allowCommands").unwrap_or(false);
+    info.data_version = data.get_i64("DataVersion").map(|v| v as i32);
+    info.version_name = data
+        .get_compound("Version")
+        .and_then(|v| v.get_str("Name"))
+        .map(str::to_string);
+    info.last_played = data.get_i64("LastPlayed");
+    // 1.16 起种子位于 WorldGenSettings
+    info.seed = data
+        .get_compound("WorldGenSettings")
+        .and_then(|s| s.get_i64("seed"))
+        .or_else(|| data.get_i64("RandomSeed"));
+    Ok(())
+}
+
+/// 读取存档信息，level.dat 损坏时尝试 level.dat_old
+pub fn read_world(dir: &Path) -> WorldInfo {
+    let folder_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut info = WorldInfo {
+        level_name: folder_name.clone(),
+        folder_name,
+        path: dir.to_path_buf(),
+        game_mode: None,
+        difficulty: None,
+        hardcore: false,
+        allow_commands: false,
+        data_version: None,
+        version_name: None,
+        last_played: None,
+        seed: None,
+        has_icon: dir.join(ICON_FILE).is_file(),
+        error: None,
+    };
+    let result = read_nbt_file(&dir.join(LEVEL_DAT))
+        .or_else(|e| read_nbt_file(&dir.join(LEVEL_DAT_OLD)).map_err(|_| e))
+        .and_then(|(_, root)| apply_level_data(&mut info, &root));
+    if let Err(e) = result {
+        info.error = Some(e);
+    }
+    info
+}
+
+/// 列出游戏目录中的存档，最近游玩的在前
+pub fn list_worlds(game_dir: &Path) -> Vec<WorldInfo> {
+    let Ok(entries) = fs::read_dir(game_dir.join(SAVES_DIR)) else {
+        return Vec::new();
+    };
+    let mut worlds: Vec<WorldInfo> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(LEVEL_DAT).is_file() || path.join(LEVEL_DAT_OLD).is_file())
+        .map(|path| read_world(&path))
+        .collect();
+    worlds.sort_by(|a, b| {
+        b.last_played
+            .cmp(&a.last_played)
+            .then_with(|| a.folder_name.cmp(&b.folder_name))
+    });
+    worlds
+}
+
+/// 读取存档图标，返回 data URL
+pub fn read_world_icon(dir: &Path) -> Option<String> {
+    let icon = fs::read(dir.join(ICON_FILE)).ok()?;
+    Some(format!("data:image/png;base64,{}", STANDARD.encode(icon)))
+}
+
+/// 根据存档名称生成不重复的文件夹名称，与游戏的规则类似
+pub fn allocate_world_folder(game_dir: &Path, level_name: &str) -> String {
+    let base: String = level_name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '.' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let base = base.trim();
+    let base = if base.is_empty() { "World" } else { base };
+    let saves = game_dir.join(SAVES_DIR);
+    let mut folder = base.to_string();
+    let mut index = 1;
+    while saves.join(&folder).exists() {
+        folder = format!("{} ({})", base, index);
+        index += 1;
+    }
+    folder
+}
+
+/// 递归复制存档，跳过 session.lock
+pub fn copy_world_files(source: &Path, target: &Path) -> Result<(), String> {
+    fs::create_dir_all(target).map_err(|e| format!("创建目录失败: {}", e))?;
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+    for entry in entries.flatten() {
+        if entry.file_name() == SESSION_LOCK {
+            continue;
+        }
+        let path = entry.path();
+        let target = target.join(entry.file_name());
+        if path.is_dir() {
+            copy_world_files(&path, &target)?;
+        } else {
+            fs::copy(&path, &target).map_err(|e| format!("复制 {} 失败: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 修改 level.dat 中的存档名称
+pub fn rename_level(dir: &Path, level_name: &str) -> Result<(), String> {
+    let path = dir.join(LEVEL_DAT);
+    let (name, mut root) = read_nbt_file(&path)?;
+    match root.get_mut("Data") {
+        Some(Tag::Compound(data)) => data.insert("LevelName", Tag::String(level_name.to_string())),
+        _ => return Err("level.dat 中缺少 Data".to_string()),
+    }
+    write_nbt_file(&path, &name, &root, true)
+}
+
+/// 复制存档并命名为 `level_name`，返回新的存档
+pub fn duplicate_world(
+    game_dir: &Path,
+    folder_name: &str,
+    level_name: &str,
+) -> Result<WorldInfo, String> {
+    let source = world_dir(game_dir, folder_name)?;
+    let target = game_dir
+        .join(SAVES_DIR)
+        .join(allocate_world_folder(game_dir, level_name));
+    let result = copy_world_files(&source, &target).and_then(|_| rename_level(&target, level_name));
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&target);
+        return Err(e);
+    }
+    Ok(read_world(&target))
+}
+
+/// level.dat 中 `DataPacks.Disabled` 列出的数据包文件名
+pub fn disabled_datapacks(dir: &Path) -> Vec<String> {
+    let Ok((_, root)) = read_nbt_file(&dir.join(LEVEL_DAT)) else {
+        return Vec::new();
+    };
+    root.get_compound("Data")
+        .and_then(|data| data.get_compound("DataPacks"))
+        .and_then(|packs| packs.get_list("Disabled"))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|tag| tag.as_str()?.strip_prefix("file/"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 将存档移动到回收站
+pub fn delete_world(game_dir: &Path, folder_name: &str) -> Result<(), String> {
+    let dir = world_dir(game_dir, folder_name)?;
+    trash::delete(&dir).map_err(|e| format!("移动存档到回收站失败: {}", e))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// 生成 1.20.1 的 level.dat 根标签
+    pub fn sample_level(level_name: &str) -> Compound {
+        let mut version = Compound::new();
+        version.insert("Id", Tag::Int(3465));
+        version.insert("Name", Tag::String("1.20.1".to_string()));
+        let mut world_gen = Compound::new();
+        world_gen.insert("seed", Tag::Long(-4172144997902289642));
+        let mut data = Compound::new();
+        data.insert("LevelName", Tag::String(level_name.to_string()));
+        data.insert("GameType", Tag::Int(1));
+        data.insert("Difficulty", Tag::Byte(2));
+        data.insert("hardcore", Tag::Byte(0));
+        data.insert("allowCommands", Tag::Byte(1));
+        data.insert("DataVersion", Tag::Int(3465));
+        data.insert("LastPlayed", Tag::Long(1_700_000_000_000));
+        data.insert("Version", Tag::Compound(version));
+        data.insert("WorldGenSettings", Tag::Compound(world_gen));
+        let mut root = Compound::new();
+        root.insert("Data", Tag::Compound(data));
+        root
+    }
+
+    /// 在 `saves` 中创建存档
+    pub fn create_world(game_dir: &Path, folder: &str, root: &Compound) -> PathBuf {
+        let dir = game_dir.join(SAVES_DIR).join(folder);
+        fs::create_dir_all(dir.join("region")).unwrap();
+        fs::write(dir.join("region/r.0.0.mca"), b"region").unwrap();
+        fs::write(dir.join(SESSION_LOCK), b"").unwrap();
+        write_nbt_file(&dir.join(LEVEL_DAT), "", root, true).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_apply_level_data() {
+        let mut info = read_world(Path::new("/nonexistent/Test"));
+        assert!(info.error.is_some());
+        assert_eq!(info.level_name, "Test");
+
+        apply_level_data(&mut info, &sample_level("My World")).unwrap();
+        assert_eq!(info.level_name, "My World");
+        assert_eq!(info.game_mode, Some(GameMode::Creative));
+        assert_eq!(info.difficulty, Some(Difficulty::Normal));
+        assert!(!info.hardcore && info.allow_commands);
+        assert_eq!(info.data_version, Some(3465));
+        assert_eq!(info.version_name.as_deref(), Some("1.20.1"));
+        assert_eq!(info.last_played, Some(1_700_000_000_000));
+        assert_eq!(info.seed, Some(-4172144997902289642));
+
+        // 1.16 之前的种子与新版本的 difficulty_settings
+        let mut root = sample_level("Old");
+        let Some(Tag::Compound(data)) = root.get_mut("Data") else {
+            unreachable!()
+        };
+        data.insert("WorldGenSettings", Tag::Compound(Compound::new()));
+        data.insert("RandomSeed", Tag::Long(42));
+        let mut settings = Compound::new();
+        settings.insert("difficulty", Tag::String("hard".to_string()));
+        settings.insert("hardcore", Tag::Byte(1));
+        data.insert("difficulty_settings", Tag::Compound(settings));
+        apply_level_data(&mut info, &root).unwrap();
+        assert_eq!(info.seed, Some(42));
+        assert_eq!(info.difficulty, Some(Difficulty::Hard));
+        assert!(info.hardcore);
+
+        assert!(apply_level_data(&mut info, &Compound::new()).is_err());
+    }
+
+    #[test]
+    fn test_list_and_duplicate_worlds() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_dir = temp.path();
+        create_world(game_dir, "New World", &sample_level("New World"));
+        let mut older = sample_level("Older");
+        if let Some(Tag::Compound(data)) = older.get_mut("Data") {
+            data.insert("LastPlayed", Tag::Long(1_600_000_000_000));
+        }
+        fs::create_dir_all(game_dir.join("saves/not a world")).unwrap();
+
+        let mut data_packs = Compound::new();
+        data_packs.insert(
+            "Disabled",
+            Tag::List(vec![
+                Tag::String("file/old.zip".to_string()),
+                Tag::String("bundle".to_string()),
+            ]),
+        );
+        if let Some(Tag::Compound(data)) = older.get_mut("Data") {
+            data.insert("DataPacks", Tag::Compound(data_packs));
+        }
+        let older_dir = create_world(game_dir, "older", &older);
+        assert_eq!(disabled_datapacks(&older_dir), vec!["old.zip".to_string()]);
+
+        let worlds = list_worlds(game_dir);
+        assert_eq!(worlds.len(), 2);
+        assert_eq!(worlds[0].folder_name, "New World");
+        assert_eq!(worlds[1].level_name, "Older");
+        assert!(world_dir(game_dir, "../saves").is_err());
+        assert!(world_dir(game_dir, "missing").is_err());
+
+        let copy = duplicate_world(game_dir, "New World", "New World").unwrap();
+        assert_eq!(copy.folder_name, "New World (1)");
+        assert_eq!(copy.level_name, "New World");
+        assert!(copy.path.join("region/r.0.0.mca").is_file());
+        assert!(!copy.path.join(SESSION_LOCK).exists());
+
+        let copy = duplicate_world(game_dir, "older", "Copy: 2").unwrap();
+        assert_eq!(copy.folder_name, "Copy_ 2");
+        assert_eq!(copy.level_name, "Copy: 2");
+        assert_eq!(list_worlds(game_dir).len(), 4);
+    }
+}
@@ -1,24 +1,36 @@
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use tauri::ipc::Channel;
 
 use super::{
     finder::{scan_jres, verify_jre_path},
-    util::sort_jres_by_version_desc,
+    mojang::{install_mojang_runtime, list_mojang_runtimes},
+    provision::{find_jdk_package, install_jdk, uninstall_managed_jre},
+    selector::{select_java_for_profile, JavaSelection},
+    util::{get_known_jres, sort_jres_by_version_desc},
 };
 use crate::{
     config::saveload::{get_jre_config, save_jre_config},
-    jre::model::JreInfo,
+    game::{profile::util::get_profile, version::util::resolve_client_json},
+    jre::model::{JdkImageType, JdkPackage, JdkVendor, JreInfo, MojangRuntimeInfo},
+    util::{model::DownloadEvent, scheduler::DownloadEventSink},
 };
 
 #[tauri::command]
 pub async fn scan_all_jres() -> Result<Vec<JreInfo>, String> {
-    let mut jre_info = scan_jres();
+    let mut jre_info = tokio::task::spawn_blocking(scan_jres)
+        .await
+        .map_err(|e| format!("扫描 JRE 失败: {}", e))?;
     sort_jres_by_version_desc(&mut jre_info);
     Ok(jre_info)
 }
 
 #[tauri::command]
 pub async fn get_jre_info(path: String) -> Result<Option<JreInfo>, String> {
-    let jre_info = verify_jre_path(&PathBuf::from(path));
+    let jre_info = tokio::task::spawn_blocking(move || verify_jre_path(&PathBuf::from(path)))
+        .await
+        .map_err(|e| format!("检查 JRE 失败: {}", e))?;
     if jre_info.is_none() {
         Err("JRE not found".into())
     } else {
@@ -28,34 +40,19 @@ pub async fn get_jre_info(path: String) -> Result<Option<JreInfo>, String> {
 
 #[tauri::command]
 pub async fn get_all_jres() -> Result<Vec<JreInfo>, String> {
-    // 自动扫描获取的JRE列表
-    let jre_info = scan_jres();
-
-    // 从配置文件读取的JRE列表
-    let jre_config = get_jre_config().await.unwrap_or_default();
-
-    // 使用HashSet去重，以路径作为唯一标识
-    use std::collections::HashSet;
-    let mut unique_paths: HashSet<PathBuf> = HashSet::new();
-    let mut result = Vec::new();
-
-    // 先添加扫描到的JRE
-    for jre in jre_info {
-        if unique_paths.insert(jre.path.clone()) {
-            result.push(jre);
-        }
-    }
-
-    // 再添加配置文件中的JRE（如果路径不重复）
-    for jre in jre_config.jres {
-        if unique_paths.insert(jre.path.clone()) {
-            result.push(jre);
-        }
-    }
+    Ok(get_known_jres().await)
+}
 
-    // 对合并后的结果进行版本排序
-    sort_jres_by_version_desc(&mut result);
-    Ok(result)
+/// 为配置自动选择 Java，返回选择结果及原因
+#[tauri::command]
+pub async fn select_jre_for_profile(
+    game_path: String,
+    profile_id: String,
+) -> Result<JavaSelection, String> {
+    let game_path = PathBuf::from(game_path);
+    let profile = get_profile(&game_path, &profile_id)?;
+    let resolved = resolve_client_json(&game_path, &profile.version_name)?;
+    select_java_for_profile(&profile, &resolved.json, &game_path.join("libraries")).await
 }
 
 #[tauri::command]
@@ -75,3 +72,51 @@ pub async fn remove_jre(jre: JreInfo) -> Result<(), String> {
 
     Ok(())
 }
+
+/// 列出当前平台可下载的 Mojang Java 运行时
+#[tauri::command]
+pub async fn get_mojang_runtimes() -> Result<Vec<MojangRuntimeInfo>, String> {
+    list_mojang_runtimes().await
+}
+
+/// 下载 Mojang Java 运行时（如 java-runtime-gamma）并登记到 JRE 配置
+#[tauri::command]
+pub async fn install_mojang_runtime_command(
+    on_event: Channel<DownloadEvent>,
+    component: String,
+) -> Result<JreInfo, String> {
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    install_mojang_runtime(&component, Some(sink)).await
+}
+
+/// 查询第三方发行版在当前平台上最新的 Java 压缩包
+#[tauri::command]
+pub async fn get_jdk_package(
+    vendor: JdkVendor,
+    major: u32,
+    image_type: JdkImageType,
+) -> Result<JdkPackage, String> {
+    find_jdk_package(vendor, major, image_type).await
+}
+
+/// 下载第三方 JDK（Adoptium、Zulu、Liberica）并登记到 JRE 配置
+#[tauri::command]
+pub async fn install_jdk_command(
+    on_event: Channel<DownloadEvent>,
+    vendor: JdkVendor,
+    major: u32,
+    image_type: JdkImageType,
+) -> Result<JreInfo, String> {
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    install_jdk(vendor, major, image_type, Some(sink)).await
+}
+
+/// 删除由启动器安装的 Java 并从 JRE 配置中移除
+#[tauri::command]
+pub async fn uninstall_jre(path: PathBuf) -> Result<(), String> {
+    uninstall_managed_jre(&path).await
+}
@@ -1,87 +1,113 @@
 use super::model::{Architecture, JreInfo};
-use std::path::PathBuf;
+use super::mojang::runtime_java_home;
+use super::probe::probe_java;
+use super::selector::native_arch;
+use crate::config::util::get_default_game_paths;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Java 目录下的 `java` 可执行文件
+pub fn java_binary(java_home: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    return java_home.join("bin").join("java.exe");
+    #[cfg(not(target_os = "windows"))]
+    java_home.join("bin").join("java")
+}
 
-/// 检查给定路径是否为有效的 JRE 安装
-pub fn verify_jre_path(path: &PathBuf) -> Option<JreInfo> {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
+/// 将 `os.arch` 的值转换为架构
+pub fn parse_arch(arch: &str) -> Option<Architecture> {
+    match arch.to_ascii_lowercase().as_str() {
+        "amd64" | "x86_64" => Some(Architecture::X86_64),
+        "x86" | "i386" | "i586" | "i686" => Some(Architecture::X86),
+        "aarch64" | "arm64" => Some(Architecture::Arm64),
+        _ => None,
+    }
+}
+
+/// 读取 Java 目录下的 `release` 文件，键统一为大写，值保留原样并去掉引号
+fn read_release_file(java_home: &Path) -> Option<HashMap<String, String>> {
+    let content = std::fs::read_to_string(java_home.join("release")).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                Some((
+                    key.trim().to_ascii_uppercase(),
+                    value.trim().trim_matches('"').trim().to_string(),
+                ))
+            })
+            .collect(),
+    )
+}
 
+/// 是否包含 `javac`，即为 JDK 而非 JRE
+fn is_jdk(java_home: &Path) -> bool {
     #[cfg(target_os = "windows")]
-    let java_bin = path.join("bin").join("java.exe");
+    let javac = java_home.join("bin").join("javac.exe");
     #[cfg(not(target_os = "windows"))]
-    let java_bin = path.join("bin").join("java");
+    let javac = java_home.join("bin").join("javac");
+    javac.is_file()
+}
 
-    if !java_bin.exists() {
-        return None;
+/// 是否自带 JavaFX，如 Liberica Full、Zulu FX 或 Oracle Java 8
+fn has_javafx(java_home: &Path, release: Option<&HashMap<String, String>>) -> bool {
+    if release
+        .and_then(|r| r.get("MODULES"))
+        .is_some_and(|modules| modules.contains("javafx."))
+    {
+        return true;
     }
+    [
+        "lib/jfxrt.jar",
+        "lib/ext/jfxrt.jar",
+        "jre/lib/ext/jfxrt.jar",
+        "lib/javafx.properties",
+        "jmods/javafx.base.jmod",
+    ]
+    .iter()
+    .any(|p| java_home.join(p).is_file())
+}
 
-    let release_file = path.join("release");
-    if !release_file.exists() {
+/// 检查给定路径是否为有效的 JRE 安装
+///
+/// 优先读取 `release` 文件，文件不存在或缺少版本时运行 `java` 获取系统属性。
+pub fn verify_jre_path(path: &PathBuf) -> Option<JreInfo> {
+    let java_bin = java_binary(path);
+    if !java_bin.is_file() {
         return None;
     }
 
-    let file = File::open(release_file).ok()?;
-    let reader = BufReader::new(file);
-
-    let mut version = None;
-    let mut detected_arch = None;
-    let mut implementor = None;
-    let manual = Some(false);
-
-    // 从release文件获取版本和架构信息
-    for line in reader.lines().flatten() {
-        let line = line.to_lowercase();
-        if line.starts_with("implementor=") {
-            implementor = Some(
-                line.trim_start_matches("implementor=")
-                    .trim_matches('"')
-                    .trim()
-                    .to_string(),
-            );
-        }
-        if line.starts_with("java_version=") {
-            version = Some(
-                line.trim_start_matches("java_version=")
-                    .trim_matches('"')
-                    .trim()
-                    .to_string(),
-            );
-        } else if line.starts_with("os_arch=") {
-            let arch = line.trim_start_matches("os_arch=").trim_matches('"').trim();
-            detected_arch = match arch {
-                "amd64" | "x86_64" => Some(Architecture::X86_64),
-                "x86" | "i386" | "i586" | "i686" => Some(Architecture::X86),
-                "aarch64" | "arm64" => Some(Architecture::Arm64),
-                _ => None,
-            };
-        }
-    }
-
-    // 如果没有找到架构信息，使用系统默认架构
-    let arch = detected_arch.unwrap_or_else(|| {
-        #[cfg(target_pointer_width = "64")]
-        {
-            #[cfg(target_arch = "aarch64")]
-            return Architecture::Arm64;
-            #[cfg(target_arch = "x86_64")]
-            return Architecture::X86_64;
+    let release = read_release_file(path);
+    let (version, implementor, arch) = match release
+        .as_ref()
+        .and_then(|r| Some((r.get("JAVA_VERSION")?, r)))
+    {
+        Some((version, release)) => (
+            version.clone(),
+            release.get("IMPLEMENTOR").cloned(),
+            release.get("OS_ARCH").and_then(|a| parse_arch(a)),
+        ),
+        None => {
+            let properties = probe_java(&java_bin)?;
+            (
+                properties.version,
+                properties.vendor,
+                properties.arch.as_deref().and_then(parse_arch),
+            )
         }
-        #[cfg(target_pointer_width = "32")]
-        return Architecture::X86;
-
-        #[allow(unreachable_code)]
-        Architecture::Unknown
-    });
-
-    // 如果找不到版本信息，返回None
-    let version = version?;
+    };
 
     Some(JreInfo {
         path: path.to_owned(),
         version,
-        arch,
+        // 如果没有找到架构信息，使用系统默认架构
+        arch: arch.unwrap_or_else(native_arch),
         implementor,
-        manual,
+        manual: Some(false),
+        managed: None,
+        jdk: Some(is_jdk(path)),
+        javafx: Some(has_javafx(path, release.as_ref())),
     })
 }
 
@@ -94,9 +120,24 @@ fn get_potential_jre_paths() -> Vec<PathBuf> {
         paths.push(PathBuf::from(java_home));
     }
 
-    // 添加用户目录下的 .jdks 目录
+    // IntelliJ 与 Gradle 下载的 JDK
     if let Some(home_dir) = dirs::home_dir() {
         paths.push(home_dir.join(".jdks"));
+        paths.push(home_dir.join(".gradle").join("jdks"));
+    }
+
+    // PATH 中的每个 java
+    if let Some(path_var) = std::env::var_os("PATH") {
+        paths.extend(
+            java_binaries_on_path(&path_var)
+                .iter()
+                .filter_map(|bin| java_home_from_binary(bin)),
+        );
+    }
+
+    // 官方启动器自带的运行时
+    if let Some(official_dir) = get_default_game_paths().get("game.dir.official_launcher_dir") {
+        paths.extend(official_runtime_homes(&official_dir.join("runtime")));
     }
 
     // 系统特定的路径
@@ -145,27 +186,54 @@ fn get_potential_jre_paths() -> Vec<PathBuf> {
         );
     }
 
+    // SDKMAN!、asdf 与 jabba 管理的 JDK
+    #[cfg(not(target_os = "windows"))]
+    if let Some(home_dir) = dirs::home_dir() {
+        paths.push(home_dir.join(".sdkman").join("candidates").join("java"));
+        paths.push(home_dir.join(".asdf").join("installs").join("java"));
+        paths.push(home_dir.join(".jabba").join("jdk"));
+    }
+
     #[cfg(target_os = "linux")]
     {
         use std::process::Command;
 
-        // 通过 which 命令查找
-        if let Ok(output) = Command::new("which").arg("java").output() {
-            if let Ok(path) = String::from_utf8(output.stdout) {
-                if let Some(parent) = PathBuf::from(path.trim()).parent() {
-                    if let Some(jre_root) = parent.parent() {
-                        paths.push(jre_root.to_owned());
-                    }
-                }
+        // 发行版 alternatives 系统中登记的 java
+        for program in ["update-alternatives", "alternatives"] {
+            if let Ok(output) = Command::new(program).args(["--list", "java"]).output() {
+                let output = String::from_utf8_lossy(&output.stdout);
+                paths.extend(
+                    parse_alternatives(&output)
+                        .iter()
+                        .filter_map(|bin| java_home_from_binary(bin)),
+                );
             }
         }
 
         // Linux 常见安装路径
         paths.extend(
-            ["/usr/lib/jvm", "/usr/java", "/usr/local/java", "/opt/java"]
-                .iter()
-                .map(PathBuf::from),
+            [
+                "/usr/lib/jvm",
+                "/usr/lib64/jvm",
+                "/usr/java",
+                "/usr/local/java",
+                "/opt/java",
+            ]
+            .iter()
+            .map(PathBuf::from),
         );
+
+        // Flatpak 安装的 OpenJDK 扩展
+        let mut flatpak_roots = vec![PathBuf::from("/var/lib/flatpak/runtime")];
+        if let Some(data_dir) = dirs::data_dir() {
+            flatpak_roots.push(data_dir.join("flatpak").join("runtime"));
+        }
+        for root in flatpak_roots {
+            paths.extend(flatpak_jvm_dirs(&root));
+        }
+
+        // Flatpak 沙盒内挂载的 OpenJDK 扩展
+        paths.extend(flatpak_sdk_jvm_dirs(Path::new("/usr/lib/sdk")));
     }
 
     #[cfg(target_os = "macos")]
@@ -197,12 +265,151 @@ pub fn find_jre() -> Option<JreInfo> {
     scan_jres().into_iter().next()
 }
 
-/// 扫描系统中所有可用的 JRE
+/// 检查目录本身，macOS 上同时检查 `Contents/Home`
+fn verify_jre_dir(path: &Path) -> Option<JreInfo> {
+    if let Some(jre) = verify_jre_path(&path.to_path_buf()) {
+        return Some(jre);
+    }
+    #[cfg(target_os = "macos")]
+    if let Some(jre) = verify_jre_path(&path.join("Contents").join("Home")) {
+        return Some(jre);
+    }
+    None
+}
+
+/// `PATH` 中每个目录下的 `java` 可执行文件
+fn java_binaries_on_path(path_var: &std::ffi::OsStr) -> Vec<PathBuf> {
+    let binary_name = java_binary(Path::new(""))
+        .file_name()
+        .map(|n| n.to_owned())
+        .unwrap_or_default();
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&binary_name))
+        .filter(|bin| bin.is_file())
+        .collect()
+}
+
+/// 由 `PATH` 中的 `java` 得到 Java 目录
+///
+/// 解析符号链接后位于带 `release` 文件的 Java 目录中时直接使用；包装脚本、shim 等则运行
+/// `java` 读取 `java.home`，其为 Java 8 JDK 下的 `jre` 目录时返回 JDK 目录。
+fn java_home_from_binary(java_bin: &Path) -> Option<PathBuf> {
+    if let Some(home) = resolve_bin_symlink(java_bin) {
+        if home.join("release").is_file() {
+            return Some(home);
+        }
+    }
+    let home = probe_java(java_bin)?.home?;
+    match home.parent() {
+        Some(jdk) if home.file_name()? == "jre" && java_binary(jdk).is_file() => {
+            Some(jdk.to_path_buf())
+        }
+        _ => Some(home),
+    }
+}
+
+/// 解析符号链接后由 `bin/java` 得到所在目录
+fn resolve_bin_symlink(java_bin: &Path) -> Option<PathBuf> {
+    let resolved = std::fs::canonicalize(java_bin).ok()?;
+    let bin_dir = resolved.parent()?;
+    if bin_dir.file_name()? != "bin" {
+        return None;
+    }
+    bin_dir.parent().map(Path::to_path_buf)
+}
+
+/// 解析 `update-alternatives --list java` 的输出
+#[cfg(any(target_os = "linux", test))]
+fn parse_alternatives(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('/'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Flatpak 运行时目录中 OpenJDK 扩展的 `jvm` 目录，
+/// 位于 `org.freedesktop.Sdk.Extension.openjdk*/<架构>/<分支>/active/files/jvm`
+#[cfg(target_os = "linux")]
+fn flatpak_jvm_dirs(runtime_root: &Path) -> Vec<PathBuf> {
+    let subdirs = |dir: &Path| -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|e| e.path()).collect())
+            .unwrap_or_default()
+    };
+    subdirs(runtime_root)
+        .into_iter()
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("org.freedesktop.Sdk.Extension.openjdk"))
+        })
+        .flat_map(|extension| subdirs(&extension))
+        .flat_map(|arch| subdirs(&arch))
+        .map(|branch| branch.join("active").join("files").join("jvm"))
+        .filter(|jvm| jvm.is_dir())
+        .collect()
+}
+
+/// Flatpak 沙盒内 OpenJDK 扩展的 `jvm` 目录，位于 `/usr/lib/sdk/openjdk*/jvm`
+#[cfg(any(target_os = "linux", test))]
+fn flatpak_sdk_jvm_dirs(sdk_root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(sdk_root)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    dirs.retain(|p| {
+        p.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("openjdk"))
+    });
+    dirs.sort();
+    dirs.into_iter()
+        .map(|extension| extension.join("jvm"))
+        .filter(|jvm| jvm.is_dir())
+        .collect()
+}
+
+/// 官方启动器的运行时目录，结构为 `runtime/<运行时名称>/<平台>/<运行时名称>`
+fn official_runtime_homes(runtime_dir: &Path) -> Vec<PathBuf> {
+    let mut homes = Vec::new();
+    let Ok(components) = std::fs::read_dir(runtime_dir) else {
+        return homes;
+    };
+    for component in components.flatten() {
+        let Ok(platforms) = std::fs::read_dir(component.path()) else {
+            continue;
+        };
+        for platform in platforms.flatten() {
+            homes.push(runtime_java_home(
+                &platform.path().join(component.file_name()),
+            ));
+        }
+    }
+    homes
+}
+
+/// 扫描系统中所有可用的 JRE，按解析符号链接后的路径去重
 pub fn scan_jres() -> Vec<JreInfo> {
     use std::collections::HashSet;
     use std::fs;
 
-    let mut result = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    let mut check = |path: &Path| -> bool {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if seen.contains(&canonical) {
+            return true;
+        }
+        match verify_jre_dir(&canonical) {
+            Some(jre) => {
+                seen.insert(canonical);
+                result.push(jre);
+                true
+            }
+            None => false,
+        }
+    };
 
     // 获取所有可能的路径
     for base_path in get_potential_jre_paths() {
@@ -211,8 +418,7 @@ pub fn scan_jres() -> Vec<JreInfo> {
         }
 
         // 如果是目录，直接检查
-        if let Some(jre) = verify_jre_path(&base_path) {
-            result.insert(jre);
+        if check(&base_path) {
             continue;
         }
 
@@ -221,23 +427,13 @@ pub fn scan_jres() -> Vec<JreInfo> {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.is_dir() {
-                    // 在 macOS 上，JDK 在 Contents/Home 下
-                    #[cfg(target_os = "macos")]
-                    let path = if path.ends_with("JavaVirtualMachines") {
-                        path.join("Contents").join("Home")
-                    } else {
-                        path
-                    };
-
-                    if let Some(jre) = verify_jre_path(&path) {
-                        result.insert(jre);
-                    }
+                    check(&path);
                 }
             }
         }
     }
 
-    result.into_iter().collect()
+    result
 }
 
 #[cfg(test)]
@@ -269,6 +465,114 @@ mod tests {
         assert!(jre.is_none());
     }
     #[test]
+    fn test_verify_jre_path() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        std::fs::write(java_binary(dir), "").unwrap();
+        std::fs::write(
+            dir.join("release"),
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.2\"\nOS_ARCH=\"aarch64\"\nMODULES=\"java.base javafx.base\"\n",
+        )
+        .unwrap();
+
+        let jre = verify_jre_path(&dir.to_path_buf()).unwrap();
+        assert_eq!(jre.version, "21.0.2");
+        assert_eq!(jre.implementor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(jre.arch, Architecture::Arm64);
+        assert_eq!(jre.jdk, Some(false));
+        assert_eq!(jre.javafx, Some(true));
+    }
+    #[test]
+    fn test_parse_alternatives() {
+        let output = "/usr/lib/jvm/java-17-openjdk-amd64/bin/java\n/usr/lib/jvm/java-21-openjdk-amd64/bin/java\n";
+        assert_eq!(
+            parse_alternatives(output),
+            vec![
+                PathBuf::from("/usr/lib/jvm/java-17-openjdk-amd64/bin/java"),
+                PathBuf::from("/usr/lib/jvm/java-21-openjdk-amd64/bin/java"),
+            ]
+        );
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_symlinked_jres() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let home = dir.join("jvm").join("temurin-17");
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(java_binary(&home), "").unwrap();
+        std::fs::write(home.join("release"), "JAVA_VERSION=\"17.0.9\"").unwrap();
+        let path_dir = dir.join("path");
+        std::fs::create_dir_all(&path_dir).unwrap();
+        std::os::unix::fs::symlink(java_binary(&home), path_dir.join("java")).unwrap();
+
+        let binaries = java_binaries_on_path(path_dir.as_os_str());
+        assert_eq!(binaries, vec![path_dir.join("java")]);
+        assert_eq!(
+            java_home_from_binary(&binaries[0]),
+            Some(std::fs::canonicalize(&home).unwrap())
+        );
+
+        let runtime_home = dir
+            .join("runtime")
+            .join("java-runtime-gamma")
+            .join("linux")
+            .join("java-runtime-gamma");
+        std::fs::create_dir_all(&runtime_home).unwrap();
+        assert_eq!(
+            official_runtime_homes(&dir.join("runtime")),
+            vec![runtime_home]
+        );
+    }
+    #[cfg(unix)]
+    #[test]
+    fn test_java_home_from_wrapper() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let jdk = dir.join("jdk8");
+        std::fs::create_dir_all(jdk.join("jre").join("bin")).unwrap();
+        std::fs::create_dir_all(jdk.join("bin")).unwrap();
+        std::fs::write(java_binary(&jdk), "").unwrap();
+        // shim 不是指向 Java 目录的符号链接，只能从 `java.home` 得知真实位置
+        let shim = dir.join("shims").join("java");
+        std::fs::create_dir_all(shim.parent().unwrap()).unwrap();
+        std::fs::write(
+            &shim,
+            format!(
+                "#!/bin/sh\ncat >&2 <<'EOF'\nProperty settings:\n    java.home = {}\n    java.version = 1.8.0_402\nEOF\n",
+                jdk.join("jre").display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(java_home_from_binary(&shim), Some(jdk));
+    }
+    #[test]
+    fn test_flatpak_sdk_jvm_dirs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let home = dir.join("openjdk17").join("jvm").join("openjdk-17");
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(java_binary(&home), "").unwrap();
+        std::fs::create_dir_all(dir.join("openjdk21").join("jvm")).unwrap();
+        std::fs::create_dir_all(dir.join("rust-stable").join("jvm")).unwrap();
+        std::fs::create_dir_all(dir.join("openjdk8")).unwrap();
+
+        let jvm_dirs = flatpak_sdk_jvm_dirs(dir);
+        assert_eq!(
+            jvm_dirs,
+            vec![
+                dir.join("openjdk17").join("jvm"),
+                dir.join("openjdk21").join("jvm"),
+            ]
+        );
+        assert!(flatpak_sdk_jvm_dirs(&dir.join("missing")).is_empty());
+    }
+    #[test]
     fn test_potential_jre_paths() {
         let paths = get_potential_jre_paths();
         assert!(!paths.is_empty(), "Expected some potential JRE paths");
@@ -1,4 +1,9 @@
 pub mod command;
 pub mod finder;
 pub mod model;
+pub mod mojang;
+pub mod probe;
+pub mod provision;
+pub mod selector;
 pub mod util;
+pub mod version;
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use super::version::JavaVersion;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Architecture {
     X86,
@@ -20,4 +23,172 @@ pub struct JreInfo {
     pub implementor: Option<String>,
     /// 是否为手动添加
     pub manual: Option<bool>,
+    /// 是否为启动器下载并管理的运行时
+    #[serde(default)]
+    pub managed: Option<bool>,
+    /// 是否为 JDK（包含 javac）
+    #[serde(default)]
+    pub jdk: Option<bool>,
+    /// 是否自带 JavaFX
+    #[serde(default)]
+    pub javafx: Option<bool>,
+}
+
+impl JreInfo {
+    /// 解析后的版本号，无法识别时为空
+    pub fn java_version(&self) -> Option<JavaVersion> {
+        JavaVersion::parse(&self.version)
+    }
+
+    /// Java 主版本号，如 8、17、21
+    pub fn major(&self) -> Option<u32> {
+        self.java_version().map(|v| v.major)
+    }
+}
+
+/// Mojang Java 运行时索引（all.json）：平台 -> 运行时名称 -> 可用版本
+pub type MojangRuntimeIndex = HashMap<String, HashMap<String, Vec<MojangRuntimeEntry>>>;
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MojangRuntimeDownload {
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MojangRuntimeVersion {
+    /// 运行时版本号，如 "17.0.8"、"8u51"
+    pub name: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MojangRuntimeEntry {
+    /// 运行时文件清单
+    pub manifest: MojangRuntimeDownload,
+    pub version: MojangRuntimeVersion,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MojangRuntimeFileDownloads {
+    pub raw: MojangRuntimeDownload,
+}
+
+/// 运行时文件清单中的条目
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MojangRuntimeFile {
+    File {
+        #[serde(default)]
+        executable: bool,
+        downloads: MojangRuntimeFileDownloads,
+    },
+    Directory,
+    Link {
+        /// 相对于链接所在目录的目标路径
+        target: String,
+    },
+}
+
+/// 运行时文件清单，键为相对于运行时目录的路径
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MojangRuntimeManifest {
+    pub files: HashMap<String, MojangRuntimeFile>,
+}
+
+/// 当前平台可下载的 Mojang Java 运行时
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MojangRuntimeInfo {
+    /// 运行时名称，如 "java-runtime-gamma"
+    pub component: String,
+    pub version: String,
+    /// 已安装的版本，未安装时为空
+    pub installed_version: Option<String>,
+}
+
+/// 第三方 JDK 发行版
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JdkVendor {
+    /// Eclipse Temurin
+    Adoptium,
+    /// Azul Zulu
+    Zulu,
+    /// BellSoft Liberica
+    Liberica,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JdkImageType {
+    Jre,
+    Jdk,
+}
+
+/// 查询到的可下载 JDK 压缩包
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JdkPackage {
+    pub vendor: JdkVendor,
+    pub image_type: JdkImageType,
+    pub major: u32,
+    /// 完整版本号
+    pub version: String,
+    pub url: String,
+    pub file_name: String,
+    pub size: Option<u64>,
+    pub sha256: Option<String>,
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AdoptiumPackage {
+    pub checksum: Option<String>,
+    pub link: String,
+    pub name: String,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AdoptiumBinary {
+    pub package: AdoptiumPackage,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AdoptiumVersion {
+    pub semver: String,
+}
+
+/// Adoptium `/v3/assets/latest` 接口返回的条目
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AdoptiumAsset {
+    pub binary: AdoptiumBinary,
+    pub version: AdoptiumVersion,
+}
+
+/// Azul 元数据接口返回的压缩包
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ZuluPackage {
+    pub package_uuid: String,
+    pub name: String,
+    pub java_version: Vec<u32>,
+    pub download_url: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ZuluPackageDetail {
+    pub sha256_hash: Option<String>,
+    pub size: Option<u64>,
+}
+
+/// BellSoft 接口返回的发行包
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibericaRelease {
+    pub download_url: String,
+    pub filename: String,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    pub version: String,
 }
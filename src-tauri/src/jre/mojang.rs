@@ -0,0 +1,339 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use super::finder::verify_jre_path;
+use super::model::{
+    Architecture, JreInfo, MojangRuntimeDownload, MojangRuntimeEntry, MojangRuntimeFile,
+    MojangRuntimeIndex, MojangRuntimeInfo, MojangRuntimeManifest,
+};
+use super::util::{get_runtimes_dir, register_managed_jre};
+use crate::util::cache::{get_cached_json, MetadataCache};
+use crate::util::mirror::current_source;
+use crate::util::model::DownloadRequest;
+use crate::util::scheduler::{DownloadEventSink, DOWNLOAD_SCHEDULER};
+use crate::util::utils::{sha1_hex, verify_sha1};
+
+/// Mojang Java 运行时索引地址
+const RUNTIME_INDEX_URL: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+const RUNTIME_INDEX_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// 文件清单地址包含其 SHA-1，内容不会变化
+const RUNTIME_MANIFEST_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// 记录已安装版本的文件，位于运行时目录下
+const VERSION_FILE_NAME: &str = ".version";
+
+/// 操作系统与架构对应的运行时索引平台名
+pub fn platform_key(os: &str, arch: &str) -> Option<&'static str> {
+    match (os, arch) {
+        ("linux", "x86_64") => Some("linux"),
+        ("linux", "x86") => Some("linux-i386"),
+        ("windows", "x86_64") => Some("windows-x64"),
+        ("windows", "x86") => Some("windows-x86"),
+        ("windows", "aarch64") => Some("windows-arm64"),
+        ("macos", "x86_64") => Some("mac-os"),
+        ("macos", "aarch64") => Some("mac-os-arm64"),
+        _ => None,
+    }
+}
+
+fn current_platform() -> Result<&'static str, String> {
+    platform_key(std::env::consts::OS, std::env::consts::ARCH).ok_or_else(|| {
+        format!(
+            "Mojang 未提供 {} {} 平台的 Java 运行时",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })
+}
+
+fn platform_arch(platform: &str) -> Architecture {
+    match platform {
+        "linux-i386" | "windows-x86" => Architecture::X86,
+        "windows-arm64" | "mac-os-arm64" => Architecture::Arm64,
+        _ => Architecture::X86_64,
+    }
+}
+
+/// 运行时目录中的 Java 目录，macOS 上位于 `jre.bundle/Contents/Home`
+pub fn runtime_java_home(runtime_dir: &Path) -> PathBuf {
+    let bundle_home = runtime_dir.join("jre.bundle").join("Contents").join("Home");
+    if bundle_home.is_dir() {
+        bundle_home
+    } else {
+        runtime_dir.to_path_buf()
+    }
+}
+
+/// 拼接清单中的相对路径，拒绝绝对路径与 `..`
+fn safe_join(base: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("运行时清单包含非法路径: {}", relative.display()));
+    }
+    Ok(base.join(relative))
+}
+
+/// 检查链接目标在运行时目录内，`relative` 为链接相对于运行时目录的路径
+fn check_link_target(relative: &str, target: &str) -> Result<(), String> {
+    let mut depth = Path::new(relative).parent().map_or(0, |p| {
+        p.components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .count()
+    });
+    for component in Path::new(target).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => {
+                return Err(format!(
+                    "运行时清单中的链接指向目录外: {} -> {}",
+                    relative, target
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn get_runtime_index() -> Result<MojangRuntimeIndex, String> {
+    get_cached_json(RUNTIME_INDEX_URL, &current_source(), RUNTIME_INDEX_TTL)
+        .await
+        .map_err(|e| format!("获取 Java 运行时列表失败: {}", e))
+}
+
+/// 当前平台的指定运行时的最新版本
+async fn get_runtime_entry(component: &str) -> Result<(&'static str, MojangRuntimeEntry), String> {
+    let platform = current_platform()?;
+    let entry = get_runtime_index()
+        .await?
+        .remove(platform)
+        .and_then(|mut runtimes| runtimes.remove(component))
+        .and_then(|entries| entries.into_iter().next())
+        .ok_or_else(|| format!("Mojang 未提供 {} 平台的 {}", platform, component))?;
+    Ok((platform, entry))
+}
+
+async fn get_runtime_manifest(entry: &MojangRuntimeEntry) -> Result<MojangRuntimeManifest, String> {
+    let body = MetadataCache::global()?
+        .fetch(&entry.manifest.url, &current_source(), RUNTIME_MANIFEST_TTL)
+        .await?;
+    if !sha1_hex(&body).eq_ignore_ascii_case(&entry.manifest.sha1) {
+        return Err("运行时文件清单校验失败".to_string());
+    }
+    serde_json::from_slice(&body).map_err(|e| format!("解析运行时文件清单失败: {}", e))
+}
+
+/// 列出当前平台可下载的运行时及其安装状态
+pub async fn list_mojang_runtimes() -> Result<Vec<MojangRuntimeInfo>, String> {
+    let platform = current_platform()?;
+    let runtimes_dir = get_runtimes_dir()?;
+    let mut result: Vec<MojangRuntimeInfo> = get_runtime_index()
+        .await?
+        .remove(platform)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(component, entries)| {
+            let entry = entries.into_iter().next()?;
+            let installed_version =
+                fs::read_to_string(runtimes_dir.join(&component).join(VERSION_FILE_NAME))
+                    .ok()
+                    .map(|v| v.trim().to_string());
+            Some(MojangRuntimeInfo {
+                component,
+                version: entry.version.name,
+                installed_version,
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| a.component.cmp(&b.component));
+    Ok(result)
+}
+
+/// 校验下载的运行时文件、设置可执行权限并创建链接
+fn finish_runtime_files(
+    files: &[(PathBuf, bool, MojangRuntimeDownload)],
+    links: &[(PathBuf, String)],
+) -> Result<(), String> {
+    for (path, executable, download) in files {
+        if !verify_sha1(path, &download.sha1) {
+            let _ = fs::remove_file(path);
+            return Err(format!("文件校验失败: {}", path.display()));
+        }
+        #[cfg(unix)]
+        if *executable {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("设置可执行权限失败 {}: {}", path.display(), e))?;
+        }
+        #[cfg(not(unix))]
+        let _ = executable;
+    }
+
+    for (path, target) in links {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        if path.symlink_metadata().is_ok() {
+            fs::remove_file(path).map_err(|e| format!("删除旧链接失败: {}", e))?;
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(target, path)
+            .map_err(|e| format!("创建链接失败 {}: {}", path.display(), e))?;
+        // Windows 的运行时清单中没有链接，无需处理
+        #[cfg(not(unix))]
+        let _ = target;
+    }
+    Ok(())
+}
+
+/// 下载并安装 Mojang 提供的 Java 运行时，安装完成后登记到 JRE 配置中
+///
+/// 已存在且校验通过的文件不会重复下载，因此也可用于修复或更新运行时。
+pub async fn install_mojang_runtime(
+    component: &str,
+    sink: Option<DownloadEventSink>,
+) -> Result<JreInfo, String> {
+    let (platform, entry) = get_runtime_entry(component).await?;
+    let manifest = get_runtime_manifest(&entry).await?;
+    let runtime_dir = get_runtimes_dir()?.join(component);
+
+    let mut files = Vec::new();
+    let mut links = Vec::new();
+    for (relative, file) in &manifest.files {
+        let path = safe_join(&runtime_dir, relative)?;
+        match file {
+            MojangRuntimeFile::Directory => {
+                fs::create_dir_all(&path).map_err(|e| format!("创建目录失败: {}", e))?;
+            }
+            MojangRuntimeFile::File {
+                executable,
+                downloads,
+            } => files.push((path, *executable, downloads.raw.clone())),
+            MojangRuntimeFile::Link { target } => {
+                check_link_target(relative, target)?;
+                links.push((path, target.clone()));
+            }
+        }
+    }
+
+    // 校验 SHA-1 需要读取运行时的每个文件，放到阻塞线程执行
+    let (files, requests) = tokio::task::spawn_blocking(move || {
+        let requests: Vec<DownloadRequest> = files
+            .iter()
+            .filter(|(path, _, download)| !verify_sha1(path, &download.sha1))
+            .map(|(path, _, download)| DownloadRequest {
+                url: download.url.clone(),
+                path: path.clone(),
+                priority: Default::default(),
+                size: Some(download.size),
+            })
+            .collect();
+        (files, requests)
+    })
+    .await
+    .map_err(|e| format!("校验运行时文件失败: {}", e))?;
+    DOWNLOAD_SCHEDULER
+        .run(format!("Java 运行时 {}", component), requests, sink)
+        .await?;
+
+    tokio::task::spawn_blocking(move || finish_runtime_files(&files, &links))
+        .await
+        .map_err(|e| format!("校验运行时文件失败: {}", e))??;
+
+    fs::write(runtime_dir.join(VERSION_FILE_NAME), &entry.version.name)
+        .map_err(|e| format!("写入运行时版本失败: {}", e))?;
+
+    let java_home = runtime_java_home(&runtime_dir);
+    let jre = match verify_jre_path(&java_home) {
+        Some(jre) => jre,
+        None => JreInfo {
+            path: java_home,
+            version: entry.version.name.clone(),
+            arch: platform_arch(platform),
+            implementor: Some("Mojang".to_string()),
+            manual: Some(false),
+            managed: None,
+            jdk: None,
+            javafx: None,
+        },
+    };
+    let jre = JreInfo {
+        managed: Some(true),
+        ..jre
+    };
+    register_managed_jre(jre.clone()).await?;
+    Ok(jre)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_platform_key() {
+        assert_eq!(platform_key("linux", "x86_64"), Some("linux"));
+        assert_eq!(platform_key("linux", "x86"), Some("linux-i386"));
+        assert_eq!(platform_key("windows", "x86_64"), Some("windows-x64"));
+        assert_eq!(platform_key("macos", "aarch64"), Some("mac-os-arm64"));
+        assert_eq!(platform_key("linux", "riscv64"), None);
+        assert_eq!(platform_arch("mac-os-arm64"), Architecture::Arm64);
+    }
+
+    #[test]
+    fn test_parse_runtime_manifest() {
+        let manifest: MojangRuntimeManifest = serde_json::from_str(
+            r#"{
+                "files": {
+                    "bin": { "type": "directory" },
+                    "bin/java": {
+                        "type": "file",
+                        "executable": true,
+                        "downloads": {
+                            "raw": { "sha1": "abc", "size": 12, "url": "https://piston-data.mojang.com/v1/objects/abc/java" }
+                        }
+                    },
+                    "legal/java.base/LICENSE": { "type": "link", "target": "../../LICENSE" }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            manifest.files["bin/java"],
+            MojangRuntimeFile::File {
+                executable: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            manifest.files["bin"],
+            MojangRuntimeFile::Directory
+        ));
+        assert!(
+            matches!(&manifest.files["legal/java.base/LICENSE"], MojangRuntimeFile::Link { target } if target == "../../LICENSE")
+        );
+    }
+
+    #[test]
+    fn test_safe_join() {
+        let base = Path::new("/runtimes/java-runtime-gamma");
+        assert_eq!(
+            safe_join(base, "bin/java").unwrap(),
+            base.join("bin").join("java")
+        );
+        assert!(safe_join(base, "../escape").is_err());
+        assert!(safe_join(base, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_check_link_target() {
+        assert!(check_link_target("legal/java.base/LICENSE", "../../LICENSE").is_ok());
+        assert!(check_link_target("bin/java", "./java-real").is_ok());
+        assert!(check_link_target("legal/java.base/LICENSE", "../../../LICENSE").is_err());
+        assert!(check_link_target("LICENSE", "../outside").is_err());
+        assert!(check_link_target("bin/java", "/usr/bin/java").is_err());
+    }
+}
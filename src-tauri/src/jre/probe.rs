@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use once_cell::sync::Lazy;
+
+/// 等待 `java` 输出属性的最长时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 通过运行 `java` 获取的系统属性
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaProperties {
+    /// `java.version`
+    pub version: String,
+    /// `java.vendor`
+    pub vendor: Option<String>,
+    /// `os.arch`
+    pub arch: Option<String>,
+    /// `java.home`，Java 8 的 JDK 中为其下的 `jre` 目录
+    pub home: Option<PathBuf>,
+}
+
+/// 可执行文件路径 -> (修改时间, 探测结果)
+type ProbeCache = HashMap<PathBuf, (SystemTime, Option<JavaProperties>)>;
+
+/// 探测结果缓存，文件修改后重新探测，探测失败的结果同样缓存
+static PROBE_CACHE: Lazy<Mutex<ProbeCache>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 解析 `-XshowSettings:properties` 的输出
+///
+/// 属性以四个空格缩进，形如 `    java.version = 17.0.9`；多值属性的后续行缩进更深，忽略。
+pub fn parse_properties(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.strip_prefix("    ")?;
+            if line.starts_with(' ') {
+                return None;
+            }
+            let (key, value) = line.split_once(" = ")?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// 运行 `java -XshowSettings:properties -version`，超时后结束进程
+fn run_probe(java_bin: &Path) -> Option<String> {
+    let mut command = Command::new(java_bin);
+    command
+        .args(["-XshowSettings:properties", "-version"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW，避免弹出控制台窗口
+        command.creation_flags(0x0800_0000);
+    }
+    let mut child = command.spawn().ok()?;
+    // 属性输出到 stderr，在单独的线程中读取，避免管道写满导致进程阻塞
+    let mut stderr = child.stderr.take()?;
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stderr.read_to_string(&mut output);
+        output
+    });
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+    reader.join().ok()
+}
+
+/// 运行 `java` 可执行文件获取版本、提供者、架构与 `java.home`
+pub fn probe_java(java_bin: &Path) -> Option<JavaProperties> {
+    let modified = java_bin.metadata().and_then(|m| m.modified()).ok()?;
+    if let Some((cached_time, cached)) = PROBE_CACHE.lock().unwrap().get(java_bin) {
+        if *cached_time == modified {
+            return cached.clone();
+        }
+    }
+    let result = run_probe(java_bin).and_then(|output| {
+        let mut properties = parse_properties(&output);
+        Some(JavaProperties {
+            version: properties.remove("java.version")?,
+            vendor: properties.remove("java.vendor"),
+            arch: properties.remove("os.arch"),
+            home: properties.remove("java.home").map(PathBuf::from),
+        })
+    });
+    PROBE_CACHE
+        .lock()
+        .unwrap()
+        .insert(java_bin.to_path_buf(), (modified, result.clone()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_OUTPUT: &str = "Property settings:
+    file.encoding = UTF-8
+    java.home = /usr/lib/jvm/java-17-openjdk-amd64
+    java.library.path = /usr/java/packages/lib
+        /usr/lib64
+        /lib64
+    java.vendor = Eclipse Adoptium
+    java.version = 17.0.9
+    os.arch = amd64
+
+openjdk version \"17.0.9\" 2023-10-17
+";
+
+    #[test]
+    fn test_parse_properties() {
+        let properties = parse_properties(SAMPLE_OUTPUT);
+        assert_eq!(properties["java.version"], "17.0.9");
+        assert_eq!(properties["java.vendor"], "Eclipse Adoptium");
+        assert_eq!(properties["os.arch"], "amd64");
+        assert_eq!(properties["java.library.path"], "/usr/java/packages/lib");
+        assert!(!properties.contains_key("/usr/lib64"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_java() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let java = dir.join("java");
+        std::fs::write(
+            &java,
+            format!("#!/bin/sh\ncat >&2 <<'EOF'\n{}EOF\n", SAMPLE_OUTPUT),
+        )
+        .unwrap();
+        std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let properties = probe_java(&java).unwrap();
+        assert_eq!(properties.version, "17.0.9");
+        assert_eq!(properties.vendor.as_deref(), Some("Eclipse Adoptium"));
+        assert_eq!(properties.arch.as_deref(), Some("amd64"));
+        assert_eq!(
+            properties.home,
+            Some(PathBuf::from("/usr/lib/jvm/java-17-openjdk-amd64"))
+        );
+        assert!(PROBE_CACHE.lock().unwrap().contains_key(&java));
+    }
+}
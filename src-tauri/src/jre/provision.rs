@@ -0,0 +1,503 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::finder::{java_binary, verify_jre_path};
+use super::model::{
+    AdoptiumAsset, JdkImageType, JdkPackage, JdkVendor, JreInfo, LibericaRelease, ZuluPackage,
+    ZuluPackageDetail,
+};
+use super::util::{get_runtimes_dir, register_managed_jre};
+use crate::config::saveload::{get_jre_config, save_jre_config};
+use crate::util::cache::get_cached_json;
+use crate::util::mirror::current_source;
+use crate::util::model::{DownloadPriority, DownloadRequest};
+use crate::util::scheduler::{DownloadEventSink, DOWNLOAD_SCHEDULER};
+use crate::util::utils::verify_sha1;
+
+const ADOPTIUM_API_ROOT: &str = "https://api.adoptium.net/v3";
+const ZULU_API_ROOT: &str = "https://api.azul.com/metadata/v1/zulu/packages";
+const LIBERICA_API_ROOT: &str = "https://api.bell-sw.com/v1/liberica/releases";
+const JDK_QUERY_TTL: Duration = Duration::from_secs(60 * 60);
+/// 在解压目录中查找 Java 目录的最大深度，macOS 的包位于 `<名称>/Contents/Home`
+const MAX_JAVA_HOME_DEPTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveType {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveType {
+    fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            ArchiveType::Zip
+        } else {
+            ArchiveType::TarGz
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ArchiveType::TarGz => "tar.gz",
+            ArchiveType::Zip => "zip",
+        }
+    }
+}
+
+impl JdkVendor {
+    fn id(&self) -> &'static str {
+        match self {
+            JdkVendor::Adoptium => "adoptium",
+            JdkVendor::Zulu => "zulu",
+            JdkVendor::Liberica => "liberica",
+        }
+    }
+
+    /// 该发行版接口使用的操作系统与架构名称
+    fn platform(&self, os: &str, arch: &str) -> Option<(&'static str, &'static str)> {
+        let os = match (self, os) {
+            (_, "linux") => "linux",
+            (_, "windows") => "windows",
+            (JdkVendor::Adoptium, "macos") => "mac",
+            (_, "macos") => "macos",
+            _ => return None,
+        };
+        let arch = match (self, arch) {
+            (JdkVendor::Liberica, "x86_64" | "x86") => "x86",
+            (JdkVendor::Liberica, "aarch64") => "arm",
+            (_, "x86_64") => "x64",
+            (_, "x86") => "x86",
+            (_, "aarch64") => "aarch64",
+            _ => return None,
+        };
+        Some((os, arch))
+    }
+}
+
+impl JdkImageType {
+    fn id(&self) -> &'static str {
+        match self {
+            JdkImageType::Jre => "jre",
+            JdkImageType::Jdk => "jdk",
+        }
+    }
+}
+
+fn current_platform(vendor: JdkVendor) -> Result<(&'static str, &'static str), String> {
+    vendor
+        .platform(std::env::consts::OS, std::env::consts::ARCH)
+        .ok_or_else(|| {
+            format!(
+                "{:?} 不支持 {} {} 平台",
+                vendor,
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )
+        })
+}
+
+async fn query_adoptium(major: u32, image_type: JdkImageType) -> Result<JdkPackage, String> {
+    let (os, arch) = current_platform(JdkVendor::Adoptium)?;
+    let url = format!(
+        "{}/assets/latest/{}/hotspot?os={}&architecture={}&image_type={}&vendor=eclipse",
+        ADOPTIUM_API_ROOT,
+        major,
+        os,
+        arch,
+        image_type.id()
+    );
+    let assets: Vec<AdoptiumAsset> =
+        get_cached_json(&url, &current_source(), JDK_QUERY_TTL).await?;
+    // 同一版本可能同时提供安装包与压缩包，只使用压缩包
+    let extension = ArchiveType::current().extension();
+    let asset = assets
+        .into_iter()
+        .find(|a| a.binary.package.name.ends_with(extension))
+        .ok_or_else(|| format!("Adoptium 未提供 Java {} 的 {}", major, image_type.id()))?;
+    Ok(JdkPackage {
+        vendor: JdkVendor::Adoptium,
+        image_type,
+        major,
+        version: asset.version.semver,
+        url: asset.binary.package.link,
+        file_name: asset.binary.package.name,
+        size: asset.binary.package.size,
+        sha256: asset.binary.package.checksum,
+        sha1: None,
+    })
+}
+
+async fn query_zulu(major: u32, image_type: JdkImageType) -> Result<JdkPackage, String> {
+    let (os, arch) = current_platform(JdkVendor::Zulu)?;
+    let url = format!(
+        "{}/?java_version={}&os={}&arch={}&archive_type={}&java_package_type={}&javafx_bundled=false&latest=true&release_status=ga&availability_types=CA&page_size=1",
+        ZULU_API_ROOT,
+        major,
+        os,
+        arch,
+        ArchiveType::current().extension(),
+        image_type.id()
+    );
+    let packages: Vec<ZuluPackage> =
+        get_cached_json(&url, &current_source(), JDK_QUERY_TTL).await?;
+    let package = packages
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Zulu 未提供 Java {} 的 {}", major, image_type.id()))?;
+    // 列表接口不含校验值，需要再查询详情
+    let detail: ZuluPackageDetail = get_cached_json(
+        &format!("{}/{}", ZULU_API_ROOT, package.package_uuid),
+        &current_source(),
+        JDK_QUERY_TTL,
+    )
+    .await?;
+    Ok(JdkPackage {
+        vendor: JdkVendor::Zulu,
+        image_type,
+        major,
+        version: package
+            .java_version
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join("."),
+        url: package.download_url,
+        file_name: package.name,
+        size: detail.size,
+        sha256: detail.sha256_hash,
+        sha1: None,
+    })
+}
+
+async fn query_liberica(major: u32, image_type: JdkImageType) -> Result<JdkPackage, String> {
+    let (os, arch) = current_platform(JdkVendor::Liberica)?;
+    let bitness = if std::env::consts::ARCH == "x86" {
+        32
+    } else {
+        64
+    };
+    let url = format!(
+        "{}?version-feature={}&os={}&arch={}&bitness={}&package-type={}&bundle-type={}&version-modifier=latest&release-type=all",
+        LIBERICA_API_ROOT,
+        major,
+        os,
+        arch,
+        bitness,
+        ArchiveType::current().extension(),
+        image_type.id()
+    );
+    let releases: Vec<LibericaRelease> =
+        get_cached_json(&url, &current_source(), JDK_QUERY_TTL).await?;
+    let release = releases
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Liberica 未提供 Java {} 的 {}", major, image_type.id()))?;
+    Ok(JdkPackage {
+        vendor: JdkVendor::Liberica,
+        image_type,
+        major,
+        version: release.version,
+        url: release.download_url,
+        file_name: release.filename,
+        size: release.size,
+        sha256: None,
+        sha1: release.sha1,
+    })
+}
+
+/// 查询指定发行版在当前平台上最新的 Java 压缩包
+pub async fn find_jdk_package(
+    vendor: JdkVendor,
+    major: u32,
+    image_type: JdkImageType,
+) -> Result<JdkPackage, String> {
+    match vendor {
+        JdkVendor::Adoptium => query_adoptium(major, image_type).await,
+        JdkVendor::Zulu => query_zulu(major, image_type).await,
+        JdkVendor::Liberica => query_liberica(major, image_type).await,
+    }
+}
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 校验压缩包，发行版未提供校验值时视为失败
+fn verify_package(path: &Path, package: &JdkPackage) -> Result<(), String> {
+    let valid = if let Some(expected) = &package.sha256 {
+        sha256_file(path)
+            .map(|actual| actual.eq_ignore_ascii_case(expected))
+            .unwrap_or(false)
+    } else if let Some(expected) = &package.sha1 {
+        verify_sha1(path, expected)
+    } else {
+        return Err(format!("{} 未提供校验值，已取消安装", package.file_name));
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{} 校验失败", package.file_name))
+    }
+}
+
+fn extract_tar_gz(archive: &Path, target: &Path) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| format!("打开压缩包失败: {}", e))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(BufReader::new(file)));
+    archive.set_preserve_permissions(true);
+    archive
+        .unpack(target)
+        .map_err(|e| format!("解压失败: {}", e))
+}
+
+fn extract_zip(archive: &Path, target: &Path) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| format!("打开压缩包失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("读取压缩包失败: {}", e))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let out_path = target.join(name);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = File::create(&out_path).map_err(|e| e.to_string())?;
+        io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// 在解压目录中查找包含 `bin/java` 与 `release` 的目录
+fn find_java_home(dir: &Path, depth: usize) -> Option<PathBuf> {
+    if java_binary(dir).is_file() && dir.join("release").is_file() {
+        return Some(dir.to_path_buf());
+    }
+    if depth == 0 {
+        return None;
+    }
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .find_map(|e| find_java_home(&e.path(), depth - 1))
+}
+
+/// 校验下载的压缩包并解压，替换 `install_dir` 中的旧版本，返回安装的 Java
+fn unpack_jdk(
+    archive_path: &Path,
+    package: &JdkPackage,
+    runtimes_dir: &Path,
+    install_dir: &Path,
+) -> Result<JreInfo, String> {
+    if let Err(e) = verify_package(archive_path, package) {
+        let _ = fs::remove_file(archive_path);
+        return Err(e);
+    }
+
+    // 先解压到临时目录，成功后再替换旧的安装
+    let extract_dir = runtimes_dir.join(format!(".extract-{}", Uuid::new_v4().simple()));
+    let archive_type = if package.file_name.ends_with(".zip") {
+        ArchiveType::Zip
+    } else {
+        ArchiveType::TarGz
+    };
+    let result = match archive_type {
+        ArchiveType::TarGz => extract_tar_gz(archive_path, &extract_dir),
+        ArchiveType::Zip => extract_zip(archive_path, &extract_dir),
+    };
+    let _ = fs::remove_file(archive_path);
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&extract_dir);
+        return Err(e);
+    }
+    let Some(extracted_home) = find_java_home(&extract_dir, MAX_JAVA_HOME_DEPTH) else {
+        let _ = fs::remove_dir_all(&extract_dir);
+        return Err(format!("{} 中未找到 Java", package.file_name));
+    };
+    if install_dir.exists() {
+        fs::remove_dir_all(install_dir).map_err(|e| format!("删除旧版本失败: {}", e))?;
+    }
+    // 保留 macOS 包的目录结构，Java 目录相对于解压目录的位置不变
+    let top_level = extract_dir.join(
+        extracted_home
+            .strip_prefix(&extract_dir)
+            .ok()
+            .and_then(|p| p.components().next())
+            .map(|c| c.as_os_str().to_owned())
+            .unwrap_or_default(),
+    );
+    fs::rename(&top_level, install_dir).map_err(|e| format!("移动 Java 失败: {}", e))?;
+    let _ = fs::remove_dir_all(&extract_dir);
+    let java_home = install_dir.join(
+        extracted_home
+            .strip_prefix(&top_level)
+            .unwrap_or(Path::new("")),
+    );
+
+    verify_jre_path(&java_home).ok_or_else(|| format!("安装的 Java 无效: {}", java_home.display()))
+}
+
+/// 下载第三方 JDK 并解压到启动器的运行时目录，安装完成后登记到 JRE 配置中
+///
+/// 安装目录为 `runtimes/<发行版>-<主版本号>-<jre|jdk>`，已存在时会被替换。
+pub async fn install_jdk(
+    vendor: JdkVendor,
+    major: u32,
+    image_type: JdkImageType,
+    sink: Option<DownloadEventSink>,
+) -> Result<JreInfo, String> {
+    let package = find_jdk_package(vendor, major, image_type).await?;
+    let runtimes_dir = get_runtimes_dir()?;
+    let archive_path = runtimes_dir.join(".downloads").join(&package.file_name);
+    DOWNLOAD_SCHEDULER
+        .run(
+            format!("{:?} Java {}", vendor, package.version),
+            vec![DownloadRequest {
+                url: package.url.clone(),
+                path: archive_path.clone(),
+                priority: DownloadPriority::Normal,
+                size: package.size,
+            }],
+            sink,
+        )
+        .await?;
+    let install_dir = runtimes_dir.join(format!("{}-{}-{}", vendor.id(), major, image_type.id()));
+    // 校验、解压与替换都是耗时的文件操作，放到阻塞线程执行
+    let jre = tokio::task::spawn_blocking(move || {
+        unpack_jdk(&archive_path, &package, &runtimes_dir, &install_dir)
+    })
+    .await
+    .map_err(|e| format!("安装 Java 失败: {}", e))??;
+    let jre = JreInfo {
+        managed: Some(true),
+        ..jre
+    };
+    register_managed_jre(jre.clone()).await?;
+    Ok(jre)
+}
+
+/// 卸载启动器管理的 Java，删除其所在的运行时目录并从 JRE 配置中移除
+pub async fn uninstall_managed_jre(path: &Path) -> Result<(), String> {
+    let mut jre_config = get_jre_config().await?;
+    if !jre_config
+        .jres
+        .iter()
+        .any(|j| j.path == path && j.managed == Some(true))
+    {
+        return Err("只能卸载由启动器安装的 Java".to_string());
+    }
+    let runtimes_dir = get_runtimes_dir()?;
+    let install_dir = path
+        .strip_prefix(&runtimes_dir)
+        .ok()
+        .and_then(|p| p.components().next())
+        .map(|c| runtimes_dir.join(c))
+        .ok_or_else(|| format!("{} 不在运行时目录中", path.display()))?;
+    if install_dir.exists() {
+        tokio::task::spawn_blocking(move || fs::remove_dir_all(install_dir))
+            .await
+            .map_err(|e| format!("删除 Java 失败: {}", e))?
+            .map_err(|e| format!("删除 Java 失败: {}", e))?;
+    }
+    jre_config.jres.retain(|j| j.path != path);
+    save_jre_config(jre_config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_platform() {
+        assert_eq!(
+            JdkVendor::Adoptium.platform("macos", "aarch64"),
+            Some(("mac", "aarch64"))
+        );
+        assert_eq!(
+            JdkVendor::Zulu.platform("linux", "x86_64"),
+            Some(("linux", "x64"))
+        );
+        assert_eq!(
+            JdkVendor::Liberica.platform("linux", "aarch64"),
+            Some(("linux", "arm"))
+        );
+        assert_eq!(JdkVendor::Adoptium.platform("freebsd", "x86_64"), None);
+    }
+
+    #[test]
+    fn test_extract_and_find_java_home() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let archive_path = dir.join("jdk.tar.gz");
+        {
+            let file = File::create(&archive_path).unwrap();
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::fast());
+            let mut builder = tar::Builder::new(encoder);
+            for (name, content) in [
+                ("jdk-17.0.9+9-jre/release", "JAVA_VERSION=\"17.0.9\"\n"),
+                ("jdk-17.0.9+9-jre/bin/java", "#!/bin/sh\n"),
+            ] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o755);
+                header.set_cksum();
+                builder
+                    .append_data(&mut header, name, content.as_bytes())
+                    .unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let extract_dir = dir.join("out");
+        extract_tar_gz(&archive_path, &extract_dir).unwrap();
+        #[cfg(not(target_os = "windows"))]
+        assert_eq!(
+            find_java_home(&extract_dir, MAX_JAVA_HOME_DEPTH),
+            Some(extract_dir.join("jdk-17.0.9+9-jre"))
+        );
+
+        let package = JdkPackage {
+            vendor: JdkVendor::Adoptium,
+            image_type: JdkImageType::Jre,
+            major: 17,
+            version: "17.0.9+9".to_string(),
+            url: String::new(),
+            file_name: "jdk.tar.gz".to_string(),
+            size: None,
+            sha256: Some(sha256_file(&archive_path).unwrap()),
+            sha1: None,
+        };
+        assert!(verify_package(&archive_path, &package).is_ok());
+        let package = JdkPackage {
+            sha256: Some("0".repeat(64)),
+            ..package
+        };
+        assert!(verify_package(&archive_path, &package).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::finder::verify_jre_path;
+use super::model::{Architecture, JreInfo};
+use super::util::{get_known_jres, sort_jres_by_version_desc};
+use crate::game::modloader::version::models::fabric::FabricLauncherMeta;
+use crate::game::profile::model::Profile;
+use crate::game::version::model::ClientJson;
+use crate::game::version::util::library_path;
+
+/// 版本 JSON 未声明 `javaVersion` 时（1.16 及以前）默认需要的 Java 主版本号
+const DEFAULT_JAVA_MAJOR: u32 = 8;
+
+/// 自动选择 Java 的结果
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaSelection {
+    pub jre: JreInfo,
+    /// 版本要求的最低 Java 主版本号
+    pub required_major: u32,
+    /// 选择该 Java 的原因
+    pub reason: String,
+}
+
+/// 启动器自身运行的架构
+pub fn native_arch() -> Architecture {
+    if cfg!(target_arch = "x86_64") {
+        Architecture::X86_64
+    } else if cfg!(target_arch = "aarch64") {
+        Architecture::Arm64
+    } else if cfg!(target_arch = "x86") {
+        Architecture::X86
+    } else {
+        Architecture::Unknown
+    }
+}
+
+/// 版本要求的最低 Java 主版本号，取版本 JSON 与 Fabric 加载器要求中较高的一个
+pub fn required_java_major(json: &ClientJson, fabric_meta: Option<&FabricLauncherMeta>) -> u32 {
+    let vanilla = json
+        .java_version
+        .as_ref()
+        .and_then(|j| j.major_version)
+        .unwrap_or(DEFAULT_JAVA_MAJOR);
+    let fabric = fabric_meta
+        .and_then(|m| m.min_java_version)
+        .map(|v| v as u32)
+        .unwrap_or(0);
+    vanilla.max(fabric)
+}
+
+/// 从已安装的 Fabric 加载器 jar 中读取 `fabric-installer.json`
+pub fn read_fabric_launcher_meta(
+    json: &ClientJson,
+    libraries_dir: &Path,
+) -> Option<FabricLauncherMeta> {
+    let library = json.libraries.iter().flatten().find(|l| {
+        l.name
+            .as_deref()
+            .is_some_and(|n| n.starts_with("net.fabricmc:fabric-loader:"))
+    })?;
+    let jar = File::open(libraries_dir.join(library_path(library)?)).ok()?;
+    let mut archive = zip::ZipArchive::new(jar).ok()?;
+    let mut entry = archive.by_name("fabric-installer.json").ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn describe(jre: &JreInfo, major: u32) -> String {
+    format!("Java {}（{}，{:?}）", major, jre.version, jre.arch)
+}
+
+/// 从已知的 JRE 中为需要 `required_major` 的版本挑选最合适的一个
+///
+/// 优先选择主版本号完全一致的，否则选择最接近的更高版本；
+/// 同一主版本中优先与系统架构一致、其次为 64 位，最后取更新的小版本。
+pub fn select_jre(jres: &[JreInfo], required_major: u32) -> Result<JavaSelection, String> {
+    let native = native_arch();
+    let mut sorted = jres.to_vec();
+    sort_jres_by_version_desc(&mut sorted);
+    let best = sorted
+        .into_iter()
+        .filter_map(|jre| jre.major().map(|major| (major, jre)))
+        .filter(|(major, _)| *major >= required_major)
+        .min_by_key(|(major, jre)| {
+            (
+                *major - required_major,
+                jre.arch != native,
+                matches!(jre.arch, Architecture::X86 | Architecture::Unknown),
+            )
+        });
+
+    let Some((major, jre)) = best else {
+        let found = jres
+            .iter()
+            .map(|j| j.version.as_str())
+            .collect::<Vec<_>>()
+            .join("、");
+        return Err(if found.is_empty() {
+            format!(
+                "未找到任何 Java，该版本需要 Java {} 或更高版本，请先安装或手动添加 Java",
+                required_major
+            )
+        } else {
+            format!(
+                "未找到合适的 Java，该版本需要 Java {} 或更高版本，已找到: {}",
+                required_major, found
+            )
+        });
+    };
+
+    let mut reason = if major == required_major {
+        format!(
+            "版本需要 Java {}，选择了 {}",
+            required_major,
+            describe(&jre, major)
+        )
+    } else {
+        format!(
+            "未找到 Java {}，选择了最接近的更高版本 {}",
+            required_major,
+            describe(&jre, major)
+        )
+    };
+    if jre.arch != native {
+        reason.push_str(&format!("，注意其架构与系统（{:?}）不一致", native));
+    }
+    Ok(JavaSelection {
+        jre,
+        required_major,
+        reason,
+    })
+}
+
+/// 使用配置中手动指定的 Java，版本过低时拒绝使用
+pub fn select_override_jre(path: &Path, required_major: u32) -> Result<JavaSelection, String> {
+    let jre = verify_jre_path(&path.to_path_buf())
+        .ok_or_else(|| format!("配置指定的 Java 无效: {}", path.display()))?;
+    let major = jre
+        .major()
+        .ok_or_else(|| format!("无法识别配置指定的 Java 版本: {}", jre.version))?;
+    if major < required_major {
+        return Err(format!(
+            "配置指定的 Java {} 低于该版本要求的 Java {}，请更换或改为自动选择",
+            major, required_major
+        ));
+    }
+    Ok(JavaSelection {
+        reason: format!("使用配置指定的 {}", describe(&jre, major)),
+        jre,
+        required_major,
+    })
+}
+
+/// 为配置选择 Java：指定了 `jre_path` 时使用该 Java，否则从所有已知 JRE 中自动选择
+pub async fn select_java_for_profile(
+    profile: &Profile,
+    json: &ClientJson,
+    libraries_dir: &Path,
+) -> Result<JavaSelection, String> {
+    let fabric_meta = read_fabric_launcher_meta(json, libraries_dir);
+    let required_major = required_java_major(json, fabric_meta.as_ref());
+    match &profile.jre_path {
+        Some(path) if !path.trim().is_empty() => {
+            select_override_jre(&PathBuf::from(path), required_major)
+        }
+        _ => select_jre(&get_known_jres().await, required_major),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jre(path: &str, version: &str, arch: Architecture) -> JreInfo {
+        JreInfo {
+            path: PathBuf::from(path),
+            version: version.to_string(),
+            arch,
+            implementor: None,
+            manual: Some(false),
+            managed: None,
+            jdk: None,
+            javafx: None,
+        }
+    }
+
+    #[test]
+    fn test_required_java_major() {
+        let json: ClientJson =
+            serde_json::from_str(r#"{ "javaVersion": { "majorVersion": 17 } }"#).unwrap();
+        assert_eq!(required_java_major(&json, None), 17);
+        let legacy: ClientJson = serde_json::from_str("{}").unwrap();
+        assert_eq!(required_java_major(&legacy, None), 8);
+
+        let meta: FabricLauncherMeta =
+            serde_json::from_str(r#"{ "version": 2, "min_java_version": 21, "libraries": {} }"#)
+                .unwrap();
+        assert_eq!(required_java_major(&json, Some(&meta)), 21);
+    }
+
+    #[test]
+    fn test_select_jre() {
+        let native = native_arch();
+        let jres = vec![
+            jre("/java8", "1.8.0_382", native.clone()),
+            jre("/java17-x86", "17.0.11", Architecture::X86),
+            jre("/java17", "17.0.2", native.clone()),
+            jre("/java17-new", "17.0.10", native.clone()),
+            jre("/java21", "21.0.1", native.clone()),
+        ];
+
+        let selection = select_jre(&jres, 17).unwrap();
+        assert_eq!(selection.jre.path, PathBuf::from("/java17-new"));
+        assert_eq!(selection.required_major, 17);
+
+        // 没有完全匹配时选择最接近的更高版本
+        let selection = select_jre(&jres, 16).unwrap();
+        assert_eq!(selection.jre.path, PathBuf::from("/java17-new"));
+        assert!(selection.reason.contains("最接近"));
+
+        assert_eq!(
+            select_jre(&jres, 8).unwrap().jre.path,
+            PathBuf::from("/java8")
+        );
+        assert!(select_jre(&jres, 25).is_err());
+        assert!(select_jre(&[], 8).unwrap_err().contains("未找到任何 Java"));
+    }
+}
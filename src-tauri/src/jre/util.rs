@@ -1,69 +1,76 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::finder::scan_jres;
 use super::model::JreInfo;
+use crate::config::saveload::{get_jre_config, save_jre_config};
+use crate::config::util::get_global_config_path;
 
-/// 将版本字符串转换为可比较的数字向量
-/// 例如: "1.8.0_556" -> [1, 8, 0, 556]
-fn parse_version(version: &str) -> Vec<i32> {
-    // 首先按 '_' 分割处理更新编号
-    let parts: Vec<&str> = version.split('_').collect();
-    let mut numbers = parts[0]
-        .split('.')
-        .map(|s| s.parse::<i32>().unwrap_or(0))
-        .collect::<Vec<i32>>();
+/// 对JRE列表按版本号进行排序（降序），无法识别版本号的排在最后
+pub fn sort_jres_by_version_desc(jres: &mut [JreInfo]) {
+    jres.sort_by_cached_key(|jre| Reverse(jre.java_version()));
+}
 
-    // 如果存在更新编号，添加到数组末尾
-    if parts.len() > 1 {
-        if let Ok(update_num) = parts[1].parse::<i32>() {
-            numbers.push(update_num);
+/// 合并自动扫描与配置文件中的 JRE，按解析符号链接后的路径去重，再按版本号降序排列
+///
+/// 扫描时可能需要运行 `java` 探测版本，因此放到阻塞线程池中执行。
+pub async fn get_known_jres() -> Vec<JreInfo> {
+    let jre_config = get_jre_config().await.unwrap_or_default();
+    let scanned = tokio::task::spawn_blocking(scan_jres)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("扫描 JRE 失败: {}", e);
+            Vec::new()
+        });
+    let mut unique_paths: HashSet<PathBuf> = HashSet::new();
+    let mut result = Vec::new();
+    // 先添加扫描到的JRE，再添加配置文件中路径不重复的JRE
+    for jre in scanned.into_iter().chain(jre_config.jres) {
+        let canonical = std::fs::canonicalize(&jre.path).unwrap_or_else(|_| jre.path.clone());
+        if unique_paths.insert(canonical) {
+            result.push(jre);
         }
     }
-
-    numbers
+    sort_jres_by_version_desc(&mut result);
+    result
 }
 
-/// 比较两个版本号
-/// 返回值:
-/// - 负数: a < b
-/// - 0: a == b
-/// - 正数: a > b
-fn compare_versions(a: &str, b: &str) -> i32 {
-    let version_a = parse_version(a);
-    let version_b = parse_version(b);
-
-    for i in 0..version_a.len().max(version_b.len()) {
-        let num_a = version_a.get(i).copied().unwrap_or(0);
-        let num_b = version_b.get(i).copied().unwrap_or(0);
-
-        if num_a != num_b {
-            return num_a - num_b;
-        }
-    }
-    0
+/// 启动器下载的 Java 运行时存放目录
+pub fn get_runtimes_dir() -> Result<PathBuf, String> {
+    let path = get_global_config_path()?.join("runtimes");
+    std::fs::create_dir_all(&path).map_err(|e| format!("创建运行时目录失败: {}", e))?;
+    Ok(path)
 }
 
-/// 对JRE列表按版本号进行排序（降序）
-pub fn sort_jres_by_version_desc(jres: &mut Vec<JreInfo>) {
-    jres.sort_by(|a, b| compare_versions(&b.version, &a.version).cmp(&0));
+/// 将启动器管理的 JRE 写入配置，已存在相同路径时替换
+pub async fn register_managed_jre(jre: JreInfo) -> Result<(), String> {
+    let mut jre_config = get_jre_config().await?;
+    jre_config.jres.retain(|j| j.path != jre.path);
+    jre_config.jres.push(jre);
+    save_jre_config(jre_config).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::jre::model::Architecture;
-    use std::path::PathBuf;
     #[test]
-    fn test_version_comparison() {
-        // 基本版本号比较
-        assert!(compare_versions("1.8.0", "1.7.0") > 0);
-        assert!(compare_versions("11.0.2", "1.8.0") > 0);
-        assert!(compare_versions("17.0.1", "11.0.2") > 0);
-        assert_eq!(compare_versions("1.8.0", "1.8.0"), 0);
-        assert!(compare_versions("1.8.0", "1.8.1") < 0);
-
-        // 带更新编号的版本比较
-        assert!(compare_versions("1.8.0_556", "1.8.0_447") > 0);
-        assert!(compare_versions("1.8.0_556", "1.8.0") > 0);
-        assert!(compare_versions("11.0.2", "1.8.0_556") > 0);
-        assert_eq!(compare_versions("1.8.0_556", "1.8.0_556"), 0);
+    fn test_jre_major() {
+        let jre = |version: &str| JreInfo {
+            path: PathBuf::from("/path/to/java"),
+            version: version.to_string(),
+            arch: Architecture::X86_64,
+            implementor: None,
+            manual: Some(false),
+            managed: None,
+            jdk: None,
+            javafx: None,
+        };
+        assert_eq!(jre("1.8.0_382").major(), Some(8));
+        assert_eq!(jre("17.0.1").major(), Some(17));
+        assert_eq!(jre("21-ea").major(), Some(21));
+        assert_eq!(jre("invalid").major(), None);
     }
 
     #[test]
@@ -75,6 +82,9 @@ mod tests {
                 arch: Architecture::X86_64,
                 implementor: Some("Oracle".to_string()),
                 manual: Some(false),
+                managed: None,
+                jdk: None,
+                javafx: None,
             },
             JreInfo {
                 path: PathBuf::from("/path/to/java17"),
@@ -82,6 +92,9 @@ mod tests {
                 arch: Architecture::X86_64,
                 implementor: Some("Oracle".to_string()),
                 manual: Some(false),
+                managed: None,
+                jdk: None,
+                javafx: None,
             },
             JreInfo {
                 path: PathBuf::from("/path/to/java11"),
@@ -89,6 +102,9 @@ mod tests {
                 arch: Architecture::X86_64,
                 implementor: Some("Oracle".to_string()),
                 manual: Some(false),
+                managed: None,
+                jdk: None,
+                javafx: None,
             },
         ];
 
@@ -0,0 +1,283 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Java 版本号，支持 JEP 223 格式（如 "17.0.9+9"、"21-ea"）与旧式 1.x 格式（如 "1.8.0_392-b08"）
+///
+/// 比较时依次比较 major、minor、security、patch，正式版高于同版本号的预览版，最后比较构建号。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JavaVersion {
+    pub major: u32,
+    pub minor: u32,
+    /// 安全更新版本，旧式版本中为 update 号（"1.8.0_392" 中的 392）
+    pub security: u32,
+    /// 发行商追加的补丁号，如 Corretto "17.0.9.8.1" 中的 8
+    pub patch: u32,
+    pub build: Option<u32>,
+    /// 预览版标识，如 "ea"
+    pub pre: Option<String>,
+}
+
+/// 解析开头的数字
+fn leading_number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+/// 解析以 `.` 分隔的版本号，返回数字列表与剩余部分
+fn parse_numbers(s: &str) -> Option<(Vec<u32>, &str)> {
+    let (first, mut rest) = leading_number(s)?;
+    let mut numbers = vec![first];
+    while let Some(next) = rest.strip_prefix('.') {
+        match leading_number(next) {
+            Some((n, r)) => {
+                numbers.push(n);
+                rest = r;
+            }
+            None => break,
+        }
+    }
+    Some((numbers, rest))
+}
+
+/// 从后缀中提取构建号：`+9`、`-b08` 或 `+9-LTS`
+fn parse_build(suffix: &str) -> Option<u32> {
+    if let Some(plus) = suffix.find('+') {
+        return leading_number(&suffix[plus + 1..]).map(|(n, _)| n);
+    }
+    suffix
+        .split('-')
+        .find_map(|part| part.strip_prefix('b').and_then(|b| b.parse().ok()))
+}
+
+/// 从 `-` 开头的后缀中提取预览版标识，发行商附加的 "LTS"、"b08" 等不算预览版
+fn parse_pre(suffix: &str) -> Option<String> {
+    let pre = suffix.strip_prefix('-')?.split(['+', '-']).next()?;
+    let is_build = pre
+        .strip_prefix('b')
+        .is_some_and(|b| b.parse::<u32>().is_ok());
+    if pre.is_empty() || is_build || pre.eq_ignore_ascii_case("lts") {
+        return None;
+    }
+    // JEP 223 规定预览版标识只含字母与数字，其余视为发行商的附加信息
+    if !pre.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some(pre.to_string())
+}
+
+impl JavaVersion {
+    /// 解析版本字符串，允许带有 `jdk-`、`jdk` 前缀
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().trim_matches('"');
+        let version = version
+            .strip_prefix("jdk-")
+            .or_else(|| version.strip_prefix("jdk"))
+            .unwrap_or(version);
+        let (numbers, rest) = parse_numbers(version)?;
+
+        // Mojang 运行时使用 "8u51" 格式
+        if numbers.len() == 1 {
+            if let Some(update) = rest.strip_prefix('u') {
+                let (security, suffix) = leading_number(update)?;
+                return Some(JavaVersion {
+                    major: numbers[0],
+                    minor: 0,
+                    security,
+                    patch: 0,
+                    build: parse_build(suffix),
+                    pre: parse_pre(suffix),
+                });
+            }
+        }
+
+        // 旧式版本 1.x.y_update
+        if numbers[0] == 1 && numbers.len() >= 2 {
+            let (security, suffix) = match rest.strip_prefix('_') {
+                Some(update) => leading_number(update)?,
+                None => (0, rest),
+            };
+            return Some(JavaVersion {
+                major: numbers[1],
+                minor: numbers.get(2).copied().unwrap_or(0),
+                security,
+                patch: 0,
+                build: parse_build(suffix),
+                pre: parse_pre(suffix),
+            });
+        }
+
+        Some(JavaVersion {
+            major: numbers[0],
+            minor: numbers.get(1).copied().unwrap_or(0),
+            security: numbers.get(2).copied().unwrap_or(0),
+            patch: numbers.get(3).copied().unwrap_or(0),
+            build: parse_build(rest),
+            pre: parse_pre(rest),
+        })
+    }
+}
+
+impl Ord for JavaVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.security, self.patch)
+            .cmp(&(other.major, other.minor, other.security, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for JavaVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for JavaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.security)?;
+        if self.patch > 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        if let Some(build) = self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn v(major: u32, minor: u32, security: u32, build: Option<u32>) -> JavaVersion {
+        JavaVersion {
+            major,
+            minor,
+            security,
+            patch: 0,
+            build,
+            pre: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_vendor_strings() {
+        let cases = [
+            ("1.8.0_392", v(8, 0, 392, None)),
+            ("1.8.0_392-b08", v(8, 0, 392, Some(8))),
+            ("1.8.0", v(8, 0, 0, None)),
+            ("1.7.0_80", v(7, 0, 80, None)),
+            ("8u51", v(8, 0, 51, None)),
+            ("jdk8u392-b08", v(8, 0, 392, Some(8))),
+            ("17.0.9+9", v(17, 0, 9, Some(9))),
+            ("17.0.9+9-LTS", v(17, 0, 9, Some(9))),
+            ("jdk-17.0.9+9", v(17, 0, 9, Some(9))),
+            ("11.0.21+9-post-Ubuntu-0ubuntu122.04", v(11, 0, 21, Some(9))),
+            ("21", v(21, 0, 0, None)),
+            ("21.0.1", v(21, 0, 1, None)),
+            ("\"21.0.2\"", v(21, 0, 2, None)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(JavaVersion::parse(input), Some(expected), "{}", input);
+        }
+
+        let ea = JavaVersion::parse("21-ea").unwrap();
+        assert_eq!((ea.major, ea.pre.as_deref()), (21, Some("ea")));
+        let ea = JavaVersion::parse("22-ea+27").unwrap();
+        assert_eq!((ea.pre.as_deref(), ea.build), (Some("ea"), Some(27)));
+        let corretto = JavaVersion::parse("17.0.9.8.1").unwrap();
+        assert_eq!((corretto.security, corretto.patch), (9, 8));
+
+        assert_eq!(JavaVersion::parse("invalid"), None);
+        assert_eq!(JavaVersion::parse(""), None);
+    }
+
+    #[test]
+    fn test_version_comparison() {
+        let parse = |s| JavaVersion::parse(s).unwrap();
+        // 基本版本号比较
+        assert!(parse("1.8.0") > parse("1.7.0"));
+        assert!(parse("11.0.2") > parse("1.8.0"));
+        assert!(parse("17.0.1") > parse("11.0.2"));
+        assert_eq!(parse("1.8.0"), parse("1.8.0"));
+        assert!(parse("1.8.0") < parse("1.8.1"));
+
+        // 带更新编号的版本比较
+        assert!(parse("1.8.0_556") > parse("1.8.0_447"));
+        assert!(parse("1.8.0_556") > parse("1.8.0"));
+        assert!(parse("11.0.2") > parse("1.8.0_556"));
+        assert_eq!(parse("1.8.0_556"), parse("1.8.0_556"));
+
+        // 预览版低于正式版
+        assert!(parse("21-ea") < parse("21"));
+        assert!(parse("21-ea") > parse("17.0.9"));
+        assert!(parse("17.0.9+9") < parse("17.0.9+11"));
+    }
+
+    /// 按常见发行商的格式输出版本字符串
+    fn format_vendor(style: u8, major: u32, minor: u32, security: u32, build: u32) -> String {
+        match style {
+            0 => format!("{}.{}.{}", major, minor, security),
+            1 => format!("{}.{}.{}+{}", major, minor, security, build),
+            2 => format!("{}.{}.{}+{}-LTS", major, minor, security, build),
+            3 => format!("jdk-{}.{}.{}+{}", major, minor, security, build),
+            _ => format!("{}.{}.{}+{}-Ubuntu-0ubuntu1", major, minor, security, build),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_parse_modern(
+            style in 0u8..5,
+            major in 9u32..40,
+            minor in 0u32..3,
+            security in 0u32..500,
+            build in 1u32..40,
+        ) {
+            let parsed = JavaVersion::parse(&format_vendor(style, major, minor, security, build)).unwrap();
+            prop_assert_eq!((parsed.major, parsed.minor, parsed.security), (major, minor, security));
+            prop_assert_eq!(parsed.build, if style == 0 { None } else { Some(build) });
+            prop_assert_eq!(parsed.pre, None);
+        }
+
+        #[test]
+        fn prop_parse_legacy(major in 5u32..9, update in 0u32..500, build in 1u32..99) {
+            let parsed = JavaVersion::parse(&format!("1.{}.0_{}-b{:02}", major, update, build)).unwrap();
+            prop_assert_eq!(parsed, v(major, 0, update, Some(build)));
+            let mojang = JavaVersion::parse(&format!("{}u{}", major, update)).unwrap();
+            prop_assert_eq!(mojang, v(major, 0, update, None));
+        }
+
+        #[test]
+        fn prop_order_matches_numbers(
+            a in (8u32..30, 0u32..3, 0u32..500, 1u32..40),
+            b in (8u32..30, 0u32..3, 0u32..500, 1u32..40),
+            style_a in 1u8..5,
+            style_b in 1u8..5,
+        ) {
+            let va = JavaVersion::parse(&format_vendor(style_a, a.0, a.1, a.2, a.3)).unwrap();
+            let vb = JavaVersion::parse(&format_vendor(style_b, b.0, b.1, b.2, b.3)).unwrap();
+            prop_assert_eq!(va.cmp(&vb), a.cmp(&b));
+        }
+
+        #[test]
+        fn prop_display_roundtrip(major in 9u32..40, minor in 0u32..3, security in 0u32..500, build in 1u32..40) {
+            let version = v(major, minor, security, Some(build));
+            prop_assert_eq!(JavaVersion::parse(&version.to_string()), Some(version));
+        }
+
+        #[test]
+        fn prop_parse_never_panics(s in "\\PC{0,24}") {
+            let _ = JavaVersion::parse(&s);
+        }
+    }
+}
@@ -1,15 +1,28 @@
 mod auth;
 mod config;
+mod curseforge;
 mod game;
 mod jre;
+mod modpack;
+mod modrinth;
 mod mojang;
+mod nbt;
 mod util;
 
 use auth::command::*;
 use config::command::*;
+use curseforge::command::*;
 use game::command::*;
+use game::launch::command::*;
 use game::modloader::version::command::*;
+use game::mods::command::*;
+use game::packs::command::*;
+use game::profile::command::*;
+use game::servers::command::*;
+use game::worlds::command::*;
 use jre::command::*;
+use modpack::command::*;
+use modrinth::command::*;
 use mojang::command::*;
 use util::command::*;
 
@@ -26,6 +39,7 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             get_device_code,
+            get_launch_account,
             check_microsoft_login_availability,
             get_minecraft_profile,
             check_game_ownership,
@@ -40,18 +54,78 @@ pub fn run() {
             get_jre_config_command,
             save_jre_config_command,
             get_all_jres,
+            select_jre_for_profile,
             remove_jre,
+            get_mojang_runtimes,
+            install_mojang_runtime_command,
+            get_jdk_package,
+            install_jdk_command,
+            uninstall_jre,
             get_launcher_config_command,
             save_launcher_config_command,
             get_account_config_command,
             save_account_config_command,
             get_local_versions_command,
+            check_version_integrity_command,
+            get_launchable_versions_command,
+            list_profiles_command,
+            get_profiles_command,
+            create_profile_command,
+            update_profile_command,
+            clone_profile_command,
+            rename_profile_command,
+            delete_profile_command,
+            set_last_profile_command,
+            migrate_profile_to_isolated_command,
+            import_launcher_profiles_command,
+            launch_profile_command,
             init_game_path_command,
             init_launcher_command,
             download_files,
+            submit_download_job,
             cancel_download,
+            pause_download,
+            resume_download,
+            get_download_jobs,
+            get_download_job,
+            clear_finished_downloads,
             get_fabric_loader_versions_by_game_version,
+            scan_mods_command,
+            set_mods_enabled_command,
+            get_mod_icon_command,
+            check_mods_command,
+            list_packs_command,
+            list_datapacks_command,
+            set_resource_packs_command,
+            get_pack_icon_command,
+            list_worlds_command,
+            get_world_icon_command,
+            backup_world_command,
+            list_world_backups_command,
+            restore_world_backup_command,
+            duplicate_world_command,
+            delete_world_command,
+            list_servers_command,
+            save_servers_command,
+            ping_server_command,
+            search_modrinth_command,
+            get_modrinth_versions_command,
+            install_modrinth_command,
+            check_modrinth_updates_command,
+            apply_modrinth_update_command,
+            search_curseforge_command,
+            get_curseforge_files_command,
+            install_curseforge_command,
+            identify_curseforge_mods_command,
+            import_mrpack_command,
+            export_mrpack_command,
+            import_curseforge_modpack_command,
+            import_multimc_instance_command,
             update_reqwest_client,
+            probe_download_sources,
+            test_custom_mirror_command,
+            clear_metadata_cache,
+            get_network_status,
             is_path_exists,
             read_local_json,
         ])
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tauri::ipc::Channel;
+
+use super::curseforge::import_curseforge_modpack;
+use super::model::{ModpackImportResult, MrpackExportOptions, MrpackIndex};
+use super::mrpack::{export_mrpack, import_mrpack};
+use super::multimc::import_multimc_instance;
+use crate::curseforge::api::CurseForgeClient;
+use crate::game::mods::util::mod_environment;
+use crate::game::profile::util::{get_profile, resolve_game_directory};
+use crate::game::version::util::resolve_client_json;
+use crate::modrinth::api::ModrinthClient;
+use crate::util::{model::DownloadEvent, scheduler::DownloadEventSink};
+
+/// 导入 `.mrpack` 整合包并新建配置
+#[tauri::command]
+pub async fn import_mrpack_command(
+    on_event: Channel<DownloadEvent>,
+    game_path: PathBuf,
+    path: PathBuf,
+) -> Result<ModpackImportResult, String> {
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    import_mrpack(&game_path, &path, Some(sink)).await
+}
+
+/// 将配置导出为 `.mrpack`
+#[tauri::command]
+pub async fn export_mrpack_command(
+    game_path: PathBuf,
+    profile_id: String,
+    output: PathBuf,
+    options: MrpackExportOptions,
+) -> Result<MrpackIndex, String> {
+    let profile = get_profile(&game_path, &profile_id)?;
+    let resolved = resolve_client_json(&game_path, &profile.version_name)?;
+    let environment = mod_environment(&resolved, None);
+    let game_dir = resolve_game_directory(&game_path, &profile);
+    // 无法访问 Modrinth 时所有文件都放入 overrides
+    let client = ModrinthClient::new().await.ok();
+    export_mrpack(client.as_ref(), &game_dir, &environment, &options, &output).await
+}
+
+/// 导入 CurseForge 整合包并新建配置，需要手动下载的文件在结果中列出
+#[tauri::command]
+pub async fn import_curseforge_modpack_command(
+    on_event: Channel<DownloadEvent>,
+    game_path: PathBuf,
+    path: PathBuf,
+) -> Result<ModpackImportResult, String> {
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    let client = CurseForgeClient::new().await?;
+    import_curseforge_modpack(&client, &game_path, &path, Some(sink)).await
+}
+
+/// 导入 MultiMC / Prism Launcher 的实例文件夹
+#[tauri::command]
+pub async fn import_multimc_instance_command(
+    game_path: PathBuf,
+    path: PathBuf,
+) -> Result<ModpackImportResult, String> {
+    tokio::task::spawn_blocking(move || import_multimc_instance(&game_path, &path))
+        .await
+        .map_err(|e| format!("导入实例失败: {}", e))?
+}
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use zip::ZipArchive;
+
+use super::model::{CurseForgeManifest, ModpackImportResult, ModpackUnavailableFile};
+use super::util::{
+    allocate_game_dir, default_version_name, discard_on_error, extract_overrides, modpack_profile,
+    open_archive, pending_install, read_archive_text,
+};
+use crate::curseforge::api::CurseForgeClient;
+use crate::curseforge::install::download_curseforge_files;
+use crate::curseforge::model::{CurseForgeFile, CurseForgeMod};
+use crate::game::profile::util::create_profile;
+use crate::game::version::model::{ModLoaderInfo, ModLoaderType};
+use crate::util::scheduler::DownloadEventSink;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 分类 ID 对应的目录，未列出的分类放入 mods
+const CLASS_DIRS: &[(u32, &str)] = &[(12, "resourcepacks"), (6552, "shaderpacks")];
+
+pub fn read_curseforge_manifest<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<CurseForgeManifest, String> {
+    let content = read_archive_text(archive, MANIFEST_FILE)?;
+    let manifest: CurseForgeManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("解析 {} 失败: {}", MANIFEST_FILE, e))?;
+    if manifest.manifest_type != "minecraftModpack" {
+        return Err(format!("不支持的整合包类型: {}", manifest.manifest_type));
+    }
+    Ok(manifest)
+}
+
+/// 解析 `forge-47.2.0` 形式的加载器 ID
+pub fn parse_manifest_loader(id: &str) -> Option<ModLoaderInfo> {
+    let (name, version) = id.split_once('-')?;
+    let loader = match name {
+        "forge" => ModLoaderType::Forge,
+        "neoforge" => ModLoaderType::NeoForge,
+        "fabric" => ModLoaderType::Fabric,
+        "quilt" => ModLoaderType::Quilt,
+        _ => return None,
+    };
+    Some(ModLoaderInfo {
+        loader,
+        version: version.to_string(),
+    })
+}
+
+/// 主加载器排在最前
+fn manifest_loaders(manifest: &CurseForgeManifest) -> Vec<ModLoaderInfo> {
+    let mut loaders = manifest.minecraft.mod_loaders.clone();
+    loaders.sort_by_key(|l| !l.primary);
+    loaders
+        .iter()
+        .filter_map(|l| parse_manifest_loader(&l.id))
+        .collect()
+}
+
+/// 导入 CurseForge 整合包：下载文件、应用 overrides，并新建配置
+///
+/// 作者禁止第三方分发的文件不会下载，在结果中列出供用户手动下载；
+/// 已被删除或无法获取的文件列在 `unavailable` 中。
+/// 新建配置前失败时删除已分配的游戏目录。
+pub async fn import_curseforge_modpack(
+    client: &CurseForgeClient,
+    game_path: &Path,
+    pack: &Path,
+    sink: Option<DownloadEventSink>,
+) -> Result<ModpackImportResult, String> {
+    let mut archive = open_archive(pack)?;
+    let manifest = read_curseforge_manifest(&mut archive)?;
+    let minecraft = manifest.minecraft.version.clone();
+    let loaders = manifest_loaders(&manifest);
+    let version_name = default_version_name(&minecraft, &loaders);
+
+    let file_ids: Vec<u32> = manifest
+        .files
+        .iter()
+        .filter(|f| f.required)
+        .map(|f| f.file_id)
+        .collect();
+    let files = client.get_files(&file_ids).await?;
+    let found: HashSet<u32> = files.iter().map(|f| f.id).collect();
+    let missing: Vec<_> = manifest
+        .files
+        .iter()
+        .filter(|f| f.required && !found.contains(&f.file_id))
+        .collect();
+    // 同时查询缺失文件所属的项目，以便列出项目名称
+    let mut mod_ids: Vec<u32> = files
+        .iter()
+        .map(|f| f.mod_id)
+        .chain(missing.iter().map(|f| f.project_id))
+        .collect();
+    mod_ids.sort_unstable();
+    mod_ids.dedup();
+    let projects: HashMap<u32, CurseForgeMod> = client
+        .get_mods(&mod_ids)
+        .await?
+        .into_iter()
+        .map(|m| (m.id, m))
+        .collect();
+    let unavailable = missing
+        .iter()
+        .map(|f| {
+            let project = projects.get(&f.project_id);
+            ModpackUnavailableFile {
+                mod_id: f.project_id,
+                file_id: f.file_id,
+                mod_name: project.map(|m| m.name.clone()),
+                url: match project.and_then(|m| m.links.website_url.clone()) {
+                    Some(url) => url,
+                    None => format!("https://www.curseforge.com/projects/{}", f.project_id),
+                },
+            }
+        })
+        .collect();
+    let mut groups: HashMap<&str, Vec<CurseForgeFile>> = HashMap::new();
+    for file in files {
+        let class_id = projects.get(&file.mod_id).and_then(|m| m.class_id);
+        let dir = CLASS_DIRS
+            .iter()
+            .find(|(id, _)| Some(*id) == class_id)
+            .map_or("mods", |(_, dir)| *dir);
+        groups.entry(dir).or_default().push(file);
+    }
+
+    let game_dir = allocate_game_dir(game_path, &manifest.name);
+    let game_directory = game_path.join(&game_dir);
+    let result = async {
+        fs::create_dir_all(&game_directory).map_err(|e| format!("创建游戏目录失败: {}", e))?;
+        let mut downloaded = 0;
+        let mut manual = Vec::new();
+        for (dir, files) in groups {
+            let result = download_curseforge_files(
+                client,
+                &manifest.name,
+                &files,
+                &game_directory.join(dir),
+                &[],
+                sink.clone(),
+            )
+            .await?;
+            downloaded += result.downloaded.len();
+            manual.extend(result.manual);
+        }
+        extract_overrides(
+            &mut archive,
+            manifest.overrides.as_deref().unwrap_or("overrides"),
+            &game_directory,
+        )?;
+
+        let profile_id = create_profile(
+            game_path,
+            modpack_profile(
+                &manifest.name,
+                &version_name,
+                &game_dir,
+                &minecraft,
+                &loaders,
+            ),
+        )?;
+        Ok((profile_id, downloaded, manual))
+    }
+    .await;
+    let (profile_id, downloaded, manual) = discard_on_error(&game_directory, result)?;
+    let pending_install = pending_install(game_path, &version_name, &minecraft, &loaders);
+    Ok(ModpackImportResult {
+        profile_id,
+        name: manifest.name,
+        game_directory,
+        installed: pending_install.is_none(),
+        pending_install,
+        minecraft,
+        loaders,
+        version_name,
+        downloaded,
+        manual,
+        unavailable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::serve;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn file_json(id: u32, mod_id: u32) -> String {
+        format!(
+            r#"{{ "id": {id}, "modId": {mod_id}, "displayName": "file {id}", "fileName": "file-{id}.zip",
+                  "releaseType": 1, "fileDate": "2024-01-01T00:00:00Z", "fileLength": 1,
+                  "downloadUrl": null }}"#
+        )
+    }
+
+    #[test]
+    fn test_parse_manifest_loader() {
+        let loader = parse_manifest_loader("neoforge-21.1.77").unwrap();
+        assert_eq!(loader.loader, ModLoaderType::NeoForge);
+        assert_eq!(loader.version, "21.1.77");
+        assert!(parse_manifest_loader("rift-1.0").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_import_curseforge_modpack() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let manifest = r#"{
+            "minecraft": { "version": "1.20.1", "modLoaders": [{ "id": "forge-47.2.0", "primary": true }] },
+            "manifestType": "minecraftModpack", "manifestVersion": 1, "name": "CF Pack", "version": "1.0",
+            "files": [
+                { "projectID": 1, "fileID": 10, "required": true },
+                { "projectID": 2, "fileID": 20, "required": true },
+                { "projectID": 3, "fileID": 30, "required": false },
+                { "projectID": 4, "fileID": 40, "required": true },
+                { "projectID": 5, "fileID": 50, "required": true }
+            ],
+            "overrides": "overrides"
+        }"#;
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [(MANIFEST_FILE, manifest), ("overrides/config/a.cfg", "a")] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let pack = game_path.join("pack.zip");
+        fs::write(&pack, writer.finish().unwrap().into_inner()).unwrap();
+
+        let base = serve(|request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let response = match request.path.as_str() {
+                "/v1/mods/files" => {
+                    assert_eq!(body["fileIds"], serde_json::json!([10, 20, 40, 50]));
+                    format!(
+                        r#"{{ "data": [{}, {}] }}"#,
+                        file_json(10, 1),
+                        file_json(20, 2)
+                    )
+                }
+                "/v1/mods" => {
+                    r#"{ "data": [
+                        { "id": 1, "name": "Mod", "slug": "mod", "classId": 6 },
+                        { "id": 2, "name": "Pack", "slug": "pack", "classId": 12 },
+                        { "id": 4, "name": "Gone", "slug": "gone", "classId": 6,
+                          "links": { "websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/gone" } }
+                    ] }"#
+                        .to_string()
+                }
+                _ => return (404, b"{}".to_vec()),
+            };
+            (200, response.into_bytes())
+        })
+        .await;
+        let client = CurseForgeClient::with_client(reqwest::Client::new(), base, "key");
+        let result = import_curseforge_modpack(&client, game_path, &pack, None)
+            .await
+            .unwrap();
+        assert_eq!(result.version_name, "1.20.1-forge-47.2.0");
+        assert_eq!(result.downloaded, 0);
+        assert!(result.game_directory.join("config/a.cfg").is_file());
+        let mut manual: Vec<_> = result
+            .manual
+            .iter()
+            .map(|m| {
+                m.path
+                    .strip_prefix(&result.game_directory)
+                    .unwrap()
+                    .to_path_buf()
+            })
+            .collect();
+        manual.sort();
+        assert_eq!(
+            manual,
+            vec![
+                Path::new("mods/file-10.zip").to_path_buf(),
+                Path::new("resourcepacks/file-20.zip").to_path_buf()
+            ]
+        );
+        assert_eq!(result.unavailable.len(), 2);
+        let gone = &result.unavailable[0];
+        assert_eq!((gone.mod_id, gone.file_id), (4, 40));
+        assert_eq!(gone.mod_name.as_deref(), Some("Gone"));
+        assert_eq!(
+            gone.url,
+            "https://www.curseforge.com/minecraft/mc-mods/gone"
+        );
+        let deleted = &result.unavailable[1];
+        assert_eq!(deleted.mod_name, None);
+        assert_eq!(deleted.url, "https://www.curseforge.com/projects/5");
+    }
+}
@@ -0,0 +1,6 @@
+pub mod command;
+pub mod curseforge;
+pub mod model;
+pub mod mrpack;
+pub mod multimc;
+pub mod util;
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::curseforge::model::CurseForgeManualDownload;
+use crate::game::version::model::ModLoaderInfo;
+
+/// 环境需求：required、optional 或 unsupported
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MrpackEnvSupport {
+    Required,
+    Optional,
+    Unsupported,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MrpackEnv {
+    pub client: MrpackEnvSupport,
+    pub server: MrpackEnvSupport,
+}
+
+/// `modrinth.index.json` 中需要下载的文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+    /// 相对于游戏目录的路径
+    pub path: String,
+    /// 至少包含 sha1 与 sha512
+    pub hashes: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<MrpackEnv>,
+    pub downloads: Vec<String>,
+    pub file_size: u64,
+}
+
+/// `.mrpack` 中的 `modrinth.index.json`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    pub format_version: u32,
+    pub game: String,
+    pub version_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub files: Vec<MrpackFile>,
+    /// minecraft、fabric-loader、quilt-loader、forge、neoforge 到版本号的映射
+    pub dependencies: HashMap<String, String>,
+}
+
+/// 导出 `.mrpack` 的选项
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackExportOptions {
+    pub name: String,
+    /// 整合包自身的版本号
+    pub version_id: String,
+    pub summary: Option<String>,
+    /// 要打包的游戏目录下的文件与文件夹，如 mods、config、options.txt
+    pub include: Vec<String>,
+}
+
+/// 导入整合包的结果
+///
+/// 导入只负责创建配置与放置文件，暂不安装游戏与加载器。`installed` 为 false 时，
+/// 需要按 `minecraft`、`loaders` 安装名为 `version_name` 的版本，`pending_install`
+/// 为展示给用户的提示；在此之前启动该配置会报错并给出同样的提示。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackImportResult {
+    pub profile_id: String,
+    pub name: String,
+    pub game_directory: PathBuf,
+    pub minecraft: String,
+    pub loaders: Vec<ModLoaderInfo>,
+    pub version_name: String,
+    /// 对应的版本是否已安装
+    pub installed: bool,
+    /// 版本未安装时需要用户安装的内容
+    pub pending_install: Option<String>,
+    /// 已下载或复制的文件数量
+    pub downloaded: usize,
+    /// 禁止第三方分发，需要手动下载的文件
+    pub manual: Vec<CurseForgeManualDownload>,
+    /// 已被删除或无法获取，导入时跳过的文件
+    pub unavailable: Vec<ModpackUnavailableFile>,
+}
+
+/// 整合包中已被删除或无法获取的文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackUnavailableFile {
+    pub mod_id: u32,
+    pub file_id: u32,
+    /// 项目也无法获取时为空
+    pub mod_name: Option<String>,
+    /// 项目页面地址
+    pub url: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CurseForgeManifestLoader {
+    /// 如 `forge-47.2.0`、`fabric-0.15.11`
+    pub id: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManifestMinecraft {
+    pub version: String,
+    #[serde(default)]
+    pub mod_loaders: Vec<CurseForgeManifestLoader>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CurseForgeManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: u32,
+    #[serde(rename = "fileID")]
+    pub file_id: u32,
+    #[serde(default = "default_true")]
+    pub required: bool,
+}
+
+/// CurseForge 整合包中的 `manifest.json`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManifest {
+    pub minecraft: CurseForgeManifestMinecraft,
+    pub manifest_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub files: Vec<CurseForgeManifestFile>,
+    pub overrides: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// MultiMC / Prism 实例 `mmc-pack.json` 中的组件
+#[derive(Deserialize, Debug, Clone)]
+pub struct MmcComponent {
+    pub uid: String,
+    pub version: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MmcPack {
+    #[serde(default)]
+    pub components: Vec<MmcComponent>,
+}
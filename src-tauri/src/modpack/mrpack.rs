@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use super::model::{
+    ModpackImportResult, MrpackEnv, MrpackEnvSupport, MrpackExportOptions, MrpackFile, MrpackIndex,
+};
+use super::util::{
+    allocate_game_dir, default_version_name, discard_on_error, extract_overrides, modpack_profile,
+    open_archive, pending_install, read_archive_text, safe_relative_path,
+};
+use crate::game::mods::model::ModEnvironment;
+use crate::game::mods::scanner::DISABLED_SUFFIX;
+use crate::game::profile::util::create_profile;
+use crate::game::version::model::{ModLoaderInfo, ModLoaderType};
+use crate::modrinth::api::ModrinthClient;
+use crate::util::model::DownloadRequest;
+use crate::util::scheduler::{DownloadEventSink, DOWNLOAD_SCHEDULER};
+use crate::util::utils::{sha1_file, sha512_file, verify_sha1, verify_sha512};
+
+const INDEX_FILE: &str = "modrinth.index.json";
+
+/// 导出时会在 Modrinth 上查找的文件夹，其中找不到的文件放入 overrides
+const HOSTED_DIRS: &[&str] = &["mods", "resourcepacks", "shaderpacks"];
+
+/// 整合包规范允许的下载域名
+const ALLOWED_DOWNLOAD_HOSTS: &[&str] = &[
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+/// `dependencies` 中的加载器键名
+const LOADER_KEYS: &[(&str, ModLoaderType)] = &[
+    ("fabric-loader", ModLoaderType::Fabric),
+    ("quilt-loader", ModLoaderType::Quilt),
+    ("forge", ModLoaderType::Forge),
+    ("neoforge", ModLoaderType::NeoForge),
+];
+
+pub fn read_mrpack_index<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<MrpackIndex, String> {
+    let content = read_archive_text(archive, INDEX_FILE)?;
+    let index: MrpackIndex =
+        serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", INDEX_FILE, e))?;
+    if index.game != "minecraft" {
+        return Err(format!("不支持的游戏: {}", index.game));
+    }
+    if index.format_version != 1 {
+        return Err(format!("不支持的整合包格式版本: {}", index.format_version));
+    }
+    Ok(index)
+}
+
+/// 从 `dependencies` 中取出游戏版本与加载器
+pub fn mrpack_dependencies(
+    dependencies: &HashMap<String, String>,
+) -> Result<(String, Vec<ModLoaderInfo>), String> {
+    let minecraft = dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or_else(|| "整合包未指定游戏版本".to_string())?;
+    let loaders = LOADER_KEYS
+        .iter()
+        .filter_map(|(key, loader)| {
+            dependencies.get(*key).map(|version| ModLoaderInfo {
+                loader: *loader,
+                version: version.clone(),
+            })
+        })
+        .collect();
+    Ok((minecraft, loaders))
+}
+
+/// 下载地址是否为 HTTPS 且域名在允许列表中
+fn is_allowed_download(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url
+                .host_str()
+                .is_some_and(|host| ALLOWED_DOWNLOAD_HOSTS.contains(&host))
+    })
+}
+
+/// 文件存在且与整合包中的哈希一致，优先使用 SHA-512
+fn verify_mrpack_file(path: &Path, file: &MrpackFile) -> bool {
+    if let Some(sha512) = file.hashes.get("sha512") {
+        verify_sha512(path, sha512)
+    } else if let Some(sha1) = file.hashes.get("sha1") {
+        verify_sha1(path, sha1)
+    } else {
+        false
+    }
+}
+
+/// 下载客户端需要的文件到游戏目录，返回下载的数量
+///
+/// 只使用域名在允许列表中的下载地址，没有可用地址时报错。
+pub async fn download_mrpack_files(
+    name: &str,
+    files: &[MrpackFile],
+    game_dir: &Path,
+    sink: Option<DownloadEventSink>,
+) -> Result<usize, String> {
+    let mut targets = Vec::new();
+    for file in files {
+        if file
+            .env
+            .as_ref()
+            .is_some_and(|env| env.client == MrpackEnvSupport::Unsupported)
+        {
+            continue;
+        }
+        let relative =
+            safe_relative_path(&file.path).ok_or_else(|| format!("无效的路径: {}", file.path))?;
+        if !file.hashes.contains_key("sha512") && !file.hashes.contains_key("sha1") {
+            return Err(format!("{} 缺少校验值", file.path));
+        }
+        let url = file
+            .downloads
+            .iter()
+            .find(|url| is_allowed_download(url))
+            .ok_or_else(|| format!("{} 没有允许的下载地址", file.path))?;
+        targets.push((game_dir.join(relative), url, file));
+    }
+
+    let requests: Vec<DownloadRequest> = targets
+        .iter()
+        .filter(|(path, _, file)| !verify_mrpack_file(path, file))
+        .map(|(path, url, file)| DownloadRequest {
+            url: url.to_string(),
+            path: path.clone(),
+            priority: Default::default(),
+            size: Some(file.file_size),
+        })
+        .collect();
+    if !requests.is_empty() {
+        DOWNLOAD_SCHEDULER.run(name, requests, sink).await?;
+    }
+    for (path, _, file) in &targets {
+        if !verify_mrpack_file(path, file) {
+            let _ = fs::remove_file(path);
+            return Err(format!("文件校验失败: {}", path.display()));
+        }
+    }
+    Ok(targets.len())
+}
+
+/// 导入 `.mrpack`：下载文件、应用 overrides 与 client-overrides，并新建配置
+///
+/// 新建配置前失败时删除已分配的游戏目录。
+pub async fn import_mrpack(
+    game_path: &Path,
+    pack: &Path,
+    sink: Option<DownloadEventSink>,
+) -> Result<ModpackImportResult, String> {
+    let mut archive = open_archive(pack)?;
+    let index = read_mrpack_index(&mut archive)?;
+    let (minecraft, loaders) = mrpack_dependencies(&index.dependencies)?;
+    let version_name = default_version_name(&minecraft, &loaders);
+
+    let game_dir = allocate_game_dir(game_path, &index.name);
+    let game_directory = game_path.join(&game_dir);
+    let result = async {
+        fs::create_dir_all(&game_directory).map_err(|e| format!("创建游戏目录失败: {}", e))?;
+        let downloaded =
+            download_mrpack_files(&index.name, &index.files, &game_directory, sink).await?;
+        // client-overrides 在后，覆盖 overrides 中的同名文件
+        extract_overrides(&mut archive, "overrides", &game_directory)?;
+        extract_overrides(&mut archive, "client-overrides", &game_directory)?;
+        let profile_id = create_profile(
+            game_path,
+            modpack_profile(&index.name, &version_name, &game_dir, &minecraft, &loaders),
+        )?;
+        Ok((profile_id, downloaded))
+    }
+    .await;
+    let (profile_id, downloaded) = discard_on_error(&game_directory, result)?;
+    let pending_install = pending_install(game_path, &version_name, &minecraft, &loaders);
+    Ok(ModpackImportResult {
+        profile_id,
+        name: index.name,
+        game_directory,
+        installed: pending_install.is_none(),
+        pending_install,
+        minecraft,
+        loaders,
+        version_name,
+        downloaded,
+        manual: Vec::new(),
+        unavailable: Vec::new(),
+    })
+}
+
+/// 展开要打包的条目，返回相对于游戏目录的文件路径
+fn collect_files(game_dir: &Path, include: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut stack: Vec<PathBuf> = Vec::new();
+    for entry in include {
+        let relative = safe_relative_path(entry).ok_or_else(|| format!("无效的路径: {}", entry))?;
+        if game_dir.join(&relative).exists() {
+            stack.push(relative);
+        }
+    }
+    while let Some(relative) = stack.pop() {
+        let path = game_dir.join(&relative);
+        if path.is_dir() {
+            let entries =
+                fs::read_dir(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+            for entry in entries.flatten() {
+                stack.push(relative.join(entry.file_name()));
+            }
+        } else if path.is_file() {
+            files.push(relative);
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// 压缩包中使用 `/` 分隔的路径
+fn archive_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 将游戏目录打包为 `.mrpack`
+///
+/// mods、resourcepacks、shaderpacks 中能在 Modrinth 上找到的文件写入 `files`，
+/// 其余文件放入 `overrides/`。`client` 为空时全部放入 overrides。
+pub async fn export_mrpack(
+    client: Option<&ModrinthClient>,
+    game_dir: &Path,
+    environment: &ModEnvironment,
+    options: &MrpackExportOptions,
+    output: &Path,
+) -> Result<MrpackIndex, String> {
+    let minecraft = environment
+        .minecraft
+        .clone()
+        .ok_or_else(|| "无法确定配置的游戏版本".to_string())?;
+    let mut dependencies = HashMap::from([("minecraft".to_string(), minecraft)]);
+    for loader in &environment.loaders {
+        if let Some((key, _)) = LOADER_KEYS.iter().find(|(_, l)| *l == loader.loader) {
+            dependencies.insert(key.to_string(), loader.version.clone());
+        }
+    }
+
+    let files = collect_files(game_dir, &options.include)?;
+    // 已禁用的文件无法在 Modrinth 上对应，原样放入 overrides
+    let candidates: Vec<(PathBuf, String)> = files
+        .iter()
+        .filter(|relative| {
+            relative
+                .parent()
+                .is_some_and(|dir| HOSTED_DIRS.iter().any(|d| Path::new(d) == dir))
+                && !relative.to_string_lossy().ends_with(DISABLED_SUFFIX)
+        })
+        .filter_map(|relative| {
+            sha512_file(&game_dir.join(relative))
+                .ok()
+                .map(|hash| (relative.clone(), hash))
+        })
+        .collect();
+    let mut versions = match client {
+        Some(client) if !candidates.is_empty() => {
+            let hashes: Vec<String> = candidates.iter().map(|(_, h)| h.clone()).collect();
+            client.get_versions_by_hashes(&hashes, "sha512").await?
+        }
+        _ => HashMap::new(),
+    };
+
+    let mut index = MrpackIndex {
+        format_version: 1,
+        game: "minecraft".to_string(),
+        version_id: options.version_id.clone(),
+        name: options.name.clone(),
+        summary: options.summary.clone(),
+        files: Vec::new(),
+        dependencies,
+    };
+    let mut hosted = Vec::new();
+    for (relative, sha512) in candidates {
+        let Some(version) = versions.remove(&sha512) else {
+            continue;
+        };
+        let Some(file) = version.files.iter().find(|f| {
+            f.hashes
+                .get("sha512")
+                .is_some_and(|h| h.eq_ignore_ascii_case(&sha512))
+        }) else {
+            continue;
+        };
+        let path = game_dir.join(&relative);
+        let sha1 = sha1_file(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        index.files.push(MrpackFile {
+            path: archive_path(&relative),
+            hashes: HashMap::from([("sha1".to_string(), sha1), ("sha512".to_string(), sha512)]),
+            env: Some(MrpackEnv {
+                client: MrpackEnvSupport::Required,
+                server: MrpackEnvSupport::Required,
+            }),
+            downloads: vec![file.url.clone()],
+            file_size: file.size,
+        });
+        hosted.push(relative);
+    }
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let out = File::create(output).map_err(|e| format!("创建 {} 失败: {}", output.display(), e))?;
+    let mut writer = ZipWriter::new(out);
+    let write_error = |e: zip::result::ZipError| format!("写入整合包失败: {}", e);
+    writer
+        .start_file(INDEX_FILE, SimpleFileOptions::default())
+        .map_err(write_error)?;
+    let content =
+        serde_json::to_vec_pretty(&index).map_err(|e| format!("序列化索引失败: {}", e))?;
+    writer
+        .write_all(&content)
+        .map_err(|e| format!("写入整合包失败: {}", e))?;
+    for relative in files.iter().filter(|f| !hosted.contains(f)) {
+        writer
+            .start_file(
+                format!("overrides/{}", archive_path(relative)),
+                SimpleFileOptions::default(),
+            )
+            .map_err(write_error)?;
+        let mut source = File::open(game_dir.join(relative))
+            .map_err(|e| format!("读取 {} 失败: {}", relative.display(), e))?;
+        std::io::copy(&mut source, &mut writer).map_err(|e| format!("写入整合包失败: {}", e))?;
+    }
+    writer.finish().map_err(write_error)?;
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::profile::model::GameDir;
+    use crate::game::profile::util::get_profile;
+    use crate::util::test_server::serve;
+    use std::io::Cursor;
+
+    #[tokio::test]
+    async fn test_import_mrpack() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let pack = game_path.join("pack.mrpack");
+        let index = r#"{
+            "formatVersion": 1, "game": "minecraft", "versionId": "1.0.0", "name": "Test Pack",
+            "files": [{
+                "path": "mods/server-only.jar",
+                "hashes": { "sha1": "00", "sha512": "00" },
+                "env": { "client": "unsupported", "server": "required" },
+                "downloads": ["https://cdn.modrinth.com/server-only.jar"],
+                "fileSize": 1
+            }],
+            "dependencies": { "minecraft": "1.20.1", "fabric-loader": "0.15.11" }
+        }"#;
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            (INDEX_FILE, index),
+            ("overrides/config/a.txt", "common"),
+            ("overrides/options.txt", "lang:en_us"),
+            ("client-overrides/options.txt", "lang:zh_cn"),
+            ("overrides/../escape.txt", "evil"),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        fs::write(&pack, writer.finish().unwrap().into_inner()).unwrap();
+
+        let result = import_mrpack(game_path, &pack, None).await.unwrap();
+        assert_eq!(result.minecraft, "1.20.1");
+        assert_eq!(result.version_name, "fabric-loader-0.15.11-1.20.1");
+        assert_eq!(result.downloaded, 0);
+        assert!(!result.installed);
+        assert_eq!(
+            result.pending_install.as_deref(),
+            Some("版本 fabric-loader-0.15.11-1.20.1 尚未安装，请先安装 Minecraft 1.20.1 + Fabric 0.15.11 并命名为 fabric-loader-0.15.11-1.20.1")
+        );
+        let dir = &result.game_directory;
+        assert_eq!(dir, &game_path.join("modpacks/Test Pack"));
+        assert_eq!(
+            fs::read_to_string(dir.join("config/a.txt")).unwrap(),
+            "common"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("options.txt")).unwrap(),
+            "lang:zh_cn"
+        );
+        assert!(!dir.join("mods/server-only.jar").exists());
+        assert!(!game_path.join("modpacks/escape.txt").exists());
+
+        let profile = get_profile(game_path, &result.profile_id).unwrap();
+        assert_eq!(profile.name, "Test Pack");
+        assert!(matches!(profile.game_dir, GameDir::Custom(ref d) if d == "modpacks/Test Pack"));
+        let requirement = profile.requirement.unwrap();
+        assert_eq!(requirement.minecraft, "1.20.1");
+        assert_eq!(requirement.loaders, result.loaders);
+
+        // 同名整合包使用新的目录
+        let again = import_mrpack(game_path, &pack, None).await.unwrap();
+        assert_eq!(
+            again.game_directory,
+            game_path.join("modpacks/Test Pack (2)")
+        );
+    }
+
+    #[test]
+    fn test_is_allowed_download() {
+        assert!(is_allowed_download(
+            "https://cdn.modrinth.com/data/AANobbMI/sodium.jar"
+        ));
+        assert!(is_allowed_download(
+            "https://github.com/owner/repo/releases/download/v1/a.jar"
+        ));
+        assert!(!is_allowed_download("http://cdn.modrinth.com/a.jar"));
+        assert!(!is_allowed_download(
+            "https://cdn.modrinth.com.evil.com/a.jar"
+        ));
+        assert!(!is_allowed_download("https://example.com/a.jar"));
+        assert!(!is_allowed_download("not a url"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_import_removes_game_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_path = temp.path();
+        let pack = game_path.join("pack.mrpack");
+        let index = r#"{
+            "formatVersion": 1, "game": "minecraft", "versionId": "1.0.0", "name": "Bad Pack",
+            "files": [{
+                "path": "mods/evil.jar",
+                "hashes": { "sha1": "00", "sha512": "00" },
+                "downloads": ["https://example.com/evil.jar"],
+                "fileSize": 1
+            }],
+            "dependencies": { "minecraft": "1.20.1" }
+        }"#;
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(INDEX_FILE, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(index.as_bytes()).unwrap();
+        fs::write(&pack, writer.finish().unwrap().into_inner()).unwrap();
+
+        let err = import_mrpack(game_path, &pack, None).await.unwrap_err();
+        assert!(err.contains("没有允许的下载地址"), "{}", err);
+        assert!(!game_path.join("modpacks/Bad Pack").exists());
+    }
+
+    #[tokio::test]
+    async fn test_export_mrpack() {
+        let temp = tempfile::tempdir().unwrap();
+        let game_dir = temp.path();
+        fs::create_dir_all(game_dir.join("mods")).unwrap();
+        fs::create_dir_all(game_dir.join("config")).unwrap();
+        fs::write(game_dir.join("mods/hosted.jar"), b"hosted").unwrap();
+        fs::write(game_dir.join("mods/custom.jar"), b"custom").unwrap();
+        fs::write(game_dir.join("config/a.toml"), b"a = 1").unwrap();
+        fs::write(game_dir.join("options.txt"), b"lang:en_us").unwrap();
+        let hosted = sha512_file(&game_dir.join("mods/hosted.jar")).unwrap();
+
+        let known = hosted.clone();
+        let base = serve(move |request| {
+            assert_eq!(request.path, "/version_files");
+            let version = format!(
+                r#"{{ "id": "v1", "project_id": "p1", "name": "Hosted", "version_number": "1.0",
+                      "version_type": "release", "date_published": "2024-01-01T00:00:00Z",
+                      "files": [{{ "hashes": {{ "sha512": "{known}" }}, "url": "https://cdn.modrinth.com/hosted.jar",
+                                  "filename": "hosted.jar", "primary": true, "size": 6 }}] }}"#
+            );
+            (200, format!(r#"{{ "{}": {} }}"#, known, version).into_bytes())
+        })
+        .await;
+        let client = ModrinthClient::with_client(reqwest::Client::new(), base);
+        let environment = ModEnvironment {
+            minecraft: Some("1.20.1".to_string()),
+            loaders: vec![ModLoaderInfo {
+                loader: ModLoaderType::Fabric,
+                version: "0.15.11".to_string(),
+            }],
+            java: None,
+        };
+        let options = MrpackExportOptions {
+            name: "Export".to_string(),
+            version_id: "1.0.0".to_string(),
+            summary: None,
+            include: vec![
+                "mods".to_string(),
+                "config".to_string(),
+                "options.txt".to_string(),
+            ],
+        };
+        let output = game_dir.join("out/export.mrpack");
+        let index = export_mrpack(Some(&client), game_dir, &environment, &options, &output)
+            .await
+            .unwrap();
+        assert_eq!(index.files.len(), 1);
+        assert_eq!(index.files[0].path, "mods/hosted.jar");
+        assert_eq!(index.dependencies["fabric-loader"], "0.15.11");
+
+        let mut archive = open_archive(&output).unwrap();
+        let read = read_mrpack_index(&mut archive).unwrap();
+        assert_eq!(read.files[0].hashes["sha512"], hosted);
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                INDEX_FILE,
+                "overrides/config/a.toml",
+                "overrides/mods/custom.jar",
+                "overrides/options.txt",
+            ]
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::model::{MmcPack, ModpackImportResult};
+use super::util::{
+    allocate_game_dir, default_version_name, discard_on_error, modpack_profile, pending_install,
+};
+use crate::game::profile::import::{java_home_from_executable, parse_memory_size};
+use crate::game::profile::model::{AdvancedProfile, JvmMemory, Resolution};
+use crate::game::profile::util::create_profile;
+use crate::game::version::model::{ModLoaderInfo, ModLoaderType};
+use crate::util::utils::copy_dir_all;
+
+/// 组件 uid 对应的加载器
+const LOADER_UIDS: &[(&str, ModLoaderType)] = &[
+    ("net.fabricmc.fabric-loader", ModLoaderType::Fabric),
+    ("org.quiltmc.quilt-loader", ModLoaderType::Quilt),
+    ("net.minecraftforge", ModLoaderType::Forge),
+    ("net.neoforged", ModLoaderType::NeoForge),
+    ("com.mumfrey.liteloader", ModLoaderType::LiteLoader),
+];
+
+/// 解析 `instance.cfg`，忽略分节，每行为 `键=值`
+pub fn parse_instance_cfg(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('[') && !line.starts_with('#') && !line.starts_with(';'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().trim_matches('"').to_string()))
+        .collect()
+}
+
+/// 从组件中取出游戏版本与加载器
+pub fn mmc_components(pack: &MmcPack) -> Result<(String, Vec<ModLoaderInfo>), String> {
+    let minecraft = pack
+        .components
+        .iter()
+        .find(|c| c.uid == "net.minecraft")
+        .and_then(|c| c.version.clone())
+        .ok_or_else(|| "实例未指定游戏版本".to_string())?;
+    let loaders = pack
+        .components
+        .iter()
+        .filter_map(|c| {
+            let (_, loader) = LOADER_UIDS.iter().find(|(uid, _)| *uid == c.uid)?;
+            Some(ModLoaderInfo {
+                loader: *loader,
+                version: c.version.clone()?,
+            })
+        })
+        .collect();
+    Ok((minecraft, loaders))
+}
+
+/// 实例中的游戏目录，新版为 `minecraft`，旧版为 `.minecraft`
+fn instance_game_dir(instance: &Path) -> Option<PathBuf> {
+    ["minecraft", ".minecraft"]
+        .iter()
+        .map(|name| instance.join(name))
+        .find(|path| path.is_dir())
+}
+
+/// 导入 MultiMC / Prism Launcher 的实例文件夹，复制游戏目录并新建配置
+pub fn import_multimc_instance(
+    game_path: &Path,
+    instance: &Path,
+) -> Result<ModpackImportResult, String> {
+    let cfg = fs::read_to_string(instance.join("instance.cfg"))
+        .map_err(|e| format!("读取 instance.cfg 失败: {}", e))?;
+    let cfg = parse_instance_cfg(&cfg);
+    let pack = fs::read_to_string(instance.join("mmc-pack.json"))
+        .map_err(|e| format!("读取 mmc-pack.json 失败: {}", e))?;
+    let pack: MmcPack =
+        serde_json::from_str(&pack).map_err(|e| format!("解析 mmc-pack.json 失败: {}", e))?;
+    let (minecraft, loaders) = mmc_components(&pack)?;
+    let version_name = default_version_name(&minecraft, &loaders);
+
+    let name = cfg
+        .get("name")
+        .filter(|n| !n.is_empty())
+        .cloned()
+        .or_else(|| {
+            instance
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| version_name.clone());
+    let game_dir = allocate_game_dir(game_path, &name);
+    let game_directory = game_path.join(&game_dir);
+    let copied = discard_on_error(
+        &game_directory,
+        match instance_game_dir(instance) {
+            Some(source) => copy_dir_all(&source, &game_directory),
+            None => fs::create_dir_all(&game_directory)
+                .map(|_| 0)
+                .map_err(|e| format!("创建游戏目录失败: {}", e)),
+        },
+    )?;
+
+    // 未写 Override* 的旧版实例直接使用对应的值
+    let overridden = |key: &str| cfg.get(key).is_none_or(|v| v == "true");
+    let value = |key: &str| cfg.get(key).filter(|v| !v.is_empty()).cloned();
+    let mut profile = modpack_profile(&name, &version_name, &game_dir, &minecraft, &loaders);
+    if overridden("OverrideMemory") {
+        profile.jvm_memory = value("MaxMemAlloc")
+            .and_then(|v| v.parse().ok())
+            .map(JvmMemory::Custom);
+    }
+    if overridden("OverrideJavaLocation") {
+        profile.jre_path = value("JavaPath").map(|p| java_home_from_executable(&p));
+    }
+    if overridden("OverrideWindow") {
+        let size = |key: &str| value(key).and_then(|v| v.parse().ok());
+        if let (Some(width), Some(height)) = (size("MinecraftWinWidth"), size("MinecraftWinHeight"))
+        {
+            profile.resolution = Some(Resolution { width, height });
+        }
+        profile.fullscreen = value("LaunchMaximized").map(|v| v == "true");
+    }
+    if overridden("OverrideJavaArgs") {
+        if let Some(args) = value("JvmArgs") {
+            // 内存参数已单独处理
+            let rest: Vec<&str> = args
+                .split_whitespace()
+                .filter(|arg| {
+                    if let Some(size) = arg.strip_prefix("-Xmx") {
+                        if profile.jvm_memory.is_none() {
+                            profile.jvm_memory = parse_memory_size(size).map(JvmMemory::Custom);
+                        }
+                        return false;
+                    }
+                    !arg.starts_with("-Xms")
+                })
+                .collect();
+            if !rest.is_empty() {
+                profile.advanced = Some(AdvancedProfile {
+                    jvm_args: Some(rest.join(" ")),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    let profile_id = discard_on_error(&game_directory, create_profile(game_path, profile))?;
+    let pending_install = pending_install(game_path, &version_name, &minecraft, &loaders);
+    Ok(ModpackImportResult {
+        profile_id,
+        name,
+        game_directory,
+        installed: pending_install.is_none(),
+        pending_install,
+        minecraft,
+        loaders,
+        version_name,
+        downloaded: copied,
+        manual: Vec::new(),
+        unavailable: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::profile::model::GameDir;
+    use crate::game::profile::util::get_profile;
+
+    #[test]
+    fn test_import_multimc_instance() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let game_path = root.join("game");
+        let instance = root.join("instances/Prism Pack");
+        fs::create_dir_all(instance.join("minecraft/mods")).unwrap();
+        fs::create_dir_all(instance.join("minecraft/config")).unwrap();
+        fs::write(instance.join("minecraft/mods/a.jar"), b"a").unwrap();
+        fs::write(instance.join("minecraft/config/b.toml"), b"b").unwrap();
+        fs::write(
+            instance.join("instance.cfg"),
+            "[General]\nname=Friends Pack\nOverrideMemory=true\nMaxMemAlloc=6144\n\
+             OverrideJavaArgs=true\nJvmArgs=-Xms1G -XX:+UseZGC\nOverrideWindow=false\n\
+             MinecraftWinWidth=1280\nMinecraftWinHeight=720\n",
+        )
+        .unwrap();
+        fs::write(
+            instance.join("mmc-pack.json"),
+            r#"{ "formatVersion": 1, "components": [
+                { "uid": "org.lwjgl3", "version": "3.3.1" },
+                { "uid": "net.minecraft", "version": "1.20.1" },
+                { "uid": "net.minecraftforge", "version": "47.2.0" }
+            ] }"#,
+        )
+        .unwrap();
+
+        let result = import_multimc_instance(&game_path, &instance).unwrap();
+        assert_eq!(result.name, "Friends Pack");
+        assert_eq!(result.version_name, "1.20.1-forge-47.2.0");
+        assert_eq!(result.downloaded, 2);
+        assert!(result.game_directory.join("mods/a.jar").is_file());
+        assert!(result.game_directory.join("config/b.toml").is_file());
+
+        let profile = get_profile(&game_path, &result.profile_id).unwrap();
+        assert!(matches!(profile.game_dir, GameDir::Custom(ref d) if d == "modpacks/Friends Pack"));
+        assert!(matches!(profile.jvm_memory, Some(JvmMemory::Custom(6144))));
+        assert!(profile.resolution.is_none());
+        assert_eq!(
+            profile.advanced.unwrap().jvm_args.as_deref(),
+            Some("-XX:+UseZGC")
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+use zip::ZipArchive;
+
+use crate::game::profile::model::{GameDir, Profile, ProfileIcon, VersionRequirement};
+use crate::game::profile::util::missing_version_message;
+use crate::game::version::model::{ModLoaderInfo, ModLoaderType};
+use crate::game::version::util::version_installed;
+
+/// 整合包游戏目录所在的文件夹，相对于游戏目录
+const MODPACKS_DIR: &str = "modpacks";
+
+/// 整合包中的相对路径，拒绝绝对路径与 `..`
+pub fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!result.as_os_str().is_empty()).then_some(result)
+}
+
+/// 安装器生成的版本名，与官方安装器保持一致
+pub fn default_version_name(minecraft: &str, loaders: &[ModLoaderInfo]) -> String {
+    match loaders.first() {
+        Some(l) => match l.loader {
+            ModLoaderType::Fabric => format!("fabric-loader-{}-{}", l.version, minecraft),
+            ModLoaderType::Quilt => format!("quilt-loader-{}-{}", l.version, minecraft),
+            ModLoaderType::Forge => format!("{}-forge-{}", minecraft, l.version),
+            ModLoaderType::NeoForge => format!("neoforge-{}", l.version),
+            ModLoaderType::OptiFine => format!("{}-OptiFine_{}", minecraft, l.version),
+            ModLoaderType::LiteLoader => format!("{}-LiteLoader{}", minecraft, l.version),
+        },
+        None => minecraft.to_string(),
+    }
+}
+
+/// 导入失败时删除已分配的游戏目录，避免留下没有配置使用的目录
+pub fn discard_on_error<T>(game_directory: &Path, result: Result<T, String>) -> Result<T, String> {
+    if result.is_err() {
+        let _ = fs::remove_dir_all(game_directory);
+    }
+    result
+}
+
+/// 为整合包分配游戏目录 `modpacks/<名称>`，已存在时追加序号，返回相对路径
+pub fn allocate_game_dir(game_path: &Path, name: &str) -> String {
+    let base: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let base = base.trim().trim_matches('.');
+    let base = if base.is_empty() { "modpack" } else { base };
+    let mut dir = format!("{}/{}", MODPACKS_DIR, base);
+    let mut index = 2;
+    while game_path.join(&dir).exists() {
+        dir = format!("{}/{} ({})", MODPACKS_DIR, base, index);
+        index += 1;
+    }
+    dir
+}
+
+fn loader_icon(loaders: &[ModLoaderInfo]) -> ProfileIcon {
+    match loaders.first().map(|l| l.loader) {
+        Some(ModLoaderType::Fabric) => ProfileIcon::Fabric,
+        Some(ModLoaderType::Quilt) => ProfileIcon::Quilt,
+        Some(ModLoaderType::Forge) => ProfileIcon::Forge,
+        Some(ModLoaderType::NeoForge) => ProfileIcon::NeoForge,
+        Some(ModLoaderType::OptiFine) => ProfileIcon::OptiFine,
+        Some(ModLoaderType::LiteLoader) => ProfileIcon::LiteLoader,
+        None => ProfileIcon::GrassBlock,
+    }
+}
+
+/// 整合包使用的配置，游戏目录为 `game_dir`，记录需要安装的游戏版本与加载器
+pub fn modpack_profile(
+    name: &str,
+    version_name: &str,
+    game_dir: &str,
+    minecraft: &str,
+    loaders: &[ModLoaderInfo],
+) -> Profile {
+    Profile {
+        icon: loader_icon(loaders),
+        name: name.to_string(),
+        version_name: version_name.to_string(),
+        game_dir: GameDir::Custom(game_dir.to_string()),
+        jre_path: None,
+        jvm_memory: None,
+        resolution: None,
+        fullscreen: None,
+        show_logs: None,
+        server_address: None,
+        advanced: None,
+        requirement: Some(VersionRequirement {
+            minecraft: minecraft.to_string(),
+            loaders: loaders.to_vec(),
+        }),
+    }
+}
+
+/// 整合包对应的版本未安装时，返回需要用户安装的内容
+// TODO: 实现游戏与加载器的安装后，在导入时直接安装，不再需要用户手动安装
+pub fn pending_install(
+    game_path: &Path,
+    version_name: &str,
+    minecraft: &str,
+    loaders: &[ModLoaderInfo],
+) -> Option<String> {
+    if version_installed(game_path, version_name) {
+        return None;
+    }
+    let requirement = VersionRequirement {
+        minecraft: minecraft.to_string(),
+        loaders: loaders.to_vec(),
+    };
+    Some(missing_version_message(version_name, Some(&requirement)))
+}
+
+/// 打开整合包压缩文件
+pub fn open_archive(path: &Path) -> Result<ZipArchive<File>, String> {
+    let file = File::open(path).map_err(|e| format!("打开整合包失败: {}", e))?;
+    ZipArchive::new(file).map_err(|e| format!("读取整合包失败: {}", e))
+}
+
+/// 读取压缩包中的文本文件
+pub fn read_archive_text<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<String, String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("整合包中缺少 {}", name))?;
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(content)
+}
+
+/// 将压缩包中 `prefix/` 下的内容解压到目标目录，返回解压的文件数量
+pub fn extract_overrides<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    prefix: &str,
+    target: &Path,
+) -> Result<usize, String> {
+    let prefix = format!("{}/", prefix.trim_end_matches('/'));
+    let mut count = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let Some(relative) = entry.name().strip_prefix(&prefix) else {
+            continue;
+        };
+        let Some(relative) = safe_relative_path(relative) else {
+            continue;
+        };
+        let out_path = target.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|e| format!("创建目录失败: {}", e))?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        let mut out = File::create(&out_path)
+            .map_err(|e| format!("写入 {} 失败: {}", out_path.display(), e))?;
+        io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("写入 {} 失败: {}", out_path.display(), e))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(
+            safe_relative_path("mods/sodium.jar"),
+            Some(PathBuf::from("mods/sodium.jar"))
+        );
+        assert_eq!(
+            safe_relative_path("./config/a.toml"),
+            Some(PathBuf::from("config/a.toml"))
+        );
+        assert_eq!(safe_relative_path("../evil.jar"), None);
+        assert_eq!(safe_relative_path("mods/../../evil.jar"), None);
+        assert_eq!(safe_relative_path("/etc/passwd"), None);
+        assert_eq!(safe_relative_path(""), None);
+    }
+
+    #[test]
+    fn test_default_version_name() {
+        let loader = |loader, version: &str| ModLoaderInfo {
+            loader,
+            version: version.to_string(),
+        };
+        assert_eq!(default_version_name("1.20.1", &[]), "1.20.1");
+        assert_eq!(
+            default_version_name("1.20.1", &[loader(ModLoaderType::Fabric, "0.15.11")]),
+            "fabric-loader-0.15.11-1.20.1"
+        );
+        assert_eq!(
+            default_version_name("1.20.1", &[loader(ModLoaderType::Forge, "47.2.0")]),
+            "1.20.1-forge-47.2.0"
+        );
+        assert_eq!(
+            default_version_name("1.21.1", &[loader(ModLoaderType::NeoForge, "21.1.77")]),
+            "neoforge-21.1.77"
+        );
+        assert_eq!(
+            default_version_name(
+                "1.12.2",
+                &[loader(ModLoaderType::LiteLoader, "1.12.2-SNAPSHOT")]
+            ),
+            "1.12.2-LiteLoader1.12.2-SNAPSHOT"
+        );
+    }
+}
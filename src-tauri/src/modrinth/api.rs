@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use reqwest::header::USER_AGENT;
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::model::{
+    ModrinthSearchQuery, ModrinthSearchResult, ModrinthUpdateRequest, ModrinthVersion,
+};
+use crate::util::network::ensure_online;
+use crate::util::reqwest_client::REQWEST_CLIENT;
+
+const MODRINTH_API: &str = "https://api.modrinth.com/v2";
+/// Modrinth 要求请求带有能识别客户端的 User-Agent
+const MODRINTH_USER_AGENT: &str = concat!("AsanMCL/", env!("CARGO_PKG_VERSION"));
+
+/// Modrinth API v2 客户端
+pub struct ModrinthClient {
+    client: Client,
+    base_url: String,
+}
+
+/// 将字符串列表编码为 API 查询参数使用的 JSON 数组
+fn json_list(values: &[&str]) -> String {
+    serde_json::to_string(values).unwrap_or_default()
+}
+
+/// 根据搜索条件生成 facets，同一组内为或，组之间为且
+pub fn build_facets(query: &ModrinthSearchQuery) -> String {
+    let mut facets: Vec<Vec<String>> = vec![vec![format!(
+        "project_type:{}",
+        query.project_type.as_deref().unwrap_or("mod")
+    )]];
+    if let Some(loader) = &query.loader {
+        facets.push(vec![format!("categories:{}", loader)]);
+    }
+    if let Some(version) = &query.game_version {
+        facets.push(vec![format!("versions:{}", version)]);
+    }
+    if let Some(category) = &query.category {
+        facets.push(vec![format!("categories:{}", category)]);
+    }
+    serde_json::to_string(&facets).unwrap_or_default()
+}
+
+impl ModrinthClient {
+    /// 使用共享的 HTTP 客户端访问 Modrinth
+    pub async fn new() -> Result<Self, String> {
+        ensure_online("访问 Modrinth").await?;
+        let client = {
+            let guard = REQWEST_CLIENT.lock().await;
+            match &*guard {
+                Some(c) => c.clone(),
+                None => return Err("HTTP客户端未初始化".to_string()),
+            }
+        };
+        Ok(Self::with_client(client, MODRINTH_API))
+    }
+
+    pub fn with_client(client: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        let response = request
+            .header(USER_AGENT, MODRINTH_USER_AGENT)
+            .send()
+            .await
+            .map_err(|e| format!("请求 Modrinth 失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "请求 Modrinth 失败: {} {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("解析 Modrinth 响应失败: {}", e))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, String> {
+        let url = format!("{}{}", self.base_url, path);
+        self.send(self.client.get(url).query(query)).await
+    }
+
+    async fn post<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, String> {
+        let url = format!("{}{}", self.base_url, path);
+        self.send(self.client.post(url).json(body)).await
+    }
+
+    /// 搜索项目
+    pub async fn search(
+        &self,
+        query: &ModrinthSearchQuery,
+    ) -> Result<ModrinthSearchResult, String> {
+        let mut params = vec![
+            ("query", query.query.clone()),
+            ("facets", build_facets(query)),
+            (
+                "index",
+                query
+                    .index
+                    .clone()
+                    .unwrap_or_else(|| "relevance".to_string()),
+            ),
+        ];
+        if let Some(offset) = query.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(limit) = query.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        self.get("/search", &params).await
+    }
+
+    /// 列出项目的版本，按发布时间从新到旧排列，可按加载器与游戏版本过滤
+    pub async fn get_project_versions(
+        &self,
+        project: &str,
+        loaders: &[&str],
+        game_versions: &[&str],
+    ) -> Result<Vec<ModrinthVersion>, String> {
+        let mut params = Vec::new();
+        if !loaders.is_empty() {
+            params.push(("loaders", json_list(loaders)));
+        }
+        if !game_versions.is_empty() {
+            params.push(("game_versions", json_list(game_versions)));
+        }
+        self.get(&format!("/project/{}/version", project), &params)
+            .await
+    }
+
+    pub async fn get_version(&self, version_id: &str) -> Result<ModrinthVersion, String> {
+        self.get(&format!("/version/{}", version_id), &[]).await
+    }
+
+    /// 根据文件哈希查询对应的版本
+    pub async fn get_versions_by_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> Result<HashMap<String, ModrinthVersion>, String> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        self.post(
+            "/version_files",
+            &serde_json::json!({ "hashes": hashes, "algorithm": algorithm }),
+        )
+        .await
+    }
+
+    /// 根据文件哈希查询符合加载器与游戏版本的最新版本
+    pub async fn get_latest_versions_by_hashes(
+        &self,
+        request: &ModrinthUpdateRequest,
+    ) -> Result<HashMap<String, ModrinthVersion>, String> {
+        if request.hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        self.post("/version_files/update", request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::serve;
+
+    #[test]
+    fn test_build_facets() {
+        let query = ModrinthSearchQuery {
+            loader: Some("fabric".to_string()),
+            game_version: Some("1.20.1".to_string()),
+            category: Some("optimization".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_facets(&query),
+            r#"[["project_type:mod"],["categories:fabric"],["versions:1.20.1"],["categories:optimization"]]"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let base = serve(|request| {
+            if !request.path.starts_with("/search?") {
+                return (404, b"{}".to_vec());
+            }
+            assert!(request.path.contains("query=sodium"));
+            assert!(request
+                .header("user-agent")
+                .is_some_and(|ua| ua.starts_with("AsanMCL/")));
+            let body = r#"{
+                "hits": [{ "project_id": "AANobbMI", "slug": "sodium", "title": "Sodium",
+                           "project_type": "mod", "downloads": 1000 }],
+                "offset": 0, "limit": 10, "total_hits": 1
+            }"#;
+            (200, body.as_bytes().to_vec())
+        })
+        .await;
+        let client = ModrinthClient::with_client(Client::new(), base);
+        let result = client
+            .search(&ModrinthSearchQuery {
+                query: "sodium".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.total_hits, 1);
+        assert_eq!(result.hits[0].slug, "sodium");
+
+        let error = client.get_version("missing").await.unwrap_err();
+        assert!(error.contains("404"));
+    }
+}
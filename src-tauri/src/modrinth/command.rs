@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::ipc::Channel;
+
+use super::api::ModrinthClient;
+use super::install::{
+    apply_modrinth_update, check_modrinth_updates, install_modrinth_version, ModrinthTarget,
+};
+use super::model::{
+    ModrinthInstalledFile, ModrinthSearchQuery, ModrinthSearchResult, ModrinthUpdate,
+    ModrinthVersion,
+};
+use crate::game::mods::util::mod_environment;
+use crate::game::profile::util::{get_profile, resolve_game_directory};
+use crate::game::version::util::resolve_client_json;
+use crate::util::{model::DownloadEvent, scheduler::DownloadEventSink};
+
+/// 配置的模组目录，以及其加载器与游戏版本
+fn profile_target(game_path: &Path, profile_id: &str) -> Result<(PathBuf, ModrinthTarget), String> {
+    let profile = get_profile(game_path, profile_id)?;
+    let resolved = resolve_client_json(game_path, &profile.version_name)?;
+    let target = ModrinthTarget::from_environment(mod_environment(&resolved, None));
+    let mods_dir = resolve_game_directory(game_path, &profile).join("mods");
+    Ok((mods_dir, target))
+}
+
+#[tauri::command]
+pub async fn search_modrinth_command(
+    query: ModrinthSearchQuery,
+) -> Result<ModrinthSearchResult, String> {
+    ModrinthClient::new().await?.search(&query).await
+}
+
+/// 列出项目的版本，指定配置时只返回适用于该配置的版本
+#[tauri::command]
+pub async fn get_modrinth_versions_command(
+    project_id: String,
+    game_path: Option<PathBuf>,
+    profile_id: Option<String>,
+) -> Result<Vec<ModrinthVersion>, String> {
+    let target = match (game_path, profile_id) {
+        (Some(game_path), Some(profile_id)) => profile_target(&game_path, &profile_id)?.1,
+        _ => ModrinthTarget::default(),
+    };
+    let loaders: Vec<&str> = target.loader.as_deref().into_iter().collect();
+    let game_versions: Vec<&str> = target.game_version.as_deref().into_iter().collect();
+    ModrinthClient::new()
+        .await?
+        .get_project_versions(&project_id, &loaders, &game_versions)
+        .await
+}
+
+/// 安装版本及其必需的前置到配置的模组目录
+#[tauri::command]
+pub async fn install_modrinth_command(
+    on_event: Channel<DownloadEvent>,
+    game_path: PathBuf,
+    profile_id: String,
+    version_id: String,
+) -> Result<Vec<ModrinthInstalledFile>, String> {
+    let (mods_dir, target) = profile_target(&game_path, &profile_id)?;
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    let client = ModrinthClient::new().await?;
+    install_modrinth_version(&client, &version_id, &mods_dir, &target, Some(sink)).await
+}
+
+/// 检查配置中已安装模组的更新
+#[tauri::command]
+pub async fn check_modrinth_updates_command(
+    game_path: PathBuf,
+    profile_id: String,
+) -> Result<Vec<ModrinthUpdate>, String> {
+    let (mods_dir, target) = profile_target(&game_path, &profile_id)?;
+    let client = ModrinthClient::new().await?;
+    check_modrinth_updates(&client, &mods_dir, &target).await
+}
+
+/// 将模组更新到指定版本，返回新文件的路径
+#[tauri::command]
+pub async fn apply_modrinth_update_command(
+    on_event: Channel<DownloadEvent>,
+    path: PathBuf,
+    version_id: String,
+) -> Result<PathBuf, String> {
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    let client = ModrinthClient::new().await?;
+    apply_modrinth_update(&client, &path, &version_id, Some(sink)).await
+}
@@ -0,0 +1,513 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::api::ModrinthClient;
+use super::model::{
+    ModrinthDependencyType, ModrinthInstalledFile, ModrinthUpdate, ModrinthUpdateRequest,
+    ModrinthVersion,
+};
+use crate::game::mods::model::ModEnvironment;
+use crate::game::mods::scanner::{mod_file_state, DISABLED_SUFFIX};
+use crate::game::version::model::ModLoaderType;
+use crate::util::model::DownloadRequest;
+use crate::util::scheduler::{DownloadEventSink, DOWNLOAD_SCHEDULER};
+use crate::util::utils::{sha512_file, verify_sha512};
+
+/// 安装到配置时使用的加载器与游戏版本
+#[derive(Debug, Clone, Default)]
+pub struct ModrinthTarget {
+    pub loader: Option<String>,
+    pub game_version: Option<String>,
+}
+
+impl ModrinthTarget {
+    /// 取环境中第一个 Modrinth 支持的加载器
+    pub fn from_environment(environment: ModEnvironment) -> Self {
+        ModrinthTarget {
+            loader: environment
+                .loaders
+                .iter()
+                .find_map(|l| modrinth_loader(l.loader))
+                .map(str::to_string),
+            game_version: environment.minecraft,
+        }
+    }
+
+    fn loaders(&self) -> Vec<&str> {
+        self.loader.as_deref().into_iter().collect()
+    }
+
+    fn game_versions(&self) -> Vec<&str> {
+        self.game_version.as_deref().into_iter().collect()
+    }
+}
+
+/// 加载器在 Modrinth 中的名称
+pub fn modrinth_loader(loader: ModLoaderType) -> Option<&'static str> {
+    match loader {
+        ModLoaderType::Fabric => Some("fabric"),
+        ModLoaderType::Quilt => Some("quilt"),
+        ModLoaderType::Forge => Some("forge"),
+        ModLoaderType::NeoForge => Some("neoforge"),
+        ModLoaderType::LiteLoader => Some("liteloader"),
+        ModLoaderType::OptiFine => None,
+    }
+}
+
+/// 计算模组目录中所有模组文件（包括已禁用的）的 SHA-512
+pub fn hash_mod_files(mods_dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, String)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && mod_file_state(path).is_some())
+        .filter_map(|path| sha512_file(&path).ok().map(|hash| (path, hash)))
+        .collect();
+    files.sort();
+    files
+}
+
+/// 在阻塞线程中计算模组文件的 SHA-512
+async fn hash_mod_files_blocking(mods_dir: &Path) -> Result<Vec<(PathBuf, String)>, String> {
+    let mods_dir = mods_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || hash_mod_files(&mods_dir))
+        .await
+        .map_err(|e| format!("计算模组哈希失败: {}", e))
+}
+
+/// 在阻塞线程中逐个校验文件的 SHA-512
+async fn verify_files(files: Vec<(PathBuf, String)>) -> Result<Vec<bool>, String> {
+    tokio::task::spawn_blocking(move || {
+        files
+            .iter()
+            .map(|(path, sha512)| verify_sha512(path, sha512))
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("校验文件失败: {}", e))
+}
+
+/// 模组目录中已安装的 Modrinth 项目 ID 及其文件
+async fn installed_projects(
+    client: &ModrinthClient,
+    mods_dir: &Path,
+) -> Result<HashMap<String, Vec<PathBuf>>, String> {
+    let files = hash_mod_files_blocking(mods_dir).await?;
+    let hashes: Vec<String> = files.iter().map(|(_, hash)| hash.clone()).collect();
+    let mut versions = client.get_versions_by_hashes(&hashes, "sha512").await?;
+    let mut projects: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for (path, hash) in files {
+        if let Some(version) = versions.remove(&hash) {
+            projects.entry(version.project_id).or_default().push(path);
+        }
+    }
+    Ok(projects)
+}
+
+/// 为依赖的项目挑选版本：指定了版本时直接使用，否则取符合条件的最新版本
+async fn resolve_dependency_version(
+    client: &ModrinthClient,
+    version_id: Option<&str>,
+    project_id: Option<&str>,
+    target: &ModrinthTarget,
+) -> Result<Option<ModrinthVersion>, String> {
+    if let Some(version_id) = version_id {
+        return client.get_version(version_id).await.map(Some);
+    }
+    let Some(project_id) = project_id else {
+        return Ok(None);
+    };
+    Ok(client
+        .get_project_versions(project_id, &target.loaders(), &target.game_versions())
+        .await?
+        .into_iter()
+        .next())
+}
+
+/// 从指定版本出发递归收集需要安装的版本，已安装或已收集的前置会跳过
+///
+/// 返回的第一个版本为指定的版本，即使其项目已安装也会包含在内，由调用方替换旧文件；
+/// 其余为必需的前置。
+pub async fn resolve_install_plan(
+    client: &ModrinthClient,
+    version: ModrinthVersion,
+    installed: &HashSet<String>,
+    target: &ModrinthTarget,
+) -> Result<Vec<ModrinthVersion>, String> {
+    let mut seen: HashSet<String> = installed.clone();
+    seen.insert(version.project_id.clone());
+    let mut plan = Vec::new();
+    let mut queue = VecDeque::from([version]);
+    while let Some(version) = queue.pop_front() {
+        for dependency in version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == ModrinthDependencyType::Required)
+        {
+            if dependency
+                .project_id
+                .as_ref()
+                .is_some_and(|id| seen.contains(id))
+            {
+                continue;
+            }
+            let resolved = resolve_dependency_version(
+                client,
+                dependency.version_id.as_deref(),
+                dependency.project_id.as_deref(),
+                target,
+            )
+            .await?;
+            let Some(resolved) = resolved else {
+                return Err(format!(
+                    "{} 的前置 {} 没有适用于当前版本的文件",
+                    version.name,
+                    dependency
+                        .project_id
+                        .as_deref()
+                        .or(dependency.file_name.as_deref())
+                        .unwrap_or("未知项目")
+                ));
+            };
+            if seen.insert(resolved.project_id.clone()) {
+                queue.push_back(resolved);
+            }
+        }
+        plan.push(version);
+    }
+    Ok(plan)
+}
+
+/// 下载版本的主文件到模组目录并校验 SHA-512，返回文件路径
+///
+/// 目标位置已有内容不同的文件时报错，除非该文件在 `replaceable` 中。
+async fn download_versions(
+    name: &str,
+    versions: &[ModrinthVersion],
+    mods_dir: &Path,
+    replaceable: &[PathBuf],
+    sink: Option<DownloadEventSink>,
+) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for version in versions {
+        let file = version
+            .primary_file()
+            .ok_or_else(|| format!("{} 没有可下载的文件", version.name))?;
+        if file.filename.contains(['/', '\\']) || file.filename.starts_with('.') {
+            return Err(format!("无效的文件名: {}", file.filename));
+        }
+        let sha512 = file
+            .hashes
+            .get("sha512")
+            .ok_or_else(|| format!("{} 缺少 SHA-512", file.filename))?;
+        files.push((mods_dir.join(&file.filename), file, sha512));
+    }
+    let hashes = || {
+        files
+            .iter()
+            .map(|(path, _, sha512)| (path.clone(), sha512.to_string()))
+            .collect()
+    };
+
+    let downloaded = verify_files(hashes()).await?;
+    let mut requests = Vec::new();
+    for ((path, file, _), downloaded) in files.iter().zip(downloaded) {
+        if downloaded {
+            continue;
+        }
+        if path.exists() && !replaceable.contains(path) {
+            return Err(format!("目标文件已存在: {}", path.display()));
+        }
+        requests.push(DownloadRequest {
+            url: file.url.clone(),
+            path: path.clone(),
+            priority: Default::default(),
+            size: Some(file.size),
+        });
+    }
+    fs::create_dir_all(mods_dir).map_err(|e| format!("创建模组目录失败: {}", e))?;
+    DOWNLOAD_SCHEDULER.run(name, requests, sink).await?;
+
+    let verified = verify_files(hashes()).await?;
+    let mut paths = Vec::new();
+    for ((path, _, _), verified) in files.into_iter().zip(verified) {
+        if !verified {
+            let _ = fs::remove_file(&path);
+            return Err(format!("文件校验失败: {}", path.display()));
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// 安装指定版本及其必需的前置到模组目录
+///
+/// 指定版本的项目已安装时，新文件下载完成后删除该项目的旧文件。
+pub async fn install_modrinth_version(
+    client: &ModrinthClient,
+    version_id: &str,
+    mods_dir: &Path,
+    target: &ModrinthTarget,
+    sink: Option<DownloadEventSink>,
+) -> Result<Vec<ModrinthInstalledFile>, String> {
+    let version = client.get_version(version_id).await?;
+    let mut installed = installed_projects(client, mods_dir).await?;
+    let previous = installed.remove(&version.project_id).unwrap_or_default();
+    let installed: HashSet<String> = installed.into_keys().collect();
+    let plan = resolve_install_plan(client, version, &installed, target).await?;
+    let name = format!("Modrinth {}", plan[0].name);
+    let paths = download_versions(&name, &plan, mods_dir, &previous, sink).await?;
+    for old in previous.iter().filter(|old| **old != paths[0]) {
+        fs::remove_file(old).map_err(|e| format!("删除旧版本失败 {}: {}", old.display(), e))?;
+    }
+    Ok(plan
+        .into_iter()
+        .zip(paths)
+        .enumerate()
+        .map(|(i, (version, path))| ModrinthInstalledFile {
+            project_id: version.project_id,
+            version_id: version.id,
+            version_number: version.version_number,
+            path,
+            dependency: i > 0,
+        })
+        .collect())
+}
+
+/// 通过已安装文件的哈希检查更新
+pub async fn check_modrinth_updates(
+    client: &ModrinthClient,
+    mods_dir: &Path,
+    target: &ModrinthTarget,
+) -> Result<Vec<ModrinthUpdate>, String> {
+    let files = hash_mod_files_blocking(mods_dir).await?;
+    let request = ModrinthUpdateRequest {
+        hashes: files.iter().map(|(_, hash)| hash.clone()).collect(),
+        algorithm: "sha512".to_string(),
+        loaders: target.loader.iter().cloned().collect(),
+        game_versions: target.game_version.iter().cloned().collect(),
+    };
+    let mut latest = client.get_latest_versions_by_hashes(&request).await?;
+    Ok(files
+        .into_iter()
+        .filter_map(|(path, hash)| {
+            let version = latest.remove(&hash)?;
+            // 最新版本中包含当前文件，说明已是最新
+            let up_to_date = version.files.iter().any(|f| {
+                f.hashes
+                    .get("sha512")
+                    .is_some_and(|h| h.eq_ignore_ascii_case(&hash))
+            });
+            if up_to_date {
+                return None;
+            }
+            Some(ModrinthUpdate {
+                file_name: path.file_name()?.to_string_lossy().into_owned(),
+                project_id: version.project_id.clone(),
+                path,
+                latest: version,
+            })
+        })
+        .collect())
+}
+
+/// 用新版本替换已安装的模组文件，保留其禁用状态，返回新文件的路径
+///
+/// 新文件名与其他已有文件冲突时报错，不会覆盖无关的文件。
+pub async fn apply_modrinth_update(
+    client: &ModrinthClient,
+    path: &Path,
+    version_id: &str,
+    sink: Option<DownloadEventSink>,
+) -> Result<PathBuf, String> {
+    let enabled =
+        mod_file_state(path).ok_or_else(|| format!("不是模组文件: {}", path.display()))?;
+    let mods_dir = path
+        .parent()
+        .ok_or_else(|| format!("无效的模组路径: {}", path.display()))?;
+    let version = client.get_version(version_id).await?;
+    if !enabled {
+        if let Some(file) = version.primary_file() {
+            let disabled = mods_dir.join(format!("{}{}", file.filename, DISABLED_SUFFIX));
+            if disabled != path && disabled.exists() {
+                return Err(format!("目标文件已存在: {}", disabled.display()));
+            }
+        }
+    }
+    let name = format!("Modrinth {}", version.name);
+    let replaceable = [path.to_path_buf()];
+    let new_path = download_versions(
+        &name,
+        std::slice::from_ref(&version),
+        mods_dir,
+        &replaceable,
+        sink,
+    )
+    .await?
+    .remove(0);
+    let new_path = if enabled {
+        new_path
+    } else {
+        let disabled = PathBuf::from(format!("{}{}", new_path.display(), DISABLED_SUFFIX));
+        fs::rename(&new_path, &disabled).map_err(|e| format!("重命名模组失败: {}", e))?;
+        disabled
+    };
+    if new_path != path {
+        fs::remove_file(path).map_err(|e| format!("删除旧版本失败 {}: {}", path.display(), e))?;
+    }
+    Ok(new_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::serve;
+    use std::sync::{Arc, Mutex};
+
+    fn version_json(id: &str, project: &str, sha512: &str, dependencies: &str) -> String {
+        format!(
+            r#"{{
+                "id": "{id}", "project_id": "{project}", "name": "{project} {id}",
+                "version_number": "1.0.0", "game_versions": ["1.20.1"], "loaders": ["fabric"],
+                "version_type": "release", "date_published": "2024-01-01T00:00:00Z",
+                "files": [{{ "hashes": {{ "sha512": "{sha512}" }}, "url": "https://cdn.modrinth.com/{id}.jar",
+                            "filename": "{id}.jar", "primary": true, "size": 10 }}],
+                "dependencies": {dependencies}
+            }}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_resolve_install_plan() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let base = serve(move |request| {
+            recorded.lock().unwrap().push(request.path.clone());
+            let body = match request.path.split('?').next().unwrap() {
+                "/version/iris" => version_json(
+                    "iris",
+                    "YL57xq9U",
+                    "aa",
+                    r#"[
+                        { "project_id": "AANobbMI", "dependency_type": "required" },
+                        { "project_id": "P7dR8mSH", "version_id": "fapi", "dependency_type": "required" },
+                        { "project_id": "mOgUt4GM", "dependency_type": "optional" }
+                    ]"#,
+                ),
+                "/project/AANobbMI/version" => format!(
+                    "[{}, {}]",
+                    version_json(
+                        "sodium-new",
+                        "AANobbMI",
+                        "bb",
+                        r#"[{ "project_id": "P7dR8mSH", "dependency_type": "required" }]"#
+                    ),
+                    version_json("sodium-old", "AANobbMI", "cc", "[]")
+                ),
+                "/version/fapi" => version_json("fapi", "P7dR8mSH", "dd", "[]"),
+                _ => return (404, b"{}".to_vec()),
+            };
+            (200, body.into_bytes())
+        })
+        .await;
+
+        let client = ModrinthClient::with_client(reqwest::Client::new(), base);
+        let target = ModrinthTarget {
+            loader: Some("fabric".to_string()),
+            game_version: Some("1.20.1".to_string()),
+        };
+        let version = client.get_version("iris").await.unwrap();
+        let plan = resolve_install_plan(&client, version.clone(), &HashSet::new(), &target)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = plan.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["iris", "sodium-new", "fapi"]);
+        assert!(requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|p| p.contains(r#"loaders=["fabric"]"#)
+                && p.contains(r#"game_versions=["1.20.1"]"#)));
+
+        // 已安装的前置不会重复安装
+        let installed = HashSet::from(["AANobbMI".to_string()]);
+        let plan = resolve_install_plan(&client, version, &installed, &target)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = plan.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, vec!["iris", "fapi"]);
+    }
+
+    #[tokio::test]
+    async fn test_check_modrinth_updates() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("old.jar"), b"old").unwrap();
+        fs::write(dir.join("latest.jar.disabled"), b"latest").unwrap();
+        let old_hash = sha512_file(&dir.join("old.jar")).unwrap();
+        let latest_hash = sha512_file(&dir.join("latest.jar.disabled")).unwrap();
+
+        let (old, latest) = (old_hash.clone(), latest_hash.clone());
+        let base = serve(move |request| {
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.path, "/version_files/update");
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            assert_eq!(body["algorithm"], "sha512");
+            assert_eq!(body["loaders"][0], "fabric");
+            let response = format!(
+                r#"{{ "{}": {}, "{}": {} }}"#,
+                old,
+                version_json("new", "proj-a", "ffff", "[]"),
+                latest,
+                version_json("same", "proj-b", &latest, "[]")
+            );
+            (200, response.into_bytes())
+        })
+        .await;
+
+        let client = ModrinthClient::with_client(reqwest::Client::new(), base);
+        let target = ModrinthTarget {
+            loader: Some("fabric".to_string()),
+            game_version: Some("1.20.1".to_string()),
+        };
+        let updates = check_modrinth_updates(&client, dir, &target).await.unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].file_name, "old.jar");
+        assert_eq!(updates[0].latest.id, "new");
+    }
+
+    #[tokio::test]
+    async fn test_update_does_not_overwrite_unrelated_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("old.jar"), b"old").unwrap();
+        fs::write(dir.join("new.jar"), b"unrelated").unwrap();
+        fs::write(dir.join("old.jar.disabled"), b"old").unwrap();
+        fs::write(dir.join("new.jar.disabled"), b"unrelated").unwrap();
+
+        let base = serve(|request| match request.path.as_str() {
+            "/version/new" => (
+                200,
+                version_json("new", "proj-a", "ffff", "[]").into_bytes(),
+            ),
+            _ => (404, b"{}".to_vec()),
+        })
+        .await;
+        let client = ModrinthClient::with_client(reqwest::Client::new(), base);
+
+        for (old, conflict) in [
+            ("old.jar", "new.jar"),
+            ("old.jar.disabled", "new.jar.disabled"),
+        ] {
+            let err = apply_modrinth_update(&client, &dir.join(old), "new", None)
+                .await
+                .unwrap_err();
+            assert!(err.contains("目标文件已存在"), "{}", err);
+            assert_eq!(fs::read(dir.join(conflict)).unwrap(), b"unrelated");
+            assert!(dir.join(old).exists());
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod api;
+pub mod command;
+pub mod install;
+pub mod model;
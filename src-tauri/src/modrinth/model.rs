@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// 搜索条件，对应 Modrinth 的 facets
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthSearchQuery {
+    #[serde(default)]
+    pub query: String,
+    /// 加载器，如 fabric、forge
+    pub loader: Option<String>,
+    pub game_version: Option<String>,
+    pub category: Option<String>,
+    /// 项目类型，默认为 mod
+    pub project_type: Option<String>,
+    /// 排序方式：relevance、downloads、follows、newest、updated
+    pub index: Option<String>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+/// 搜索结果中的项目
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModrinthSearchHit {
+    pub project_id: String,
+    pub slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    pub project_type: String,
+    pub date_modified: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModrinthSearchResult {
+    pub hits: Vec<ModrinthSearchHit>,
+    pub offset: u32,
+    pub limit: u32,
+    pub total_hits: u32,
+}
+
+/// 依赖类型：required、optional、incompatible、embedded
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModrinthDependencyType {
+    Required,
+    Optional,
+    Incompatible,
+    Embedded,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModrinthDependency {
+    pub version_id: Option<String>,
+    pub project_id: Option<String>,
+    pub file_name: Option<String>,
+    pub dependency_type: ModrinthDependencyType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModrinthFile {
+    /// 算法名到哈希值的映射，包含 sha1 与 sha512
+    pub hashes: HashMap<String, String>,
+    pub url: String,
+    pub filename: String,
+    #[serde(default)]
+    pub primary: bool,
+    pub size: u64,
+}
+
+/// 项目的一个版本
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModrinthVersion {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub version_number: String,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    /// release、beta 或 alpha
+    pub version_type: String,
+    pub date_published: String,
+    #[serde(default)]
+    pub files: Vec<ModrinthFile>,
+    #[serde(default)]
+    pub dependencies: Vec<ModrinthDependency>,
+}
+
+impl ModrinthVersion {
+    /// 主文件，未标记时取第一个文件
+    pub fn primary_file(&self) -> Option<&ModrinthFile> {
+        self.files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| self.files.first())
+    }
+}
+
+/// `/version_files/update` 的请求体
+#[derive(Serialize, Debug, Clone)]
+pub struct ModrinthUpdateRequest {
+    pub hashes: Vec<String>,
+    pub algorithm: String,
+    pub loaders: Vec<String>,
+    pub game_versions: Vec<String>,
+}
+
+/// 安装到模组目录的文件
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthInstalledFile {
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub path: PathBuf,
+    /// 是否作为前置被自动安装
+    pub dependency: bool,
+}
+
+/// 已安装模组的可用更新
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthUpdate {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub project_id: String,
+    pub latest: ModrinthVersion,
+}
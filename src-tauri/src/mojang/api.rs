@@ -1,4 +1,5 @@
 use crate::mojang::model::QueriedMinecraftProfile;
+use crate::util::cache::get_cached_json;
 use crate::util::reqwest_client::REQWEST_CLIENT;
 use crate::{config::model::DownloadSource, game::version::model::VersionManifest};
 
@@ -8,10 +9,13 @@ use super::model::{
 };
 use base64::{engine::general_purpose::STANDARD, Engine};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use std::time::Duration;
 pub struct MinecraftClient;
 
-const MOJANG_LAUNCHERMETA_ROOT: &str = "https://launchermeta.mojang.com";
-const BMCLAPI_LAUNCHERMETA_ROOT: &str = "https://bmclapi2.bangbang93.com";
+const VERSION_MANIFEST_URL: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+/// 版本清单的缓存有效期
+const VERSION_MANIFEST_TTL: Duration = Duration::from_secs(10 * 60);
 
 impl MinecraftClient {
     pub fn new() -> Self {
@@ -230,35 +234,9 @@ pub async fn get_player_skin_preview_url(uuid: &str) -> SkinPreviewInfo {
 pub async fn get_version_manifest(
     download_source: DownloadSource,
 ) -> Result<VersionManifest, String> {
-    let url = match download_source {
-        DownloadSource::Official => format!(
-            "{}/mc/game/version_manifest_v2.json",
-            MOJANG_LAUNCHERMETA_ROOT
-        ),
-        DownloadSource::BmclApi => format!(
-            "{}/mc/game/version_manifest_v2.json",
-            BMCLAPI_LAUNCHERMETA_ROOT
-        ),
-    };
-    println!("Fetching version manifest from: {}", url);
-    let client = {
-        let guard = REQWEST_CLIENT.lock().await;
-        match &*guard {
-            Some(c) => c.clone(),
-            None => return Err("HTTP客户端未初始化".to_string()),
-        }
-    };
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch version manifest: {}", e))?;
-    if !response.status().is_success() {
-        return Err(format!("Failed to fetch version manifest: {}", response.status()).into());
-    }
-    let manifest: VersionManifest = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse version manifest: {}", e))?;
+    let manifest: VersionManifest =
+        get_cached_json(VERSION_MANIFEST_URL, &download_source, VERSION_MANIFEST_TTL)
+            .await
+            .map_err(|e| format!("Failed to fetch version manifest: {}", e))?;
     Ok(manifest)
 }
@@ -0,0 +1,6 @@
+//! Minecraft 使用的 NBT（Named Binary Tag）格式，Java 版、大端序
+
+pub mod model;
+pub mod mutf8;
+pub mod reader;
+pub mod writer;
@@ -0,0 +1,185 @@
+/// NBT 标签，不包含仅用于结束复合标签的 `TAG_End`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    /// 列表中的元素类型必须一致，空列表写入时元素类型为 `TAG_End`
+    List(Vec<Tag>),
+    Compound(Compound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+pub const TAG_END: u8 = 0;
+pub const TAG_BYTE: u8 = 1;
+pub const TAG_SHORT: u8 = 2;
+pub const TAG_INT: u8 = 3;
+pub const TAG_LONG: u8 = 4;
+pub const TAG_FLOAT: u8 = 5;
+pub const TAG_DOUBLE: u8 = 6;
+pub const TAG_BYTE_ARRAY: u8 = 7;
+pub const TAG_STRING: u8 = 8;
+pub const TAG_LIST: u8 = 9;
+pub const TAG_COMPOUND: u8 = 10;
+pub const TAG_INT_ARRAY: u8 = 11;
+pub const TAG_LONG_ARRAY: u8 = 12;
+
+impl Tag {
+    /// 标签类型编号
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => TAG_BYTE,
+            Tag::Short(_) => TAG_SHORT,
+            Tag::Int(_) => TAG_INT,
+            Tag::Long(_) => TAG_LONG,
+            Tag::Float(_) => TAG_FLOAT,
+            Tag::Double(_) => TAG_DOUBLE,
+            Tag::ByteArray(_) => TAG_BYTE_ARRAY,
+            Tag::String(_) => TAG_STRING,
+            Tag::List(_) => TAG_LIST,
+            Tag::Compound(_) => TAG_COMPOUND,
+            Tag::IntArray(_) => TAG_INT_ARRAY,
+            Tag::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    /// 整数类标签的值
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Tag::Byte(v) => Some(*v as i64),
+            Tag::Short(v) => Some(*v as i64),
+            Tag::Int(v) => Some(*v as i64),
+            Tag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&Compound> {
+        match self {
+            Tag::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// 复合标签，保留条目的原始顺序
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Compound {
+    entries: Vec<(String, Tag)>,
+}
+
+impl Compound {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.entries.iter().find(|(n, _)| n == name).map(|(_, t)| t)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Tag> {
+        self.entries
+            .iter_mut()
+            .find(|(n, _)| n == name)
+            .map(|(_, t)| t)
+    }
+
+    /// 插入或替换条目，替换时保留原来的位置
+    pub fn insert(&mut self, name: impl Into<String>, tag: Tag) {
+        let name = name.into();
+        match self.get_mut(&name) {
+            Some(existing) => *existing = tag,
+            None => self.entries.push((name, tag)),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Tag> {
+        let index = self.entries.iter().position(|(n, _)| n == name)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Tag)> {
+        self.entries.iter().map(|(n, t)| (n.as_str(), t))
+    }
+
+    pub fn get_i64(&self, name: &str) -> Option<i64> {
+        self.get(name)?.as_i64()
+    }
+
+    /// 字节标签作为布尔值，非 0 即为 true
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get_i64(name).map(|v| v != 0)
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name)?.as_str()
+    }
+
+    pub fn get_list(&self, name: &str) -> Option<&[Tag]> {
+        self.get(name)?.as_list()
+    }
+
+    pub fn get_compound(&self, name: &str) -> Option<&Compound> {
+        self.get(name)?.as_compound()
+    }
+}
+
+impl FromIterator<(String, Tag)> for Compound {
+    fn from_iter<I: IntoIterator<Item = (String, Tag)>>(iter: I) -> Self {
+        let mut compound = Compound::new();
+        for (name, tag) in iter {
+            compound.insert(name, tag);
+        }
+        compound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compound_access() {
+        let mut compound: Compound = [
+            ("hardcore".to_string(), Tag::Byte(1)),
+            ("LastPlayed".to_string(), Tag::Long(1_700_000_000_000)),
+            ("LevelName".to_string(), Tag::String("World".to_string())),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(compound.get_bool("hardcore"), Some(true));
+        assert_eq!(compound.get_i64("LastPlayed"), Some(1_700_000_000_000));
+        assert_eq!(compound.get_str("LevelName"), Some("World"));
+        assert_eq!(compound.get_str("hardcore"), None);
+        assert_eq!(compound.get_i64("missing"), None);
+
+        compound.insert("hardcore", Tag::Byte(0));
+        assert_eq!(compound.iter().count(), 3);
+        assert_eq!(compound.iter().next().unwrap(), ("hardcore", &Tag::Byte(0)));
+        assert_eq!(
+            compound.remove("LevelName"),
+            Some(Tag::String("World".to_string()))
+        );
+        assert_eq!(compound.iter().count(), 2);
+    }
+}
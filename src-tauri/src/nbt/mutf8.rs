@@ -0,0 +1,75 @@
+//! Java 的 Modified UTF-8：`\0` 编码为两个字节，增补字符编码为两个代理项
+
+/// 编码为 Modified UTF-8
+pub fn encode(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    for unit in text.encode_utf16() {
+        match unit {
+            0x0001..=0x007f => out.push(unit as u8),
+            0x0000 | 0x0080..=0x07ff => {
+                out.push(0xc0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3f) as u8);
+            }
+            _ => {
+                out.push(0xe0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                out.push(0x80 | (unit & 0x3f) as u8);
+            }
+        }
+    }
+    out
+}
+
+/// 解码 Modified UTF-8，不符合时按标准 UTF-8 解码，孤立的代理项替换为 U+FFFD
+pub fn decode(bytes: &[u8]) -> Result<String, String> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let continuation = |offset: usize| -> Option<u16> {
+            bytes
+                .get(i + offset)
+                .filter(|c| *c & 0xc0 == 0x80)
+                .map(|c| (*c & 0x3f) as u16)
+        };
+        let (unit, width) = match b {
+            0x00..=0x7f => (b as u16, 1),
+            0xc0..=0xdf => match continuation(1) {
+                Some(c1) => (((b & 0x1f) as u16) << 6 | c1, 2),
+                None => break,
+            },
+            0xe0..=0xef => match (continuation(1), continuation(2)) {
+                (Some(c1), Some(c2)) => (((b & 0x0f) as u16) << 12 | c1 << 6 | c2, 3),
+                _ => break,
+            },
+            _ => break,
+        };
+        units.push(unit);
+        i += width;
+    }
+    if i == bytes.len() {
+        return Ok(String::from_utf16_lossy(&units));
+    }
+    String::from_utf8(bytes.to_vec()).map_err(|_| "NBT 字符串编码无效".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutf8() {
+        assert_eq!(encode("abc"), b"abc");
+        assert_eq!(encode("a\0b"), [b'a', 0xc0, 0x80, b'b']);
+        assert_eq!(encode("世界"), "世界".as_bytes());
+        // U+1F600 编码为两个三字节的代理项
+        assert_eq!(encode("😀"), [0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]);
+
+        for text in ["", "abc", "a\0b", "我的世界", "😀 smile", "§6金色"] {
+            assert_eq!(decode(&encode(text)).unwrap(), text);
+        }
+        // 其他工具写入的标准 UTF-8
+        assert_eq!(decode("😀".as_bytes()).unwrap(), "😀");
+        assert!(decode(&[0xe0, 0x80]).is_err());
+    }
+}
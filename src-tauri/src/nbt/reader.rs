@@ -0,0 +1,259 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use super::model::*;
+use super::mutf8;
+
+/// 嵌套的最大深度，与游戏一致
+const MAX_DEPTH: usize = 512;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| "NBT 数据不完整".to_string())?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+
+    /// 数组与列表的长度，同时检查剩余数据是否足够，避免按伪造的长度分配内存
+    fn length(&mut self, element_size: usize) -> Result<usize, String> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Err(format!("NBT 长度无效: {}", len));
+        }
+        let len = len as usize;
+        if len.saturating_mul(element_size) > self.data.len() - self.pos {
+            return Err("NBT 数据不完整".to_string());
+        }
+        Ok(len)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        mutf8::decode(self.take(len)?)
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, String> {
+        if depth > MAX_DEPTH {
+            return Err("NBT 嵌套过深".to_string());
+        }
+        Ok(match id {
+            TAG_BYTE => Tag::Byte(self.u8()? as i8),
+            TAG_SHORT => Tag::Short(self.i16()?),
+            TAG_INT => Tag::Int(self.i32()?),
+            TAG_LONG => Tag::Long(self.i64()?),
+            TAG_FLOAT => Tag::Float(f32::from_be_bytes(self.array()?)),
+            TAG_DOUBLE => Tag::Double(f64::from_be_bytes(self.array()?)),
+            TAG_BYTE_ARRAY => {
+                let len = self.length(1)?;
+                Tag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            TAG_STRING => Tag::String(self.string()?),
+            TAG_LIST => {
+                let element = self.u8()?;
+                // 元素至少占一个字节，空复合标签也有结束标记
+                let len = self.length(if element == TAG_END { 0 } else { 1 })?;
+                if element == TAG_END && len > 0 {
+                    return Err("NBT 列表的元素类型无效".to_string());
+                }
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.payload(element, depth + 1)?);
+                }
+                Tag::List(items)
+            }
+            TAG_COMPOUND => Tag::Compound(self.compound(depth + 1)?),
+            TAG_INT_ARRAY => {
+                let len = self.length(4)?;
+                Tag::IntArray((0..len).map(|_| self.i32()).collect::<Result<_, _>>()?)
+            }
+            TAG_LONG_ARRAY => {
+                let len = self.length(8)?;
+                Tag::LongArray((0..len).map(|_| self.i64()).collect::<Result<_, _>>()?)
+            }
+            _ => return Err(format!("未知的 NBT 标签类型: {}", id)),
+        })
+    }
+
+    fn compound(&mut self, depth: usize) -> Result<Compound, String> {
+        let mut compound = Compound::new();
+        loop {
+            let id = self.u8()?;
+            if id == TAG_END {
+                return Ok(compound);
+            }
+            let name = self.string()?;
+            let tag = self.payload(id, depth)?;
+            compound.insert(name, tag);
+        }
+    }
+}
+
+/// 读取未压缩的 NBT，根标签必须为复合标签，返回根标签的名称与内容
+pub fn read_nbt(data: &[u8]) -> Result<(String, Compound), String> {
+    let mut reader = Reader { data, pos: 0 };
+    let id = reader.u8()?;
+    if id != TAG_COMPOUND {
+        return Err(format!("NBT 根标签不是复合标签: {}", id));
+    }
+    let name = reader.string()?;
+    let root = reader.compound(1)?;
+    Ok((name, root))
+}
+
+/// 读取 NBT，以 gzip 魔数开头时先解压
+pub fn read_nbt_auto(data: &[u8]) -> Result<(String, Compound), String> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(data)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| format!("解压 NBT 失败: {}", e))?;
+        return read_nbt(&decompressed);
+    }
+    read_nbt(data)
+}
+
+/// 读取 NBT 文件，自动识别 gzip 压缩
+pub fn read_nbt_file(path: &Path) -> Result<(String, Compound), String> {
+    let data = fs::read(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    read_nbt_auto(&data).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NBT 规范中的 hello_world.nbt
+    const HELLO_WORLD: &[u8] = &[
+        0x0a, 0x00, 0x0b, b'h', b'e', b'l', b'l', b'o', b' ', b'w', b'o', b'r', b'l', b'd', 0x08,
+        0x00, 0x04, b'n', b'a', b'm', b'e', 0x00, 0x09, b'B', b'a', b'n', b'a', b'n', b'r', b'a',
+        b'm', b'a', 0x00,
+    ];
+
+    #[test]
+    fn test_read_hello_world() {
+        let (name, root) = read_nbt(HELLO_WORLD).unwrap();
+        assert_eq!(name, "hello world");
+        assert_eq!(root.iter().count(), 1);
+        assert_eq!(root.get_str("name"), Some("Bananrama"));
+    }
+
+    #[test]
+    fn test_read_all_tags() {
+        let mut data = vec![0x0a, 0x00, 0x00];
+        let mut entry = |id: u8, name: &str, payload: &[u8]| {
+            data.push(id);
+            data.extend((name.len() as u16).to_be_bytes());
+            data.extend(name.as_bytes());
+            data.extend(payload);
+        };
+        entry(TAG_BYTE, "b", &[0xff]);
+        entry(TAG_SHORT, "s", &(-2i16).to_be_bytes());
+        entry(TAG_INT, "i", &3i32.to_be_bytes());
+        entry(TAG_LONG, "l", &(-4i64).to_be_bytes());
+        entry(TAG_FLOAT, "f", &0.5f32.to_be_bytes());
+        entry(TAG_DOUBLE, "d", &1.25f64.to_be_bytes());
+        entry(TAG_BYTE_ARRAY, "ba", &[0, 0, 0, 2, 1, 0x80]);
+        entry(TAG_LIST, "empty", &[TAG_END, 0, 0, 0, 0]);
+        entry(TAG_LIST, "list", &[TAG_SHORT, 0, 0, 0, 2, 0, 1, 0, 2]);
+        entry(TAG_INT_ARRAY, "ia", &[0, 0, 0, 1, 0, 0, 1, 0]);
+        entry(TAG_LONG_ARRAY, "la", &[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 7]);
+        entry(TAG_COMPOUND, "c", &[TAG_BYTE, 0, 1, b'x', 5, TAG_END]);
+        data.push(TAG_END);
+
+        let (_, root) = read_nbt(&data).unwrap();
+        assert_eq!(root.get("b"), Some(&Tag::Byte(-1)));
+        assert_eq!(root.get_i64("s"), Some(-2));
+        assert_eq!(root.get_i64("i"), Some(3));
+        assert_eq!(root.get_i64("l"), Some(-4));
+        assert_eq!(root.get("f"), Some(&Tag::Float(0.5)));
+        assert_eq!(root.get("d"), Some(&Tag::Double(1.25)));
+        assert_eq!(root.get("ba"), Some(&Tag::ByteArray(vec![1, -128])));
+        assert_eq!(root.get_list("empty"), Some(&[][..]));
+        assert_eq!(
+            root.get_list("list"),
+            Some(&[Tag::Short(1), Tag::Short(2)][..])
+        );
+        assert_eq!(root.get("ia"), Some(&Tag::IntArray(vec![256])));
+        assert_eq!(root.get("la"), Some(&Tag::LongArray(vec![7])));
+        assert_eq!(root.get_compound("c").unwrap().get_i64("x"), Some(5));
+    }
+
+    #[test]
+    fn test_read_gzip() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(HELLO_WORLD).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(read_nbt(&compressed).is_err());
+        let (name, root) = read_nbt_auto(&compressed).unwrap();
+        assert_eq!(name, "hello world");
+        assert_eq!(root.get_str("name"), Some("Bananrama"));
+        assert_eq!(read_nbt_auto(HELLO_WORLD).unwrap().0, "hello world");
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        assert!(read_nbt(&[]).is_err());
+        // 根标签不是复合标签
+        assert!(read_nbt(&[0x08, 0x00, 0x00, 0x00, 0x00]).is_err());
+        // 缺少结束标记
+        assert!(read_nbt(&HELLO_WORLD[..HELLO_WORLD.len() - 1]).is_err());
+        // 未知的标签类型
+        assert!(read_nbt(&[0x0a, 0x00, 0x00, 0x0d, 0x00, 0x00]).is_err());
+        // 负数长度与超出数据的长度
+        assert!(read_nbt(&[0x0a, 0x00, 0x00, 0x07, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(read_nbt(&[0x0a, 0x00, 0x00, 0x0b, 0x00, 0x00, 0x7f, 0xff, 0xff, 0xff]).is_err());
+        // 非空列表的元素类型为 TAG_End
+        assert!(read_nbt(&[0x0a, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0, 0, 0, 1, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_read_depth_limit() {
+        // 每层为只含一个列表的列表，最内层为空列表
+        let nested = |depth: usize| {
+            let mut data = vec![0x0a, 0x00, 0x00, TAG_LIST, 0x00, 0x00];
+            for _ in 0..depth {
+                data.extend([TAG_LIST, 0, 0, 0, 1]);
+            }
+            data.extend([TAG_END, 0, 0, 0, 0, TAG_END]);
+            data
+        };
+        assert!(read_nbt(&nested(100)).is_ok());
+        assert_eq!(read_nbt(&nested(600)).unwrap_err(), "NBT 嵌套过深");
+    }
+}
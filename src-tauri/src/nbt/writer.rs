@@ -0,0 +1,176 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use flate2::{write::GzEncoder, Compression};
+
+use super::model::*;
+use super::mutf8;
+
+fn write_string(out: &mut Vec<u8>, text: &str) -> Result<(), String> {
+    let bytes = mutf8::encode(text);
+    let len = u16::try_from(bytes.len()).map_err(|_| "NBT 字符串过长".to_string())?;
+    out.extend(len.to_be_bytes());
+    out.extend(bytes);
+    Ok(())
+}
+
+fn write_length(out: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    let len = i32::try_from(len).map_err(|_| "NBT 数组过长".to_string())?;
+    out.extend(len.to_be_bytes());
+    Ok(())
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) -> Result<(), String> {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend(v.to_be_bytes()),
+        Tag::Int(v) => out.extend(v.to_be_bytes()),
+        Tag::Long(v) => out.extend(v.to_be_bytes()),
+        Tag::Float(v) => out.extend(v.to_be_bytes()),
+        Tag::Double(v) => out.extend(v.to_be_bytes()),
+        Tag::ByteArray(v) => {
+            write_length(out, v.len())?;
+            out.extend(v.iter().map(|b| *b as u8));
+        }
+        Tag::String(v) => write_string(out, v)?,
+        Tag::List(items) => {
+            let element = items.first().map_or(TAG_END, Tag::id);
+            if items.iter().any(|item| item.id() != element) {
+                return Err("NBT 列表中的元素类型不一致".to_string());
+            }
+            out.push(element);
+            write_length(out, items.len())?;
+            for item in items {
+                write_payload(out, item)?;
+            }
+        }
+        Tag::Compound(compound) => write_compound(out, compound)?,
+        Tag::IntArray(v) => {
+            write_length(out, v.len())?;
+            v.iter().for_each(|n| out.extend(n.to_be_bytes()));
+        }
+        Tag::LongArray(v) => {
+            write_length(out, v.len())?;
+            v.iter().for_each(|n| out.extend(n.to_be_bytes()));
+        }
+    }
+    Ok(())
+}
+
+fn write_compound(out: &mut Vec<u8>, compound: &Compound) -> Result<(), String> {
+    for (name, tag) in compound.iter() {
+        out.push(tag.id());
+        write_string(out, name)?;
+        write_payload(out, tag)?;
+    }
+    out.push(TAG_END);
+    Ok(())
+}
+
+/// 写入未压缩的 NBT
+pub fn write_nbt(name: &str, root: &Compound) -> Result<Vec<u8>, String> {
+    let mut out = vec![TAG_COMPOUND];
+    write_string(&mut out, name)?;
+    write_compound(&mut out, root)?;
+    Ok(out)
+}
+
+/// 写入 gzip 压缩的 NBT，如 level.dat
+pub fn write_nbt_gzip(name: &str, root: &Compound) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&write_nbt(name, root)?)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| format!("压缩 NBT 失败: {}", e))
+}
+
+/// 写入 NBT 文件，先写入临时文件再替换，避免写入中断时损坏原文件
+pub fn write_nbt_file(path: &Path, name: &str, root: &Compound, gzip: bool) -> Result<(), String> {
+    let data = if gzip {
+        write_nbt_gzip(name, root)?
+    } else {
+        write_nbt(name, root)?
+    };
+    let temp = path.with_extension("dat_tmp");
+    fs::write(&temp, data).map_err(|e| format!("写入 {} 失败: {}", temp.display(), e))?;
+    fs::rename(&temp, path).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::reader::{read_nbt, read_nbt_auto, read_nbt_file};
+
+    fn sample() -> Compound {
+        let mut nested = Compound::new();
+        nested.insert("Name", Tag::String("1.20.1".to_string()));
+        nested.insert("Id", Tag::Int(3465));
+        let mut root = Compound::new();
+        root.insert("byte", Tag::Byte(-1));
+        root.insert("short", Tag::Short(i16::MIN));
+        root.insert("int", Tag::Int(i32::MAX));
+        root.insert("long", Tag::Long(i64::MIN));
+        root.insert("float", Tag::Float(-0.75));
+        root.insert("double", Tag::Double(std::f64::consts::PI));
+        root.insert("bytes", Tag::ByteArray(vec![-128, 0, 127]));
+        root.insert("text", Tag::String("我的世界\0😀".to_string()));
+        root.insert("empty", Tag::List(Vec::new()));
+        root.insert(
+            "list",
+            Tag::List(vec![
+                Tag::Compound(nested.clone()),
+                Tag::Compound(Compound::new()),
+            ]),
+        );
+        root.insert("nested", Tag::Compound(nested));
+        root.insert("ints", Tag::IntArray(vec![1, -2, 3]));
+        root.insert("longs", Tag::LongArray(vec![i64::MAX, 0]));
+        root
+    }
+
+    #[test]
+    fn test_write_hello_world() {
+        let mut root = Compound::new();
+        root.insert("name", Tag::String("Bananrama".to_string()));
+        let data = write_nbt("hello world", &root).unwrap();
+        assert_eq!(data.len(), 33);
+        assert_eq!(&data[..3], [0x0a, 0x00, 0x0b]);
+        assert_eq!(read_nbt(&data).unwrap(), ("hello world".to_string(), root));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let root = sample();
+        let data = write_nbt("", &root).unwrap();
+        assert_eq!(read_nbt(&data).unwrap(), (String::new(), root.clone()));
+
+        let compressed = write_nbt_gzip("Data", &root).unwrap();
+        assert_eq!(&compressed[..2], [0x1f, 0x8b]);
+        assert_eq!(
+            read_nbt_auto(&compressed).unwrap(),
+            ("Data".to_string(), root)
+        );
+    }
+
+    #[test]
+    fn test_write_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("level.dat");
+        write_nbt_file(&path, "", &sample(), true).unwrap();
+        assert_eq!(read_nbt_file(&path).unwrap().1, sample());
+        assert!(!dir.join("level.dat_tmp").exists());
+    }
+
+    #[test]
+    fn test_write_invalid() {
+        let mut root = Compound::new();
+        root.insert("mixed", Tag::List(vec![Tag::Int(1), Tag::Long(2)]));
+        assert!(write_nbt("", &root).is_err());
+
+        let mut root = Compound::new();
+        root.insert("long", Tag::String("a".repeat(70000)));
+        assert_eq!(write_nbt("", &root).unwrap_err(), "NBT 字符串过长");
+    }
+}
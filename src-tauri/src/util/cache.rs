@@ -0,0 +1,370 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::model::DownloadSource;
+use crate::config::util::get_global_config_path;
+use crate::util::mirror::{get_with_failover_headers, reachable_candidates, Mirror};
+use crate::util::network::offline_error;
+use crate::util::utils::write_atomic;
+
+/// 缓存条目的元数据，与响应体分开存放
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntryMeta {
+    /// 官方地址，与实际请求的镜像无关
+    pub url: String,
+    /// 提供当前响应体的源，ETag 与 Last-Modified 只对该源有效
+    #[serde(default)]
+    pub mirror: Option<Mirror>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// 最近一次确认内容有效的时间（Unix 秒）
+    pub fetched_at: u64,
+}
+
+impl CacheEntryMeta {
+    pub fn is_fresh(&self, ttl: Duration, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+
+    /// 向给定源发起条件请求时使用的请求头，其他源的校验值不可混用
+    fn validators_for(&self, mirror: Mirror) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if self.mirror != Some(mirror) {
+            return headers;
+        }
+        if let Some(value) = self
+            .etag
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = self
+            .last_modified
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+}
+
+/// 元数据请求的磁盘缓存，以官方地址为键
+pub struct MetadataCache {
+    dir: PathBuf,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl MetadataCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// 位于全局配置目录下的 `cache/meta`
+    pub fn global() -> Result<Self, String> {
+        Ok(Self::new(
+            get_global_config_path()?.join("cache").join("meta"),
+        ))
+    }
+
+    fn key(url: &str) -> String {
+        format!("{:x}", Sha256::digest(url.as_bytes()))
+    }
+
+    fn body_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.body", Self::key(url)))
+    }
+
+    fn meta_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.meta.json", Self::key(url)))
+    }
+
+    /// 读取缓存，元数据或响应体缺失时返回 None
+    pub fn load(&self, url: &str) -> Option<(CacheEntryMeta, Vec<u8>)> {
+        let meta = fs::read_to_string(self.meta_path(url)).ok()?;
+        let meta: CacheEntryMeta = serde_json::from_str(&meta).ok()?;
+        let body = fs::read(self.body_path(url)).ok()?;
+        Some((meta, body))
+    }
+
+    /// 写入响应体与元数据
+    ///
+    /// 替换响应体前先删除旧的元数据，中途失败时 `load` 只会得到缺失而不是错配的条目。
+    pub fn store(&self, meta: &CacheEntryMeta, body: &[u8]) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+        let meta_path = self.meta_path(&meta.url);
+        if meta_path.exists() {
+            fs::remove_file(&meta_path).map_err(|e| format!("写入缓存失败: {}", e))?;
+        }
+        write_atomic(&self.body_path(&meta.url), body)
+            .map_err(|e| format!("写入缓存失败: {}", e))?;
+        self.store_meta(meta)
+    }
+
+    fn store_meta(&self, meta: &CacheEntryMeta) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(meta)
+            .map_err(|e| format!("序列化缓存元数据失败: {}", e))?;
+        write_atomic(&self.meta_path(&meta.url), content.as_bytes())
+            .map_err(|e| format!("写入缓存元数据失败: {}", e))
+    }
+
+    /// 删除所有缓存文件
+    pub fn clear(&self) -> Result<(), String> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir).map_err(|e| format!("清除缓存失败: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// 获取地址对应的内容
+    ///
+    /// 缓存未过期时直接返回；过期后携带 ETag / Last-Modified 发起条件请求；
+    /// 请求失败时退回到过期的缓存；离线模式下不发起请求，直接使用已有缓存。
+    pub async fn fetch(
+        &self,
+        url: &str,
+        source: &DownloadSource,
+        ttl: Duration,
+    ) -> Result<Vec<u8>, String> {
+        let cached = self.load(url);
+        let now = now_secs();
+        if let Some((meta, body)) = &cached {
+            if meta.is_fresh(ttl, now) {
+                return Ok(body.clone());
+            }
+        }
+        if reachable_candidates(url, source).await.is_empty() {
+            return match cached {
+                Some((_, body)) => Ok(body),
+                None => Err(offline_error(&format!("获取 {}", url))),
+            };
+        }
+        let cached_meta = cached.as_ref().map(|(meta, _)| meta);
+        let result = get_with_failover_headers(url, source, |mirror| {
+            cached_meta
+                .map(|meta| meta.validators_for(mirror))
+                .unwrap_or_default()
+        })
+        .await;
+        let (mirror, response) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                return match cached {
+                    Some((_, body)) => {
+                        eprintln!("请求 {} 失败，使用过期缓存: {}", url, e);
+                        Ok(body)
+                    }
+                    None => Err(e),
+                };
+            }
+        };
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some((mut meta, body)) = cached {
+                meta.fetched_at = now;
+                if let Err(e) = self.store_meta(&meta) {
+                    eprintln!("{}", e);
+                }
+                return Ok(body);
+            }
+            return Err(format!("{}: 服务器返回 304 但本地没有缓存", url));
+        }
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let meta = CacheEntryMeta {
+            url: url.to_string(),
+            mirror: Some(mirror),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            fetched_at: now,
+        };
+        let body = match response.bytes().await {
+            Ok(body) => body.to_vec(),
+            Err(e) => {
+                return match cached {
+                    Some((_, body)) => {
+                        eprintln!("读取 {} 失败，使用过期缓存: {}", url, e);
+                        Ok(body)
+                    }
+                    None => Err(e.to_string()),
+                };
+            }
+        };
+        if let Err(e) = self.store(&meta, &body) {
+            eprintln!("{}", e);
+        }
+        Ok(body)
+    }
+}
+
+/// 通过全局缓存获取并解析 JSON
+pub async fn get_cached_json<T: DeserializeOwned>(
+    url: &str,
+    source: &DownloadSource,
+    ttl: Duration,
+) -> Result<T, String> {
+    let body = MetadataCache::global()?.fetch(url, source, ttl).await?;
+    serde_json::from_slice(&body).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{init_client, serve};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("meta");
+        let cache = MetadataCache::new(dir.clone());
+        let url = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+        assert!(cache.load(url).is_none());
+
+        let meta = CacheEntryMeta {
+            url: url.to_string(),
+            mirror: Some(Mirror::Official),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            fetched_at: 1000,
+        };
+        cache.store(&meta, b"{}").unwrap();
+        let (loaded, body) = cache.load(url).unwrap();
+        assert_eq!(loaded.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(body, b"{}");
+
+        assert!(loaded.is_fresh(Duration::from_secs(60), 1059));
+        assert!(!loaded.is_fresh(Duration::from_secs(60), 1060));
+
+        cache.clear().unwrap();
+        assert!(!dir.exists());
+    }
+
+    fn test_url(base: &str) -> String {
+        format!("{}/manifest.json", base)
+    }
+
+    #[tokio::test]
+    async fn test_revalidate_not_modified() {
+        init_client().await;
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let base = serve(move |request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            if request.header("if-none-match") == Some("\"v1\"") {
+                (304, Vec::new())
+            } else {
+                (200, b"{\"v\":1}".to_vec())
+            }
+        })
+        .await;
+        let temp = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(temp.path().to_path_buf());
+        let url = test_url(&base);
+        cache
+            .store(
+                &CacheEntryMeta {
+                    url: url.clone(),
+                    mirror: Some(Mirror::Official),
+                    etag: Some("\"v1\"".to_string()),
+                    last_modified: None,
+                    fetched_at: 0,
+                },
+                b"{\"v\":0}",
+            )
+            .unwrap();
+
+        // 过期的缓存经 304 确认后继续使用，并刷新确认时间
+        let ttl = Duration::from_secs(60);
+        let body = cache
+            .fetch(&url, &DownloadSource::Official, ttl)
+            .await
+            .unwrap();
+        assert_eq!(body, b"{\"v\":0}");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        let (meta, _) = cache.load(&url).unwrap();
+        assert!(meta.is_fresh(ttl, now_secs()));
+
+        // 未过期时不发起请求
+        cache
+            .fetch(&url, &DownloadSource::Official, ttl)
+            .await
+            .unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // 校验值来自其他源时不发送，直接获取完整内容
+        let (mut meta, body) = cache.load(&url).unwrap();
+        meta.mirror = Some(Mirror::BmclApi);
+        meta.fetched_at = 0;
+        cache.store(&meta, &body).unwrap();
+        let body = cache
+            .fetch(&url, &DownloadSource::Official, ttl)
+            .await
+            .unwrap();
+        assert_eq!(body, b"{\"v\":1}");
+        assert_eq!(cache.load(&url).unwrap().0.mirror, Some(Mirror::Official));
+    }
+
+    #[tokio::test]
+    async fn test_stale_fallback() {
+        init_client().await;
+        let base = serve(|_| (500, Vec::new())).await;
+        let temp = tempfile::tempdir().unwrap();
+        let cache = MetadataCache::new(temp.path().to_path_buf());
+        let ttl = Duration::from_secs(60);
+
+        // 没有缓存时返回请求错误
+        let url = test_url(&base);
+        assert!(cache
+            .fetch(&url, &DownloadSource::Official, ttl)
+            .await
+            .is_err());
+
+        let stale = |url: &str| CacheEntryMeta {
+            url: url.to_string(),
+            mirror: Some(Mirror::Official),
+            etag: None,
+            last_modified: None,
+            fetched_at: 0,
+        };
+        cache.store(&stale(&url), b"stale").unwrap();
+        let body = cache
+            .fetch(&url, &DownloadSource::Official, ttl)
+            .await
+            .unwrap();
+        assert_eq!(body, b"stale");
+
+        // 服务器不可达时同样退回到过期缓存
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unreachable = test_url(&format!("http://{}", listener.local_addr().unwrap()));
+        drop(listener);
+        cache.store(&stale(&unreachable), b"offline").unwrap();
+        let body = cache
+            .fetch(&unreachable, &DownloadSource::Official, ttl)
+            .await
+            .unwrap();
+        assert_eq!(body, b"offline");
+    }
+}
@@ -1,20 +1,18 @@
-use futures::FutureExt;
-use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Mutex;
+use std::sync::Arc;
 use std::{collections::HashMap, path::PathBuf};
 use tauri::ipc::Channel;
-use tauri::{AppHandle, Emitter};
-use tokio::sync::watch;
 
-use crate::config::model::LauncherConfig;
-use crate::util::downloader::download_with_progress;
-use crate::util::model::{DownloadError, DownloadProgress};
+use crate::config::model::{CustomMirrorConfig, DownloadSource, LauncherConfig};
+use crate::util::cache::MetadataCache;
+use crate::util::mirror::{
+    probe_mirrors, set_current_source, set_custom_mirror, test_custom_mirror,
+    CustomMirrorTestResult, MirrorLatency,
+};
+use crate::util::model::{DownloadEvent, DownloadJobSnapshot, DownloadRequest};
+use crate::util::network::{network_status, set_offline_mode, NetworkStatus};
+use crate::util::scheduler::{guess_priority, DownloadEventSink, DOWNLOAD_SCHEDULER};
 use crate::util::{game::init_game_path, init::init_launcher};
-// 全局存储下载任务的取消句柄
-static DOWNLOAD_CANCEL_MAP: Lazy<Mutex<HashMap<String, watch::Sender<bool>>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
 
 #[tauri::command]
 pub async fn init_game_path_command(path: PathBuf) -> Result<(), String> {
@@ -26,127 +24,115 @@ pub fn init_launcher_command() -> () {
     init_launcher()
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", tag = "event", content = "data")]
-pub enum DownloadEvent {
-    Progress {
-        id: String,
-        path: PathBuf,
-        progress: f64,
-        speed: f64,
-    },
-    Error {
-        error: String,
-    },
-    Finished,
-}
-
 #[tauri::command]
 pub async fn download_files(
     on_event: Channel<DownloadEvent>,
     files: HashMap<String, PathBuf>,
+    name: Option<String>,
 ) -> Result<(), String> {
-    use futures::future;
-    let mut handles = Vec::new();
-    for (url, save_path) in files {
-        let on_event = on_event.clone();
-        let (cancel_tx, cancel_rx) = match watch::channel(false) {
-            (tx, rx) => (tx, rx),
-        };
-        let save_path_clone = save_path.clone();
-        let cancel_tx_clone = cancel_tx.clone();
-        handles.push(tokio::spawn({
-            let event_clone_for_error = on_event.clone();
-            async move {
-                let result = std::panic::AssertUnwindSafe(download_with_progress(
-                    &url,
-                    save_path,
-                    {
-                        let on_event = on_event.clone();
-                        move |id, percent, speed| {
-                            if percent >= 0.0 {
-                                let mut map = DOWNLOAD_CANCEL_MAP.lock().unwrap();
-                                map.entry(id.clone())
-                                    .or_insert_with(|| cancel_tx_clone.clone());
-                            }
-                            let payload = DownloadProgress {
-                                id: id.clone(),
-                                path: save_path_clone.clone(),
-                                progress: percent,
-                                speed: speed,
-                            };
-                            println!("下载进度: {:?}", payload);
-                            let _ = on_event.send(DownloadEvent::Progress {
-                                id: id.clone(),
-                                path: save_path_clone.clone(),
-                                progress: percent,
-                                speed: speed,
-                            });
-                        }
-                    },
-                    &cancel_rx,
-                ))
-                .catch_unwind()
-                .await;
-                match result {
-                    Ok(Ok(id)) => {
-                        let mut map = DOWNLOAD_CANCEL_MAP.lock().unwrap();
-                        map.remove(&id);
-                        Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
-                    }
-                    Ok(Err(_e)) => {
-                        let _ = event_clone_for_error.send(DownloadEvent::Error {
-                            error: format!("{:?}", _e),
-                        });
-                        Err(_e)
-                    }
-                    Err(_e) => {
-                        let _ = event_clone_for_error.send(DownloadEvent::Error {
-                            error: format!("{:?}", _e),
-                        });
-                        Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!("{:?}", _e),
-                        ))
-                            as Box<dyn std::error::Error + Send + Sync>)
-                    }
-                }
-            }
-        }));
-    }
-    let results = future::join_all(handles).await;
-    let mut errors = Vec::new();
-    for res in results {
-        match res {
-            Ok(Ok(())) => {}                          // 成功
-            Ok(Err(e)) => errors.push(e.to_string()), // 任务返回错误
-            Err(e) => errors.push(e.to_string()),     // 任务本身 panic 或 join 失败
-        }
-    }
-    if errors.is_empty() {
-        println!("所有下载任务已完成");
-        let _ = on_event.send(DownloadEvent::Finished);
-        Ok(())
-    } else {
-        Err(format!("部分下载任务失败: {}", errors.join("; ")))
-    }
+    let requests = files
+        .into_iter()
+        .map(|(url, path)| DownloadRequest {
+            priority: guess_priority(&path),
+            url,
+            path,
+            size: None,
+        })
+        .collect();
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    DOWNLOAD_SCHEDULER
+        .run(name.unwrap_or_default(), requests, Some(sink))
+        .await
+}
+
+/// 提交下载作业后立即返回作业 ID，进度与结束事件通过 `on_event` 推送
+#[tauri::command]
+pub async fn submit_download_job(
+    on_event: Channel<DownloadEvent>,
+    name: String,
+    requests: Vec<DownloadRequest>,
+) -> Result<String, String> {
+    DOWNLOAD_SCHEDULER.check_online(&requests).await?;
+    let sink: DownloadEventSink = Arc::new(move |event| {
+        let _ = on_event.send(event);
+    });
+    Ok(DOWNLOAD_SCHEDULER.submit(name, requests, Some(sink)))
 }
 
+/// 取消下载作业或任务，`id` 可为作业 ID 或任务 ID
 #[tauri::command]
 pub fn cancel_download(id: String) -> Result<(), String> {
-    let mut map = DOWNLOAD_CANCEL_MAP.lock().unwrap();
-    // println!("{:?}", map);
-    if let Some(tx) = map.remove(&id) {
-        let _ = tx.send(true);
-        Ok(())
-    } else {
-        Err("未找到对应下载任务".to_string())
-    }
+    DOWNLOAD_SCHEDULER.cancel(&id)
+}
+
+#[tauri::command]
+pub fn pause_download(id: String) -> Result<(), String> {
+    DOWNLOAD_SCHEDULER.pause(&id)
+}
+
+#[tauri::command]
+pub fn resume_download(id: String) -> Result<(), String> {
+    DOWNLOAD_SCHEDULER.resume(&id)
+}
+
+#[tauri::command]
+pub fn get_download_jobs() -> Vec<DownloadJobSnapshot> {
+    DOWNLOAD_SCHEDULER.jobs()
+}
+
+#[tauri::command]
+pub fn get_download_job(id: String) -> Result<DownloadJobSnapshot, String> {
+    DOWNLOAD_SCHEDULER
+        .job(&id)
+        .ok_or_else(|| "未找到对应下载任务".to_string())
+}
+
+#[tauri::command]
+pub fn clear_finished_downloads() {
+    DOWNLOAD_SCHEDULER.clear_finished()
 }
 
 #[tauri::command]
 pub async fn update_reqwest_client(config: LauncherConfig) -> () {
     super::reqwest_client::update_reqwest_client(&config).await;
+    set_current_source(config.download_source.clone());
+    set_custom_mirror(config.custom_mirror.clone());
+    set_offline_mode(config.offline_mode);
+    if let DownloadSource::Auto = config.download_source {
+        tokio::spawn(probe_mirrors());
+    }
+    DOWNLOAD_SCHEDULER.set_limits(
+        config.max_download_concurrency,
+        config.max_downloads_per_host,
+    );
+}
+
+/// 测试各下载源的延迟，结果同时用于自动模式的源排序
+#[tauri::command]
+pub async fn probe_download_sources() -> Result<Vec<MirrorLatency>, String> {
+    probe_mirrors().await
+}
+
+/// 测试自定义源中各类别地址的连通性
+#[tauri::command]
+pub async fn test_custom_mirror_command(
+    config: CustomMirrorConfig,
+) -> Result<Vec<CustomMirrorTestResult>, String> {
+    test_custom_mirror(&config).await
+}
+
+/// 清除元数据缓存
+#[tauri::command]
+pub fn clear_metadata_cache() -> Result<(), String> {
+    MetadataCache::global()?.clear()
+}
+
+/// 获取当前的网络状态，`force` 为 true 时忽略缓存重新检测
+#[tauri::command]
+pub async fn get_network_status(force: Option<bool>) -> NetworkStatus {
+    network_status(force.unwrap_or(false)).await
 }
 
 #[tauri::command]
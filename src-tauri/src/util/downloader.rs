@@ -1,34 +1,58 @@
 use crate::util::reqwest_client::REQWEST_CLIENT;
-use std::fs::File;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use std::fs::OpenOptions;
 use std::io::Write;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
-use uuid::Uuid;
-/// 下载文件并实时返回进度（百分比），支持 302 跳转，可取消。
+
+/// 下载任务的控制信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferControl {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// 单次传输结束时的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferOutcome {
+    /// 文件已完整写入
+    Completed,
+    /// 收到暂停信号，已写入的部分保留在磁盘上
+    Paused,
+    /// 收到取消信号
+    Canceled,
+}
+
+/// 下载文件并实时返回进度，支持 302 跳转、断点续传、暂停与取消。
 ///
 /// # 参数
 /// - `url`: 下载链接
 /// - `save_path`: 保存路径（含文件名）
-/// - `progress_callback`: 进度回调，参数为 (id, 百分比)
-/// - `cancel_token`: 取消信号，收到 true 时中断下载
+/// - `resume_from`: 已下载的字节数，大于 0 时通过 Range 请求续传
+/// - `progress_callback`: 进度回调，参数为 (已下载字节, 总字节, 速度 KB/s)
+/// - `control`: 控制信号，收到 Pause 或 Cancel 时中断下载
+/// - `stall_timeout`: 等待响应或下一个数据块的最长时间
 ///
 /// # 返回
-/// Ok((id, ()))，id为本次下载的唯一标识符
+/// 传输结束时的状态
 ///
 /// # 错误
-/// 下载失败、写入失败或被取消时返回错误
+/// 请求失败、超时、写入失败或进度回调 panic 时返回错误
 pub async fn download_with_progress<F>(
     url: &str,
     save_path: PathBuf,
+    resume_from: u64,
     mut progress_callback: F,
-    cancel_token: &watch::Receiver<bool>,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>>
+    control: &watch::Receiver<TransferControl>,
+    stall_timeout: Duration,
+) -> Result<TransferOutcome, Box<dyn std::error::Error + Send + Sync>>
 where
-    F: FnMut(String, f64, f64) + Send + 'static, // 新增速度参数
+    F: FnMut(u64, Option<u64>, f64) + Send,
 {
-    let id = Uuid::new_v4().to_string();
     let client = {
         let guard = REQWEST_CLIENT.lock().await;
         match &*guard {
@@ -36,49 +60,74 @@ where
             None => return Err("HTTP客户端未初始化".into()),
         }
     };
-    if let Err(_) = catch_unwind(AssertUnwindSafe(|| {
-        progress_callback(id.clone(), -1.0, 0.0)
-    })) {
-        return Err("进度回调发生 panic".into());
+    // 在发送 HTTP 请求前检查控制信号
+    match *control.borrow() {
+        TransferControl::Run => {}
+        TransferControl::Pause => return Ok(TransferOutcome::Paused),
+        TransferControl::Cancel => return Ok(TransferOutcome::Canceled),
     }
-    // 在发送 HTTP 请求前检查是否已取消
-    if *cancel_token.borrow() {
-        return Err("canceled".into());
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header(RANGE, format!("bytes={}-", resume_from));
     }
-    let resp = match client.get(url).send().await {
-        Ok(r) => r,
-        Err(e) => return Err(format!("请求发送失败: {}", e).into()),
+    let resp = match tokio::time::timeout(stall_timeout, request.send()).await {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => return Err(format!("请求发送失败: {}", e).into()),
+        Err(_) => return Err("等待响应超时".into()),
     };
     if !resp.status().is_success() {
         return Err(format!("下载失败，状态码: {}", resp.status()).into());
     }
-    let total_size = resp.content_length();
+    // 服务器不支持 Range 时从头开始下载
+    let resumed = resume_from > 0 && resp.status() == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded: u64 = if resumed { resume_from } else { 0 };
+    let total_size = resp.content_length().map(|len| len + downloaded);
     // 自动创建父目录
     if let Some(parent) = save_path.parent() {
         if let Err(e) = std::fs::create_dir_all(parent) {
             return Err(format!("创建文件夹失败: {}", e).into());
         }
     }
-    let mut file = match File::create(&save_path) {
+    let file = if resumed {
+        OpenOptions::new().append(true).open(&save_path)
+    } else {
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&save_path)
+    };
+    let mut file = match file {
         Ok(f) => f,
         Err(e) => return Err(format!("文件创建失败: {}", e).into()),
     };
+    if catch_unwind(AssertUnwindSafe(|| {
+        progress_callback(downloaded, total_size, 0.0)
+    }))
+    .is_err()
+    {
+        return Err("进度回调发生 panic".into());
+    }
     let mut stream = resp.bytes_stream();
-    let mut downloaded: u64 = 0;
     let mut avg_speed = 0.0f64; // 平滑速度
     let mut last_speed_time = Instant::now();
-    let mut last_speed_downloaded = 0u64;
-    let mut last_reported_speed = 0.0f64;
+    let mut last_speed_downloaded = downloaded;
     let speed_interval = std::time::Duration::from_millis(500);
     use futures_util::StreamExt;
 
-    while let Some(chunk) = match stream.next().await {
-        Some(Ok(c)) => Some(c),
-        Some(Err(e)) => return Err(format!("下载数据块失败: {}", e).into()),
-        None => None,
+    while let Some(chunk) = match tokio::time::timeout(stall_timeout, stream.next()).await {
+        Ok(Some(Ok(c))) => Some(c),
+        Ok(Some(Err(e))) => return Err(format!("下载数据块失败: {}", e).into()),
+        Ok(None) => None,
+        Err(_) => return Err("接收数据超时".into()),
     } {
-        if *cancel_token.borrow() {
-            return Err("canceled".into());
+        match *control.borrow() {
+            TransferControl::Run => {}
+            TransferControl::Pause => {
+                let _ = file.flush();
+                return Ok(TransferOutcome::Paused);
+            }
+            TransferControl::Cancel => return Ok(TransferOutcome::Canceled),
         }
         if let Err(e) = file.write_all(&chunk) {
             return Err(format!("写入文件失败: {}", e).into());
@@ -102,29 +151,24 @@ where
             };
             last_speed_time = now;
             last_speed_downloaded = downloaded;
-            last_reported_speed = avg_speed;
         }
-        let cb_result = if let Some(size) = total_size {
-            let percent = (downloaded as f64 / size as f64) * 100.0;
-            catch_unwind(AssertUnwindSafe(|| {
-                progress_callback(id.clone(), percent.min(100.0), last_reported_speed)
-            }))
-        } else {
-            catch_unwind(AssertUnwindSafe(|| {
-                progress_callback(id.clone(), -1.0, last_reported_speed)
-            }))
-        };
-        if cb_result.is_err() {
+        if catch_unwind(AssertUnwindSafe(|| {
+            progress_callback(downloaded, total_size, avg_speed)
+        }))
+        .is_err()
+        {
             return Err("进度回调发生 panic".into());
         }
     }
     if let Err(e) = file.flush() {
         return Err(format!("文件刷新失败: {}", e).into());
     }
-    if let Err(_) = catch_unwind(AssertUnwindSafe(|| {
-        progress_callback(id.clone(), 100.0, 0.0)
-    })) {
+    if catch_unwind(AssertUnwindSafe(|| {
+        progress_callback(downloaded, Some(downloaded), 0.0)
+    }))
+    .is_err()
+    {
         return Err("进度回调发生 panic".into());
     }
-    Ok(id)
+    Ok(TransferOutcome::Completed)
 }
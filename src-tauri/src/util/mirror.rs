@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::config::model::{CustomMirrorConfig, DownloadSource};
+use crate::util::network::{is_offline_for, offline_error};
+use crate::util::reqwest_client::REQWEST_CLIENT;
+
+/// BMCLAPI 根地址
+const BMCLAPI_ROOT: &str = "https://bmclapi2.bangbang93.com";
+
+/// 官方地址（不含协议头）与 BMCLAPI 镜像地址的对应关系
+const BMCLAPI_REWRITES: &[(&str, &str)] = &[
+    ("piston-meta.mojang.com", BMCLAPI_ROOT),
+    ("piston-data.mojang.com", BMCLAPI_ROOT),
+    ("launchermeta.mojang.com", BMCLAPI_ROOT),
+    ("launcher.mojang.com", BMCLAPI_ROOT),
+    (
+        "libraries.minecraft.net",
+        "https://bmclapi2.bangbang93.com/maven",
+    ),
+    (
+        "resources.download.minecraft.net",
+        "https://bmclapi2.bangbang93.com/assets",
+    ),
+    (
+        "maven.fabricmc.net",
+        "https://bmclapi2.bangbang93.com/maven",
+    ),
+    (
+        "meta.fabricmc.net",
+        "https://bmclapi2.bangbang93.com/fabric-meta",
+    ),
+    (
+        "maven.minecraftforge.net",
+        "https://bmclapi2.bangbang93.com/maven",
+    ),
+    (
+        "files.minecraftforge.net/maven",
+        "https://bmclapi2.bangbang93.com/maven",
+    ),
+    (
+        "maven.neoforged.net/releases",
+        "https://bmclapi2.bangbang93.com/maven",
+    ),
+];
+
+/// 自定义源各资源类别所对应的官方地址（不含协议头）
+const CUSTOM_META_HOSTS: &[&str] = &["piston-meta.mojang.com", "launchermeta.mojang.com"];
+const CUSTOM_DATA_HOSTS: &[&str] = &["piston-data.mojang.com", "launcher.mojang.com"];
+const CUSTOM_LIBRARIES_HOSTS: &[&str] = &["libraries.minecraft.net"];
+const CUSTOM_ASSETS_HOSTS: &[&str] = &["resources.download.minecraft.net"];
+const CUSTOM_FABRIC_META_HOSTS: &[&str] = &["meta.fabricmc.net"];
+const CUSTOM_FORGE_MAVEN_HOSTS: &[&str] = &[
+    "maven.minecraftforge.net",
+    "files.minecraftforge.net/maven",
+    "maven.neoforged.net/releases",
+];
+
+/// 单次请求（到收到响应头为止）的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// 测速请求的超时时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 请求失败时记入的延迟，使失败的源在自动模式下排到后面
+const FAILURE_PENALTY_MS: f64 = 30_000.0;
+
+/// 测速时请求的地址，体积小且所有源都提供
+const PROBE_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+/// 具体的下载源，`DownloadSource::Auto` 会在这些源之间切换
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Mirror {
+    Official,
+    BmclApi,
+    Custom,
+}
+
+impl Mirror {
+    /// 自动模式下的默认顺序，官方源为主源
+    ///
+    /// 自定义源只有在至少配置了一个类别时才参与自动模式，见 `auto_mirrors`。
+    pub const ALL: [Mirror; 3] = [Mirror::Official, Mirror::BmclApi, Mirror::Custom];
+
+    /// 将官方地址改写为该源下的地址，无法改写时原样返回
+    pub fn rewrite(&self, url: &str) -> String {
+        match self {
+            Mirror::Official => url.to_string(),
+            Mirror::BmclApi => rewrite_with(url, BMCLAPI_REWRITES),
+            Mirror::Custom => rewrite_with(url, &custom_rewrites(&CUSTOM_MIRROR.read().unwrap())),
+        }
+    }
+}
+
+fn rewrite_with<S: AsRef<str>>(url: &str, table: &[(&str, S)]) -> String {
+    let Some((_, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    for (official, mirror) in table {
+        if let Some(tail) = rest.strip_prefix(official) {
+            if tail.is_empty() || tail.starts_with('/') || tail.starts_with('?') {
+                return format!("{}{}", mirror.as_ref().trim_end_matches('/'), tail);
+            }
+        }
+    }
+    url.to_string()
+}
+
+/// 根据自定义源配置生成改写表，未配置的类别保持官方地址
+fn custom_rewrites(config: &CustomMirrorConfig) -> Vec<(&'static str, String)> {
+    let categories = [
+        (CUSTOM_META_HOSTS, &config.meta),
+        (CUSTOM_DATA_HOSTS, &config.data),
+        (CUSTOM_LIBRARIES_HOSTS, &config.libraries),
+        (CUSTOM_ASSETS_HOSTS, &config.assets),
+        (CUSTOM_FABRIC_META_HOSTS, &config.fabric_meta),
+        (CUSTOM_FORGE_MAVEN_HOSTS, &config.forge_maven),
+    ];
+    let mut table = Vec::new();
+    for (hosts, base) in categories {
+        let Some(base) = base.as_deref().map(str::trim).filter(|b| !b.is_empty()) else {
+            continue;
+        };
+        for host in hosts {
+            table.push((*host, base.to_string()));
+        }
+    }
+    table
+}
+
+/// 当前全局下载源，用于下载调度器等不直接接收下载源参数的地方
+static CURRENT_SOURCE: Lazy<RwLock<DownloadSource>> =
+    Lazy::new(|| RwLock::new(DownloadSource::Official));
+
+/// 当前自定义源配置
+static CUSTOM_MIRROR: Lazy<RwLock<CustomMirrorConfig>> =
+    Lazy::new(|| RwLock::new(CustomMirrorConfig::default()));
+
+/// 各源的平滑延迟（毫秒）
+static MIRROR_LATENCY: Lazy<Mutex<HashMap<Mirror, f64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn set_current_source(source: DownloadSource) {
+    *CURRENT_SOURCE.write().unwrap() = source;
+}
+
+pub fn current_source() -> DownloadSource {
+    CURRENT_SOURCE.read().unwrap().clone()
+}
+
+pub fn set_custom_mirror(config: CustomMirrorConfig) {
+    *CUSTOM_MIRROR.write().unwrap() = config;
+}
+
+fn record_latency(mirror: Mirror, ms: f64) {
+    let mut map = MIRROR_LATENCY.lock().unwrap();
+    let value = map.entry(mirror).or_insert(ms);
+    *value = *value * 0.7 + ms * 0.3;
+}
+
+pub fn record_failure(mirror: Mirror) {
+    record_latency(mirror, FAILURE_PENALTY_MS);
+}
+
+/// 按尝试顺序列出给定下载源对应的具体源
+pub fn mirrors_for(source: &DownloadSource) -> Vec<Mirror> {
+    match source {
+        DownloadSource::Official => vec![Mirror::Official],
+        DownloadSource::BmclApi => vec![Mirror::BmclApi],
+        DownloadSource::Custom => vec![Mirror::Custom],
+        DownloadSource::Auto => {
+            let map = MIRROR_LATENCY.lock().unwrap();
+            rank_mirrors(auto_mirrors(), &map)
+        }
+    }
+}
+
+/// 参与自动模式的源，未配置任何类别的自定义源会改写回官方地址，因此排除
+fn auto_mirrors() -> Vec<Mirror> {
+    let custom_configured = !custom_rewrites(&CUSTOM_MIRROR.read().unwrap()).is_empty();
+    Mirror::ALL
+        .into_iter()
+        .filter(|m| *m != Mirror::Custom || custom_configured)
+        .collect()
+}
+
+/// 所有源均有测速数据时按延迟排序，否则保持默认顺序
+fn rank_mirrors(mut mirrors: Vec<Mirror>, latency: &HashMap<Mirror, f64>) -> Vec<Mirror> {
+    if mirrors.iter().all(|m| latency.contains_key(m)) {
+        mirrors.sort_by(|a, b| latency[a].total_cmp(&latency[b]));
+    }
+    mirrors
+}
+
+/// 列出某个官方地址在给定下载源下依次尝试的地址
+pub fn candidate_urls(url: &str, source: &DownloadSource) -> Vec<(Mirror, String)> {
+    let mut candidates: Vec<(Mirror, String)> = Vec::new();
+    for mirror in mirrors_for(source) {
+        let rewritten = mirror.rewrite(url);
+        if !candidates.iter().any(|(_, u)| *u == rewritten) {
+            candidates.push((mirror, rewritten));
+        }
+    }
+    candidates
+}
+
+/// 同 `candidate_urls`，但去掉离线时无法访问的源；自定义镜像等非 Mojang 地址只受手动离线模式限制
+pub async fn reachable_candidates(url: &str, source: &DownloadSource) -> Vec<(Mirror, String)> {
+    let mut reachable = Vec::new();
+    for (mirror, candidate) in candidate_urls(url, source) {
+        if !is_offline_for(&candidate).await {
+            reachable.push((mirror, candidate));
+        }
+    }
+    reachable
+}
+
+/// 请求一个官方地址，在自动模式下遇到错误、超时或非成功状态码时切换到下一个源
+pub async fn get_with_failover(
+    url: &str,
+    source: &DownloadSource,
+) -> Result<reqwest::Response, String> {
+    get_with_failover_headers(url, source, |_| HeaderMap::new())
+        .await
+        .map(|(_, response)| response)
+}
+
+/// 同 `get_with_failover`，可按源附加请求头，并返回实际响应的源；304 Not Modified 视为成功
+pub async fn get_with_failover_headers(
+    url: &str,
+    source: &DownloadSource,
+    headers_for: impl Fn(Mirror) -> HeaderMap,
+) -> Result<(Mirror, reqwest::Response), String> {
+    let candidates = reachable_candidates(url, source).await;
+    if candidates.is_empty() {
+        return Err(offline_error(&format!("访问 {}", url)));
+    }
+    let client = {
+        let guard = REQWEST_CLIENT.lock().await;
+        match &*guard {
+            Some(c) => c.clone(),
+            None => return Err("HTTP客户端未初始化".to_string()),
+        }
+    };
+    let mut errors = Vec::new();
+    for (mirror, candidate) in candidates {
+        let start = Instant::now();
+        let request = client.get(&candidate).headers(headers_for(mirror));
+        let result = tokio::time::timeout(REQUEST_TIMEOUT, request.send()).await;
+        match result {
+            Ok(Ok(response))
+                if response.status().is_success()
+                    || response.status() == StatusCode::NOT_MODIFIED =>
+            {
+                record_latency(mirror, start.elapsed().as_secs_f64() * 1000.0);
+                return Ok((mirror, response));
+            }
+            Ok(Ok(response)) => {
+                record_failure(mirror);
+                errors.push(format!("{}: {}", candidate, response.status()));
+            }
+            Ok(Err(e)) => {
+                record_failure(mirror);
+                errors.push(format!("{}: {}", candidate, e));
+            }
+            Err(_) => {
+                record_failure(mirror);
+                errors.push(format!("{}: 请求超时", candidate));
+            }
+        }
+    }
+    Err(errors.join("; "))
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorLatency {
+    pub mirror: Mirror,
+    /// 测速失败时为空
+    pub latency_ms: Option<u64>,
+}
+
+/// 并发测试各源的延迟并记录结果，供自动模式排序使用
+pub async fn probe_mirrors() -> Result<Vec<MirrorLatency>, String> {
+    let client = {
+        let guard = REQWEST_CLIENT.lock().await;
+        match &*guard {
+            Some(c) => c.clone(),
+            None => return Err("HTTP客户端未初始化".to_string()),
+        }
+    };
+    let probes = auto_mirrors().into_iter().map(|mirror| {
+        let client = client.clone();
+        let url = mirror.rewrite(PROBE_URL);
+        async move {
+            let start = Instant::now();
+            let result = tokio::time::timeout(PROBE_TIMEOUT, client.head(&url).send()).await;
+            let latency = match result {
+                Ok(Ok(response)) if response.status().is_success() => Some(start.elapsed()),
+                _ => None,
+            };
+            (mirror, latency)
+        }
+    });
+    let results = futures::future::join_all(probes).await;
+    let mut map = MIRROR_LATENCY.lock().unwrap();
+    Ok(results
+        .into_iter()
+        .map(|(mirror, latency)| {
+            // 测速结果直接覆盖历史数据
+            let ms = latency.map_or(FAILURE_PENALTY_MS, |d| d.as_secs_f64() * 1000.0);
+            map.insert(mirror, ms);
+            MirrorLatency {
+                mirror,
+                latency_ms: latency.map(|d| d.as_millis() as u64),
+            }
+        })
+        .collect())
+}
+
+/// 自定义源连通性测试时各类别请求的路径，为空时只测试能否连接
+const CUSTOM_TEST_PATHS: &[(&str, &str)] = &[
+    ("meta", "/mc/game/version_manifest_v2.json"),
+    ("data", ""),
+    (
+        "libraries",
+        "/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar",
+    ),
+    ("assets", ""),
+    ("fabricMeta", "/v2/versions/game"),
+    ("forgeMaven", ""),
+];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomMirrorTestResult {
+    /// 资源类别
+    pub category: String,
+    pub url: String,
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 测试自定义源中已配置的各类别地址能否访问
+///
+/// 有固定测试文件的类别要求返回成功状态码，其余类别只要收到 HTTP 响应即视为可达。
+pub async fn test_custom_mirror(
+    config: &CustomMirrorConfig,
+) -> Result<Vec<CustomMirrorTestResult>, String> {
+    let client = {
+        let guard = REQWEST_CLIENT.lock().await;
+        match &*guard {
+            Some(c) => c.clone(),
+            None => return Err("HTTP客户端未初始化".to_string()),
+        }
+    };
+    let bases = [
+        &config.meta,
+        &config.data,
+        &config.libraries,
+        &config.assets,
+        &config.fabric_meta,
+        &config.forge_maven,
+    ];
+    let tests = CUSTOM_TEST_PATHS
+        .iter()
+        .zip(bases)
+        .filter_map(|((category, path), base)| {
+            let base = base.as_deref().map(str::trim).filter(|b| !b.is_empty())?;
+            Some((
+                *category,
+                *path,
+                format!("{}{}", base.trim_end_matches('/'), path),
+            ))
+        })
+        .map(|(category, path, url)| {
+            let client = client.clone();
+            async move {
+                let start = Instant::now();
+                let result = tokio::time::timeout(PROBE_TIMEOUT, client.get(&url).send()).await;
+                let latency_ms = Some(start.elapsed().as_millis() as u64);
+                let (reachable, status, latency_ms, error) = match result {
+                    Ok(Ok(response)) => {
+                        let status = response.status();
+                        let reachable = if path.is_empty() {
+                            !status.is_server_error()
+                        } else {
+                            status.is_success()
+                        };
+                        (reachable, Some(status.as_u16()), latency_ms, None)
+                    }
+                    Ok(Err(e)) => (false, None, None, Some(e.to_string())),
+                    Err(_) => (false, None, None, Some("请求超时".to_string())),
+                };
+                CustomMirrorTestResult {
+                    category: category.to_string(),
+                    url,
+                    reachable,
+                    status,
+                    latency_ms,
+                    error,
+                }
+            }
+        });
+    Ok(futures::future::join_all(tests).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_bmclapi() {
+        let cases = [
+            (
+                "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json",
+                "https://bmclapi2.bangbang93.com/mc/game/version_manifest_v2.json",
+            ),
+            (
+                "https://piston-data.mojang.com/v1/objects/abc/client.jar",
+                "https://bmclapi2.bangbang93.com/v1/objects/abc/client.jar",
+            ),
+            (
+                "https://libraries.minecraft.net/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar",
+                "https://bmclapi2.bangbang93.com/maven/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar",
+            ),
+            (
+                "https://resources.download.minecraft.net/ab/abcdef",
+                "https://bmclapi2.bangbang93.com/assets/ab/abcdef",
+            ),
+            (
+                "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar",
+                "https://bmclapi2.bangbang93.com/maven/net/fabricmc/fabric-loader/0.16.14/fabric-loader-0.16.14.jar",
+            ),
+            (
+                "https://meta.fabricmc.net/v2/versions/game",
+                "https://bmclapi2.bangbang93.com/fabric-meta/v2/versions/game",
+            ),
+            // 非官方地址保持不变
+            (
+                "https://example.com/file.jar",
+                "https://example.com/file.jar",
+            ),
+            // 只匹配完整主机名
+            (
+                "https://meta.fabricmc.net.example.com/a",
+                "https://meta.fabricmc.net.example.com/a",
+            ),
+        ];
+        for (official, expected) in cases {
+            assert_eq!(Mirror::BmclApi.rewrite(official), expected);
+            assert_eq!(Mirror::Official.rewrite(official), official);
+        }
+    }
+
+    #[test]
+    fn test_rewrite_custom() {
+        let config = CustomMirrorConfig {
+            meta: Some("http://10.0.0.2:8080/meta/".to_string()),
+            libraries: Some("http://10.0.0.2:8080/libraries".to_string()),
+            fabric_meta: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let table = custom_rewrites(&config);
+        assert_eq!(
+            rewrite_with(
+                "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json",
+                &table
+            ),
+            "http://10.0.0.2:8080/meta/mc/game/version_manifest_v2.json"
+        );
+        assert_eq!(
+            rewrite_with(
+                "https://libraries.minecraft.net/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar",
+                &table
+            ),
+            "http://10.0.0.2:8080/libraries/com/mojang/brigadier/1.0.18/brigadier-1.0.18.jar"
+        );
+        // 未配置的类别保持官方地址
+        assert_eq!(
+            rewrite_with("https://meta.fabricmc.net/v2/versions/game", &table),
+            "https://meta.fabricmc.net/v2/versions/game"
+        );
+    }
+
+    #[test]
+    fn test_rank_mirrors() {
+        let mirrors = vec![Mirror::Official, Mirror::BmclApi];
+        let mut latency = HashMap::new();
+        assert_eq!(rank_mirrors(mirrors.clone(), &latency), mirrors);
+        latency.insert(Mirror::BmclApi, 50.0);
+        assert_eq!(rank_mirrors(mirrors.clone(), &latency), mirrors);
+        latency.insert(Mirror::Official, 300.0);
+        assert_eq!(
+            rank_mirrors(mirrors, &latency),
+            vec![Mirror::BmclApi, Mirror::Official]
+        );
+    }
+
+    #[test]
+    fn test_candidate_urls_dedup() {
+        let candidates = candidate_urls("https://example.com/a", &DownloadSource::Auto);
+        assert_eq!(candidates.len(), 1);
+        let candidates = candidate_urls(
+            "https://meta.fabricmc.net/v2/versions/game",
+            &DownloadSource::Auto,
+        );
+        assert_eq!(candidates.len(), 2);
+    }
+}
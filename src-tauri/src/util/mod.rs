@@ -1,6 +1,13 @@
+pub mod cache;
 pub mod command;
 pub mod downloader;
 pub mod game;
 pub mod init;
+pub mod mirror;
 pub mod model;
+pub mod network;
 pub mod reqwest_client;
+pub mod scheduler;
+#[cfg(test)]
+pub mod test_server;
+pub mod utils;
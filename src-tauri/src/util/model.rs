@@ -1,16 +1,114 @@
 use std::path::PathBuf;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Clone, Debug)]
-pub struct DownloadProgress {
+pub struct DownloadError {
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum DownloadEvent {
+    Progress {
+        id: String,
+        path: PathBuf,
+        progress: f64,
+        speed: f64,
+    },
+    JobProgress {
+        id: String,
+        downloaded_bytes: u64,
+        total_bytes: u64,
+        speed: f64,
+        eta: Option<u64>,
+    },
+    Error {
+        id: String,
+        error: String,
+    },
+    Finished,
+}
+
+/// 下载优先级，数值越大越先被调度
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadPriority {
+    /// 资源文件等数量庞大的小文件
+    Low,
+    /// 库文件、客户端 jar 等
+    #[default]
+    Normal,
+    /// 版本 JSON、资源索引等后续步骤依赖的元数据
+    High,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+impl DownloadState {
+    /// 是否为终止状态
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            DownloadState::Completed | DownloadState::Failed | DownloadState::Canceled
+        )
+    }
+}
+
+/// 提交给调度器的单个下载请求
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRequest {
+    pub url: String,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub priority: DownloadPriority,
+    /// 预期文件大小，用于在响应到达前估算总字节数
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadTaskSnapshot {
     pub id: String,
+    pub job_id: String,
+    pub url: String,
     pub path: PathBuf,
-    pub progress: f64,
+    pub priority: DownloadPriority,
+    pub state: DownloadState,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
     pub speed: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Clone, Debug)]
-pub struct DownloadError {
-    pub error: String,
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJobSnapshot {
+    pub id: String,
+    pub name: String,
+    pub state: DownloadState,
+    pub total_tasks: usize,
+    pub completed_tasks: usize,
+    pub failed_tasks: usize,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+    /// 所有运行中任务的速度之和，单位 KB/s
+    pub speed: f64,
+    /// 预计剩余秒数，总大小未知或速度为 0 时为空
+    pub eta: Option<u64>,
+    pub tasks: Vec<DownloadTaskSnapshot>,
 }
@@ -0,0 +1,172 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::util::reqwest_client::REQWEST_CLIENT;
+
+/// 联网检测时请求的地址，任意一个可达即视为在线
+const DETECT_URLS: &[&str] = &[
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json",
+    "https://bmclapi2.bangbang93.com/mc/game/version_manifest_v2.json",
+];
+/// 自动检测只探测 Mojang 与 BMCLAPI，其结果只限制访问这些域名的请求；
+/// 自定义镜像、局域网等其他地址只受手动离线模式限制
+const DETECTED_HOSTS: &[&str] = &["mojang.com", "minecraft.net", "bangbang93.com"];
+const DETECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// 检测结果的有效期，避免每次请求都重新检测
+const DETECT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 用户是否手动开启了离线模式
+static OFFLINE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 最近一次自动检测的结果与时间
+static DETECTED: Lazy<Mutex<Option<(bool, Instant)>>> = Lazy::new(|| Mutex::new(None));
+
+/// 同一时间只进行一次检测，其余调用等待并复用结果
+static DETECTING: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    /// 是否处于离线模式（手动开启或检测到无网络）
+    pub offline: bool,
+    /// 是否手动开启了离线模式
+    pub explicit: bool,
+    /// 自动检测的结果，手动离线时不检测
+    pub reachable: Option<bool>,
+}
+
+pub fn set_offline_mode(enabled: bool) {
+    OFFLINE_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// 检测网络是否可用，结果会缓存一段时间
+///
+/// 使用共享的 HTTP 客户端发起请求，因此会经过用户配置的代理。
+pub async fn detect_online(force: bool) -> bool {
+    let cached = || match *DETECTED.lock().unwrap() {
+        Some((online, at)) if at.elapsed() < DETECT_INTERVAL => Some(online),
+        _ => None,
+    };
+    if !force {
+        if let Some(online) = cached() {
+            return online;
+        }
+    }
+    let _detecting = DETECTING.lock().await;
+    // 等待期间其他调用可能已完成检测
+    if !force {
+        if let Some(online) = cached() {
+            return online;
+        }
+    }
+    let client = {
+        let guard = REQWEST_CLIENT.lock().await;
+        match &*guard {
+            Some(c) => c.clone(),
+            None => return true,
+        }
+    };
+    let probes = DETECT_URLS.iter().map(|url| {
+        let client = client.clone();
+        async move {
+            matches!(
+                tokio::time::timeout(DETECT_TIMEOUT, client.head(*url).send()).await,
+                Ok(Ok(_))
+            )
+        }
+    });
+    let online = futures::future::join_all(probes)
+        .await
+        .into_iter()
+        .any(|ok| ok);
+    *DETECTED.lock().unwrap() = Some((online, Instant::now()));
+    if !online {
+        eprintln!("未检测到网络连接，已切换到离线模式");
+    }
+    online
+}
+
+/// 测试中跳过联网检测，直接视为在线
+#[cfg(test)]
+pub fn assume_online() {
+    *DETECTED.lock().unwrap() = Some((true, Instant::now()));
+}
+
+/// 当前是否应按离线模式运行
+pub async fn is_offline() -> bool {
+    OFFLINE_MODE.load(Ordering::Relaxed) || !detect_online(false).await
+}
+
+/// 地址是否属于自动检测探测的域名
+fn is_detected_host(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| {
+            DETECTED_HOSTS
+                .iter()
+                .any(|d| host == *d || host.ends_with(&format!(".{}", d)))
+        })
+}
+
+/// 当前是否无法访问该地址：手动离线时都无法访问，自动检测到离线时只限制 Mojang 与 BMCLAPI
+pub async fn is_offline_for(url: &str) -> bool {
+    if OFFLINE_MODE.load(Ordering::Relaxed) {
+        return true;
+    }
+    is_detected_host(url) && !detect_online(false).await
+}
+
+pub async fn network_status(force: bool) -> NetworkStatus {
+    let explicit = OFFLINE_MODE.load(Ordering::Relaxed);
+    if explicit {
+        return NetworkStatus {
+            offline: true,
+            explicit,
+            reachable: None,
+        };
+    }
+    let reachable = detect_online(force).await;
+    NetworkStatus {
+        offline: !reachable,
+        explicit,
+        reachable: Some(reachable),
+    }
+}
+
+/// 离线时无法完成的操作的提示信息
+pub fn offline_error(step: &str) -> String {
+    format!("当前处于离线模式，无法{}，请连接网络后重试", step)
+}
+
+/// 执行只能联网完成的步骤前调用，离线时返回带有步骤说明的错误
+pub async fn ensure_online(step: &str) -> Result<(), String> {
+    if is_offline().await {
+        Err(offline_error(step))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_detected_host() {
+        assert!(is_detected_host(
+            "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json"
+        ));
+        assert!(is_detected_host("https://libraries.minecraft.net/a.jar"));
+        assert!(is_detected_host(
+            "https://bmclapi2.bangbang93.com/maven/a.jar"
+        ));
+        assert!(!is_detected_host("http://192.168.1.10:8080/maven/a.jar"));
+        assert!(!is_detected_host("https://notmojang.com/a.jar"));
+        assert!(!is_detected_host("not a url"));
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::lock::Mutex;
 use once_cell::sync::Lazy;
@@ -10,8 +11,15 @@ pub type SharedClient = Arc<Mutex<Option<Client>>>;
 
 pub static REQWEST_CLIENT: Lazy<SharedClient> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
+/// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 两次读取之间的最长间隔，避免连接挂起时请求永远不返回
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub fn create_reqwest_client(config: &LauncherConfig) -> Result<Client, reqwest::Error> {
-    let mut builder = Client::builder();
+    let mut builder = Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT);
     if config.enable_proxy {
         match &config.proxy.host {
             Some(host) if !host.trim().is_empty() => {
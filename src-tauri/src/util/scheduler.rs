@@ -0,0 +1,1039 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::util::downloader::{download_with_progress, TransferControl, TransferOutcome};
+use crate::util::mirror::{
+    candidate_urls, current_source, reachable_candidates, record_failure, Mirror,
+};
+use crate::util::model::{
+    DownloadEvent, DownloadJobSnapshot, DownloadPriority, DownloadRequest, DownloadState,
+    DownloadTaskSnapshot,
+};
+use crate::util::network::offline_error;
+
+pub const DEFAULT_MAX_CONCURRENCY: usize = 64;
+pub const DEFAULT_MAX_PER_HOST: usize = 8;
+
+/// 单个任务两次进度事件之间的最小间隔
+const TASK_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+/// 单个作业两次汇总进度事件之间的最小间隔
+const JOB_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+/// 超过该时长未收到数据即视为当前源失效
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 下载事件的接收者，通常包装了前端传入的 Channel
+pub type DownloadEventSink = Arc<dyn Fn(DownloadEvent) + Send + Sync>;
+
+/// 全局下载调度器
+pub static DOWNLOAD_SCHEDULER: Lazy<DownloadScheduler> = Lazy::new(DownloadScheduler::new);
+
+struct TaskEntry {
+    id: String,
+    job_id: String,
+    request: DownloadRequest,
+    host: String,
+    /// 提交顺序，同优先级下先提交的先调度
+    seq: u64,
+    state: DownloadState,
+    downloaded: u64,
+    total: Option<u64>,
+    speed: f64,
+    error: Option<String>,
+    /// 仅在任务运行时存在
+    control: Option<watch::Sender<TransferControl>>,
+    last_event: Option<Instant>,
+}
+
+impl TaskEntry {
+    fn snapshot(&self) -> DownloadTaskSnapshot {
+        DownloadTaskSnapshot {
+            id: self.id.clone(),
+            job_id: self.job_id.clone(),
+            url: self.request.url.clone(),
+            path: self.request.path.clone(),
+            priority: self.request.priority,
+            state: self.state,
+            downloaded_bytes: self.downloaded,
+            total_bytes: self.total.or(self.request.size),
+            speed: self.speed,
+            error: self.error.clone(),
+        }
+    }
+
+    fn progress_event(&self) -> DownloadEvent {
+        let progress = match self.total {
+            Some(total) if total > 0 => (self.downloaded as f64 / total as f64 * 100.0).min(100.0),
+            _ => -1.0,
+        };
+        DownloadEvent::Progress {
+            id: self.id.clone(),
+            path: self.request.path.clone(),
+            progress,
+            speed: self.speed,
+        }
+    }
+}
+
+struct JobEntry {
+    id: String,
+    name: String,
+    task_ids: Vec<String>,
+    sink: Option<DownloadEventSink>,
+    /// 作业结束后写入最终状态
+    done: watch::Sender<Option<DownloadState>>,
+    last_event: Option<Instant>,
+    /// 作业结束后保留的快照，任务列表只含未完成的任务
+    summary: Option<DownloadJobSnapshot>,
+}
+
+struct SchedulerState {
+    max_concurrency: usize,
+    max_per_host: usize,
+    running: usize,
+    host_running: HashMap<String, usize>,
+    next_seq: u64,
+    jobs: HashMap<String, JobEntry>,
+    tasks: HashMap<String, TaskEntry>,
+}
+
+impl SchedulerState {
+    fn job_snapshot(&self, job: &JobEntry) -> DownloadJobSnapshot {
+        if let Some(summary) = &job.summary {
+            return summary.clone();
+        }
+        let tasks: Vec<DownloadTaskSnapshot> = job
+            .task_ids
+            .iter()
+            .filter_map(|id| self.tasks.get(id))
+            .map(TaskEntry::snapshot)
+            .collect();
+        summarize_job(&job.id, &job.name, tasks)
+    }
+
+    fn job_state(&self, job: &JobEntry) -> DownloadState {
+        job_state_of(
+            job.task_ids
+                .iter()
+                .filter_map(|id| self.tasks.get(id))
+                .map(|t| t.state),
+        )
+    }
+}
+
+/// 根据任务状态推导作业状态
+fn job_state_of(states: impl Iterator<Item = DownloadState>) -> DownloadState {
+    let mut any_running = false;
+    let mut any_queued = false;
+    let mut any_paused = false;
+    let mut any_failed = false;
+    let mut any_canceled = false;
+    for state in states {
+        match state {
+            DownloadState::Running => any_running = true,
+            DownloadState::Queued => any_queued = true,
+            DownloadState::Paused => any_paused = true,
+            DownloadState::Failed => any_failed = true,
+            DownloadState::Canceled => any_canceled = true,
+            DownloadState::Completed => {}
+        }
+    }
+    if any_running {
+        DownloadState::Running
+    } else if any_queued {
+        DownloadState::Queued
+    } else if any_paused {
+        DownloadState::Paused
+    } else if any_failed {
+        DownloadState::Failed
+    } else if any_canceled {
+        DownloadState::Canceled
+    } else {
+        DownloadState::Completed
+    }
+}
+
+/// 汇总作业下所有任务的字节数、速度与剩余时间
+fn summarize_job(id: &str, name: &str, tasks: Vec<DownloadTaskSnapshot>) -> DownloadJobSnapshot {
+    let mut downloaded_bytes = 0;
+    let mut total_bytes = 0;
+    let mut total_known = true;
+    let mut speed = 0.0;
+    let mut completed_tasks = 0;
+    let mut failed_tasks = 0;
+    for task in &tasks {
+        downloaded_bytes += task.downloaded_bytes;
+        match task.total_bytes {
+            Some(total) => total_bytes += total.max(task.downloaded_bytes),
+            None => {
+                total_bytes += task.downloaded_bytes;
+                if !task.state.is_finished() {
+                    total_known = false;
+                }
+            }
+        }
+        if task.state == DownloadState::Running {
+            speed += task.speed;
+        }
+        match task.state {
+            DownloadState::Completed => completed_tasks += 1,
+            DownloadState::Failed => failed_tasks += 1,
+            _ => {}
+        }
+    }
+    let eta = if total_known && speed > 0.0 {
+        Some((total_bytes.saturating_sub(downloaded_bytes) as f64 / (speed * 1024.0)).ceil() as u64)
+    } else {
+        None
+    };
+    DownloadJobSnapshot {
+        id: id.to_string(),
+        name: name.to_string(),
+        state: job_state_of(tasks.iter().map(|t| t.state)),
+        total_tasks: tasks.len(),
+        completed_tasks,
+        failed_tasks,
+        downloaded_bytes,
+        total_bytes,
+        speed,
+        eta,
+        tasks,
+    }
+}
+
+/// 根据保存路径推断下载优先级
+pub fn guess_priority(path: &Path) -> DownloadPriority {
+    let is_asset_object = path
+        .components()
+        .collect::<Vec<_>>()
+        .windows(2)
+        .any(|w| w[0].as_os_str() == "assets" && w[1].as_os_str() == "objects");
+    if is_asset_object {
+        DownloadPriority::Low
+    } else if path.extension().is_some_and(|ext| ext == "json") {
+        DownloadPriority::High
+    } else {
+        DownloadPriority::Normal
+    }
+}
+
+fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_default()
+}
+
+/// 带并发上限、单主机上限与优先级的下载调度器
+///
+/// 一次提交的一组文件为一个作业（job），其中每个文件为一个任务（task）。
+/// 作业与任务均可单独暂停、继续与取消。
+pub struct DownloadScheduler {
+    state: Mutex<SchedulerState>,
+}
+
+impl DownloadScheduler {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(SchedulerState {
+                max_concurrency: DEFAULT_MAX_CONCURRENCY,
+                max_per_host: DEFAULT_MAX_PER_HOST,
+                running: 0,
+                host_running: HashMap::new(),
+                next_seq: 0,
+                jobs: HashMap::new(),
+                tasks: HashMap::new(),
+            }),
+        }
+    }
+
+    /// 更新并发限制，0 视为 1
+    pub fn set_limits(&'static self, max_concurrency: usize, max_per_host: usize) {
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            state.max_concurrency = max_concurrency.max(1);
+            state.max_per_host = max_per_host.max(1);
+        }
+        self.pump();
+    }
+
+    /// 提交作业前检查网络，每个作业只检查一次；任一请求的所有源都无法访问时返回离线错误
+    pub async fn check_online(&self, requests: &[DownloadRequest]) -> Result<(), String> {
+        let source = current_source();
+        for request in requests {
+            if reachable_candidates(&request.url, &source).await.is_empty() {
+                return Err(offline_error(&format!("下载 {}", request.url)));
+            }
+        }
+        Ok(())
+    }
+
+    /// 提交一个下载作业，返回作业 ID
+    pub fn submit(
+        &'static self,
+        name: impl Into<String>,
+        requests: Vec<DownloadRequest>,
+        sink: Option<DownloadEventSink>,
+    ) -> String {
+        let job_id = Uuid::new_v4().to_string();
+        let (done, _) = watch::channel(None);
+        let source = current_source();
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            let mut task_ids = Vec::with_capacity(requests.len());
+            for request in requests {
+                let id = Uuid::new_v4().to_string();
+                let seq = state.next_seq;
+                state.next_seq += 1;
+                state.tasks.insert(
+                    id.clone(),
+                    TaskEntry {
+                        id: id.clone(),
+                        job_id: job_id.clone(),
+                        host: candidate_urls(&request.url, &source)
+                            .first()
+                            .map(|(_, url)| host_of(url))
+                            .unwrap_or_default(),
+                        request,
+                        seq,
+                        state: DownloadState::Queued,
+                        downloaded: 0,
+                        total: None,
+                        speed: 0.0,
+                        error: None,
+                        control: None,
+                        last_event: None,
+                    },
+                );
+                task_ids.push(id);
+            }
+            let empty = task_ids.is_empty();
+            state.jobs.insert(
+                job_id.clone(),
+                JobEntry {
+                    id: job_id.clone(),
+                    name: name.into(),
+                    task_ids,
+                    sink,
+                    done,
+                    last_event: None,
+                    summary: None,
+                },
+            );
+            if empty {
+                let mut events = Vec::new();
+                self.finish_job_if_done(state, &job_id, &mut events);
+                drop(guard);
+                emit(events);
+            }
+        }
+        self.pump();
+        job_id
+    }
+
+    /// 提交作业并等待其结束
+    pub async fn run(
+        &'static self,
+        name: impl Into<String>,
+        requests: Vec<DownloadRequest>,
+        sink: Option<DownloadEventSink>,
+    ) -> Result<(), String> {
+        self.check_online(&requests).await?;
+        let job_id = self.submit(name, requests, sink);
+        self.wait(&job_id).await
+    }
+
+    /// 等待作业结束，作业未全部完成时返回失败任务的错误信息
+    pub async fn wait(&self, job_id: &str) -> Result<(), String> {
+        let mut rx = {
+            let state = self.state.lock().unwrap();
+            match state.jobs.get(job_id) {
+                Some(job) => job.done.subscribe(),
+                None => return Err("未找到对应下载任务".to_string()),
+            }
+        };
+        let final_state = loop {
+            if let Some(final_state) = *rx.borrow_and_update() {
+                break final_state;
+            }
+            if rx.changed().await.is_err() {
+                return Err("下载任务已被移除".to_string());
+            }
+        };
+        match final_state {
+            DownloadState::Completed => Ok(()),
+            DownloadState::Canceled => Err("canceled".to_string()),
+            _ => {
+                let state = self.state.lock().unwrap();
+                let errors: Vec<String> = state
+                    .jobs
+                    .get(job_id)
+                    .and_then(|job| job.summary.as_ref())
+                    .map(|summary| {
+                        summary
+                            .tasks
+                            .iter()
+                            .filter_map(|t| t.error.as_ref().map(|e| format!("{}: {}", t.url, e)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Err(format!("部分下载任务失败: {}", errors.join("; ")))
+            }
+        }
+    }
+
+    /// 暂停作业或任务
+    pub fn pause(&'static self, id: &str) -> Result<(), String> {
+        let mut events = Vec::new();
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            let task_ids = self.resolve_ids(state, id)?;
+            for task_id in &task_ids {
+                if let Some(task) = state.tasks.get_mut(task_id) {
+                    match task.state {
+                        DownloadState::Queued => task.state = DownloadState::Paused,
+                        DownloadState::Running => {
+                            if let Some(control) = &task.control {
+                                let _ = control.send(TransferControl::Pause);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            self.collect_job_progress(state, &task_ids, &mut events, true);
+        }
+        emit(events);
+        Ok(())
+    }
+
+    /// 继续已暂停的作业或任务
+    pub fn resume(&'static self, id: &str) -> Result<(), String> {
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            let task_ids = self.resolve_ids(state, id)?;
+            for task_id in &task_ids {
+                if let Some(task) = state.tasks.get_mut(task_id) {
+                    match task.state {
+                        DownloadState::Paused => task.state = DownloadState::Queued,
+                        // 暂停信号已发出但任务尚未停下
+                        DownloadState::Running => {
+                            if let Some(control) = &task.control {
+                                let _ = control.send(TransferControl::Run);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        self.pump();
+        Ok(())
+    }
+
+    /// 取消作业或任务
+    pub fn cancel(&'static self, id: &str) -> Result<(), String> {
+        let mut events = Vec::new();
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            let task_ids = self.resolve_ids(state, id)?;
+            let mut job_ids = Vec::new();
+            for task_id in &task_ids {
+                if let Some(task) = state.tasks.get_mut(task_id) {
+                    match task.state {
+                        DownloadState::Queued | DownloadState::Paused => {
+                            task.state = DownloadState::Canceled;
+                            task.speed = 0.0;
+                            let _ = std::fs::remove_file(&task.request.path);
+                            job_ids.push(task.job_id.clone());
+                        }
+                        DownloadState::Running => {
+                            if let Some(control) = &task.control {
+                                let _ = control.send(TransferControl::Cancel);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            job_ids.dedup();
+            for job_id in job_ids {
+                self.finish_job_if_done(state, &job_id, &mut events);
+            }
+        }
+        emit(events);
+        Ok(())
+    }
+
+    /// 获取所有作业的快照
+    pub fn jobs(&self) -> Vec<DownloadJobSnapshot> {
+        let state = self.state.lock().unwrap();
+        state
+            .jobs
+            .values()
+            .map(|job| state.job_snapshot(job))
+            .collect()
+    }
+
+    /// 获取指定作业的快照
+    pub fn job(&self, job_id: &str) -> Option<DownloadJobSnapshot> {
+        let state = self.state.lock().unwrap();
+        state.jobs.get(job_id).map(|job| state.job_snapshot(job))
+    }
+
+    /// 从注册表中移除所有已结束的作业
+    pub fn clear_finished(&self) {
+        let mut state = self.state.lock().unwrap();
+        // 已结束作业的任务在结束时已被移除
+        state.jobs.retain(|_, job| job.done.borrow().is_none());
+    }
+
+    /// 将 ID 解析为任务 ID 列表，ID 可以是作业 ID 或任务 ID
+    fn resolve_ids(&self, state: &SchedulerState, id: &str) -> Result<Vec<String>, String> {
+        if let Some(job) = state.jobs.get(id) {
+            Ok(job.task_ids.clone())
+        } else if state.tasks.contains_key(id) {
+            Ok(vec![id.to_string()])
+        } else {
+            Err("未找到对应下载任务".to_string())
+        }
+    }
+
+    /// 在并发限制内按优先级启动排队中的任务
+    fn pump(&'static self) {
+        let mut to_start = Vec::new();
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            if state.running >= state.max_concurrency {
+                return;
+            }
+            let mut queued: Vec<(DownloadPriority, u64, String)> = state
+                .tasks
+                .values()
+                .filter(|t| t.state == DownloadState::Queued)
+                .map(|t| (t.request.priority, t.seq, t.id.clone()))
+                .collect();
+            queued.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            for (_, _, task_id) in queued {
+                if state.running >= state.max_concurrency {
+                    break;
+                }
+                let max_per_host = state.max_per_host;
+                let host = state.tasks[&task_id].host.clone();
+                let host_running = state.host_running.get(&host).copied().unwrap_or(0);
+                if host_running >= max_per_host {
+                    continue;
+                }
+                state.running += 1;
+                *state.host_running.entry(host).or_insert(0) += 1;
+                let task = state.tasks.get_mut(&task_id).unwrap();
+                let (control, control_rx) = watch::channel(TransferControl::Run);
+                task.state = DownloadState::Running;
+                task.control = Some(control);
+                task.error = None;
+                // 续传时以磁盘上实际写入的长度为准
+                let resume_from = if task.downloaded > 0 {
+                    std::fs::metadata(&task.request.path)
+                        .map(|m| m.len())
+                        .unwrap_or(0)
+                } else {
+                    0
+                };
+                to_start.push((
+                    task_id,
+                    task.request.url.clone(),
+                    task.request.path.clone(),
+                    resume_from,
+                    control_rx,
+                ));
+            }
+        }
+        for (task_id, url, path, resume_from, control_rx) in to_start {
+            tokio::spawn(self.run_task(task_id, url, path, resume_from, control_rx));
+        }
+    }
+
+    async fn run_task(
+        &'static self,
+        task_id: String,
+        url: String,
+        path: PathBuf,
+        resume_from: u64,
+        control: watch::Receiver<TransferControl>,
+    ) {
+        let candidates = candidate_urls(&url, &current_source());
+        let result = self
+            .download_from_candidates(
+                &task_id,
+                &candidates,
+                &path,
+                resume_from,
+                &control,
+                STALL_TIMEOUT,
+            )
+            .await;
+        self.finish_task(&task_id, result);
+        self.pump();
+    }
+
+    /// 依次尝试各个源，请求失败或超过 `stall_timeout` 未收到数据时切换到下一个源
+    async fn download_from_candidates(
+        &'static self,
+        task_id: &str,
+        candidates: &[(Mirror, String)],
+        path: &Path,
+        resume_from: u64,
+        control: &watch::Receiver<TransferControl>,
+        stall_timeout: Duration,
+    ) -> Result<TransferOutcome, String> {
+        let mut errors = Vec::new();
+        for (index, (mirror, candidate)) in candidates.iter().enumerate() {
+            let progress_task_id = task_id.to_string();
+            // 切换源后从头下载
+            let offset = if index == 0 { resume_from } else { 0 };
+            match download_with_progress(
+                candidate,
+                path.to_path_buf(),
+                offset,
+                move |downloaded, total, speed| {
+                    self.on_progress(&progress_task_id, downloaded, total, speed)
+                },
+                control,
+                stall_timeout,
+            )
+            .await
+            {
+                Ok(outcome) => return Ok(outcome),
+                Err(e) => {
+                    record_failure(*mirror);
+                    errors.push(format!("{}: {}", candidate, e));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Err("没有可用的下载地址".to_string())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    fn on_progress(&self, task_id: &str, downloaded: u64, total: Option<u64>, speed: f64) {
+        let mut events = Vec::new();
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            let Some(task) = state.tasks.get_mut(task_id) else {
+                return;
+            };
+            task.downloaded = downloaded;
+            task.total = total;
+            task.speed = speed;
+            let now = Instant::now();
+            let due = task
+                .last_event
+                .is_none_or(|last| now.duration_since(last) >= TASK_EVENT_INTERVAL);
+            if !due {
+                return;
+            }
+            task.last_event = Some(now);
+            let event = task.progress_event();
+            let job_id = task.job_id.clone();
+            if let Some(sink) = state.jobs.get(&job_id).and_then(|j| j.sink.clone()) {
+                events.push((sink, event));
+            }
+            self.collect_job_progress(state, &[task_id.to_string()], &mut events, false);
+        }
+        emit(events);
+    }
+
+    fn finish_task(&'static self, task_id: &str, result: Result<TransferOutcome, String>) {
+        let mut events = Vec::new();
+        {
+            let mut guard = self.state.lock().unwrap();
+            let state = &mut *guard;
+            let Some(task) = state.tasks.get_mut(task_id) else {
+                return;
+            };
+            let requested = task
+                .control
+                .take()
+                .map(|c| *c.borrow())
+                .unwrap_or(TransferControl::Run);
+            task.speed = 0.0;
+            match result {
+                Ok(TransferOutcome::Completed) => {
+                    task.state = DownloadState::Completed;
+                    task.total = Some(task.downloaded);
+                }
+                // 传输停下后再次确认控制信号，以免在停止过程中被继续或取消
+                Ok(TransferOutcome::Paused) | Ok(TransferOutcome::Canceled) => match requested {
+                    TransferControl::Run => task.state = DownloadState::Queued,
+                    TransferControl::Pause => task.state = DownloadState::Paused,
+                    TransferControl::Cancel => {
+                        task.state = DownloadState::Canceled;
+                        let _ = std::fs::remove_file(&task.request.path);
+                    }
+                },
+                Err(e) => {
+                    task.state = DownloadState::Failed;
+                    task.error = Some(e.clone());
+                    let event = DownloadEvent::Error {
+                        id: task.id.clone(),
+                        error: e,
+                    };
+                    if let Some(sink) = state.jobs.get(&task.job_id).and_then(|j| j.sink.clone()) {
+                        events.push((sink, event));
+                    }
+                }
+            }
+            let task = &state.tasks[task_id];
+            let host = task.host.clone();
+            let job_id = task.job_id.clone();
+            let final_event = task.progress_event();
+            if let Some(sink) = state.jobs.get(&job_id).and_then(|j| j.sink.clone()) {
+                events.push((sink, final_event));
+            }
+            state.running = state.running.saturating_sub(1);
+            if let Some(count) = state.host_running.get_mut(&host) {
+                *count = count.saturating_sub(1);
+            }
+            self.collect_job_progress(state, &[task_id.to_string()], &mut events, true);
+            self.finish_job_if_done(state, &job_id, &mut events);
+        }
+        emit(events);
+    }
+
+    /// 为给定任务所属的作业生成汇总进度事件
+    fn collect_job_progress(
+        &self,
+        state: &mut SchedulerState,
+        task_ids: &[String],
+        events: &mut Vec<(DownloadEventSink, DownloadEvent)>,
+        force: bool,
+    ) {
+        let mut job_ids: Vec<String> = task_ids
+            .iter()
+            .filter_map(|id| state.tasks.get(id))
+            .map(|t| t.job_id.clone())
+            .collect();
+        job_ids.dedup();
+        let now = Instant::now();
+        for job_id in job_ids {
+            let Some(job) = state.jobs.get(&job_id) else {
+                continue;
+            };
+            let Some(sink) = job.sink.clone() else {
+                continue;
+            };
+            let due = job
+                .last_event
+                .is_none_or(|last| now.duration_since(last) >= JOB_EVENT_INTERVAL);
+            if !force && !due {
+                continue;
+            }
+            let snapshot = state.job_snapshot(job);
+            events.push((
+                sink,
+                DownloadEvent::JobProgress {
+                    id: snapshot.id,
+                    downloaded_bytes: snapshot.downloaded_bytes,
+                    total_bytes: snapshot.total_bytes,
+                    speed: snapshot.speed,
+                    eta: snapshot.eta,
+                },
+            ));
+            if let Some(job) = state.jobs.get_mut(&job_id) {
+                job.last_event = Some(now);
+            }
+        }
+    }
+
+    /// 作业的所有任务均已结束时通知等待者，并从注册表中移除已结束的任务
+    fn finish_job_if_done(
+        &self,
+        state: &mut SchedulerState,
+        job_id: &str,
+        events: &mut Vec<(DownloadEventSink, DownloadEvent)>,
+    ) {
+        let Some(job) = state.jobs.get(job_id) else {
+            return;
+        };
+        if job.done.borrow().is_some() {
+            return;
+        }
+        let job_state = state.job_state(job);
+        if !job_state.is_finished() {
+            return;
+        }
+        let mut summary = state.job_snapshot(job);
+        summary
+            .tasks
+            .retain(|t| t.state != DownloadState::Completed);
+        let Some(job) = state.jobs.get_mut(job_id) else {
+            return;
+        };
+        for task_id in std::mem::take(&mut job.task_ids) {
+            state.tasks.remove(&task_id);
+        }
+        job.summary = Some(summary);
+        if job_state == DownloadState::Completed {
+            if let Some(sink) = job.sink.clone() {
+                events.push((sink, DownloadEvent::Finished));
+            }
+        }
+        job.done.send_replace(Some(job_state));
+    }
+}
+
+fn emit(events: Vec<(DownloadEventSink, DownloadEvent)>) {
+    for (sink, event) in events {
+        sink(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{init_client, serve_async};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 创建独立于全局调度器的实例，避免测试之间互相影响
+    async fn test_scheduler() -> &'static DownloadScheduler {
+        init_client().await;
+        Box::leak(Box::new(DownloadScheduler::new()))
+    }
+
+    /// 统计同时处理中的请求数的峰值
+    #[derive(Default)]
+    struct Gauge {
+        current: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl Gauge {
+        fn enter(&self) {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(current, Ordering::SeqCst);
+        }
+
+        fn leave(&self) {
+            self.current.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        fn peak(&self) -> usize {
+            self.peak.load(Ordering::SeqCst)
+        }
+    }
+
+    /// 启动一个延迟 `delay` 后才响应的服务器，处理期间计入 `gauges`
+    async fn slow_server(delay: Duration, gauges: Vec<Arc<Gauge>>) -> String {
+        serve_async(move |_| {
+            let gauges = gauges.clone();
+            async move {
+                gauges.iter().for_each(|g| g.enter());
+                tokio::time::sleep(delay).await;
+                gauges.iter().for_each(|g| g.leave());
+                (200, b"content".to_vec())
+            }
+        })
+        .await
+    }
+
+    fn requests(base: &str, dir: &Path, prefix: &str, count: usize) -> Vec<DownloadRequest> {
+        (0..count)
+            .map(|i| DownloadRequest {
+                url: format!("{}/{}{}", base, prefix, i),
+                path: dir.join(format!("{}{}", prefix, i)),
+                priority: DownloadPriority::Normal,
+                size: None,
+            })
+            .collect()
+    }
+
+    fn task(
+        state: DownloadState,
+        downloaded: u64,
+        total: Option<u64>,
+        speed: f64,
+    ) -> DownloadTaskSnapshot {
+        DownloadTaskSnapshot {
+            id: Uuid::new_v4().to_string(),
+            job_id: "job".to_string(),
+            url: "https://example.com/a".to_string(),
+            path: PathBuf::from("a"),
+            priority: DownloadPriority::Normal,
+            state,
+            downloaded_bytes: downloaded,
+            total_bytes: total,
+            speed,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_job_state() {
+        use DownloadState::*;
+        assert_eq!(
+            job_state_of([Completed, Running, Queued].into_iter()),
+            Running
+        );
+        assert_eq!(
+            job_state_of([Completed, Paused, Queued].into_iter()),
+            Queued
+        );
+        assert_eq!(job_state_of([Completed, Paused].into_iter()), Paused);
+        assert_eq!(
+            job_state_of([Completed, Failed, Canceled].into_iter()),
+            Failed
+        );
+        assert_eq!(job_state_of([Completed, Canceled].into_iter()), Canceled);
+        assert_eq!(job_state_of([Completed, Completed].into_iter()), Completed);
+        assert_eq!(job_state_of(std::iter::empty()), Completed);
+    }
+
+    #[test]
+    fn test_summarize_job() {
+        let snapshot = summarize_job(
+            "job",
+            "test",
+            vec![
+                task(DownloadState::Completed, 1024, Some(1024), 0.0),
+                task(DownloadState::Running, 1024, Some(4096), 1.0),
+                task(DownloadState::Queued, 0, Some(1024), 0.0),
+            ],
+        );
+        assert_eq!(snapshot.downloaded_bytes, 2048);
+        assert_eq!(snapshot.total_bytes, 6144);
+        assert_eq!(snapshot.completed_tasks, 1);
+        assert_eq!(snapshot.eta, Some(4));
+
+        // 存在大小未知的未完成任务时无法估算剩余时间
+        let snapshot = summarize_job(
+            "job",
+            "test",
+            vec![task(DownloadState::Running, 1024, None, 1.0)],
+        );
+        assert_eq!(snapshot.eta, None);
+    }
+
+    #[test]
+    fn test_guess_priority() {
+        assert_eq!(
+            guess_priority(Path::new(".minecraft/versions/1.21/1.21.json")),
+            DownloadPriority::High
+        );
+        assert_eq!(
+            guess_priority(Path::new(".minecraft/assets/objects/ab/abcdef")),
+            DownloadPriority::Low
+        );
+        assert_eq!(
+            guess_priority(Path::new(".minecraft/libraries/a/b/c.jar")),
+            DownloadPriority::Normal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limits() {
+        let scheduler = test_scheduler().await;
+        scheduler.set_limits(3, 2);
+        let total = Arc::new(Gauge::default());
+        let host_a = Arc::new(Gauge::default());
+        let host_b = Arc::new(Gauge::default());
+        let delay = Duration::from_millis(100);
+        let base_a = slow_server(delay, vec![total.clone(), host_a.clone()]).await;
+        // 第二台服务器改用 localhost 访问，使两者被视为不同主机
+        let base_b = slow_server(delay, vec![total.clone(), host_b.clone()])
+            .await
+            .replace("127.0.0.1", "localhost");
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut all = requests(&base_a, dir, "a", 6);
+        all.extend(requests(&base_b, dir, "b", 6));
+        scheduler.run("limits", all, None).await.unwrap();
+
+        assert_eq!(total.peak(), 3);
+        assert!(host_a.peak() <= 2, "{}", host_a.peak());
+        assert!(host_b.peak() <= 2, "{}", host_b.peak());
+        assert_eq!(std::fs::read(dir.join("b5")).unwrap(), b"content");
+    }
+
+    #[tokio::test]
+    async fn test_pause_resume_cancel() {
+        let scheduler = test_scheduler().await;
+        scheduler.set_limits(1, 1);
+        let base = slow_server(Duration::from_millis(100), Vec::new()).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let job_id = scheduler.submit("pause", requests(&base, dir, "p", 3), None);
+        scheduler.pause(&job_id).unwrap();
+        let paused = async {
+            while scheduler.job(&job_id).unwrap().state != DownloadState::Paused {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), paused)
+            .await
+            .unwrap();
+        assert!(!dir.join("p2").exists());
+        scheduler.resume(&job_id).unwrap();
+        scheduler.wait(&job_id).await.unwrap();
+        assert_eq!(std::fs::read(dir.join("p2")).unwrap(), b"content");
+
+        // 结束的作业只保留汇总，任务从注册表中移除
+        let snapshot = scheduler.job(&job_id).unwrap();
+        assert_eq!(snapshot.state, DownloadState::Completed);
+        assert_eq!(snapshot.completed_tasks, 3);
+        assert!(scheduler.state.lock().unwrap().tasks.is_empty());
+
+        let job_id = scheduler.submit("cancel", requests(&base, dir, "c", 2), None);
+        scheduler.cancel(&job_id).unwrap();
+        assert_eq!(scheduler.wait(&job_id).await.unwrap_err(), "canceled");
+        assert!(!dir.join("c0").exists());
+        assert!(!dir.join("c1").exists());
+        assert!(scheduler.state.lock().unwrap().tasks.is_empty());
+
+        scheduler.clear_finished();
+        assert!(scheduler.jobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stalled_source_fails_over() {
+        let scheduler = test_scheduler().await;
+        let stalled = serve_async(|_| std::future::pending()).await;
+        let healthy = slow_server(Duration::ZERO, Vec::new()).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("file");
+        let (_control_tx, control) = watch::channel(TransferControl::Run);
+        let timeout = Duration::from_millis(200);
+
+        let candidates = vec![
+            (Mirror::Official, format!("{}/file", stalled)),
+            (Mirror::BmclApi, format!("{}/file", healthy)),
+        ];
+        let outcome = scheduler
+            .download_from_candidates("task", &candidates, &path, 0, &control, timeout)
+            .await
+            .unwrap();
+        assert_eq!(outcome, TransferOutcome::Completed);
+        assert_eq!(std::fs::read(&path).unwrap(), b"content");
+
+        let error = scheduler
+            .download_from_candidates("task", &candidates[..1], &path, 0, &control, timeout)
+            .await
+            .unwrap_err();
+        assert!(error.contains("等待响应超时"), "{}", error);
+    }
+}
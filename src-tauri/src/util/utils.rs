@@ -0,0 +1,110 @@
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+
+/// 计算文件的 SHA-1 值（小写十六进制）
+pub fn sha1_file(path: &Path) -> std::io::Result<String> {
+    hash_file::<Sha1>(path)
+}
+
+/// 计算文件的 SHA-512 值（小写十六进制）
+pub fn sha512_file(path: &Path) -> std::io::Result<String> {
+    hash_file::<Sha512>(path)
+}
+
+fn hash_file<D: Digest>(path: &Path) -> std::io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = D::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 先写入同目录下的临时文件再重命名，避免中断时留下不完整的文件
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// 递归复制目录，返回复制的文件数量
+pub fn copy_dir_all(source: &Path, target: &Path) -> Result<usize, String> {
+    fs::create_dir_all(target).map_err(|e| format!("创建目录失败: {}", e))?;
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let target = target.join(entry.file_name());
+        if path.is_dir() {
+            count += copy_dir_all(&path, &target)?;
+        } else if path.is_file() {
+            fs::copy(&path, &target).map_err(|e| format!("复制 {} 失败: {}", path.display(), e))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// 计算数据的 SHA-1 值（小写十六进制）
+pub fn sha1_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha1::digest(data))
+}
+
+/// 文件存在且 SHA-1 与期望值一致（不区分大小写）
+pub fn verify_sha1(path: &Path, expected: &str) -> bool {
+    sha1_file(path)
+        .map(|actual| actual.eq_ignore_ascii_case(expected))
+        .unwrap_or(false)
+}
+
+/// 文件存在且 SHA-512 与期望值一致（不区分大小写）
+pub fn verify_sha512(path: &Path, expected: &str) -> bool {
+    sha512_file(path)
+        .map(|actual| actual.eq_ignore_ascii_case(expected))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha1_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("file");
+        std::fs::write(&path, b"hello world").unwrap();
+        assert_eq!(
+            sha1_file(&path).unwrap(),
+            "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed"
+        );
+        assert!(verify_sha1(
+            &path,
+            "2AAE6C35C94FCFB415DBE95F408B9CE91EE846ED"
+        ));
+        assert!(verify_sha512(
+            &path,
+            "309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f"
+        ));
+        std::fs::remove_file(&path).unwrap();
+        assert!(!verify_sha1(
+            &path,
+            "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed"
+        ));
+    }
+}
@@ -26,4 +26,54 @@ export interface JreInfo {
     implementor?: string;
     /** 是否为手动添加 */
     manual?: boolean;
-}
\ No newline at end of file
+    /** 是否为启动器下载并管理的运行时 */
+    managed?: boolean;
+    /** 是否为 JDK（包含 javac） */
+    jdk?: boolean;
+    /** 是否自带 JavaFX */
+    javafx?: boolean;
+}
+
+/**
+ * 当前平台可下载的 Mojang Java 运行时
+ */
+export interface MojangRuntimeInfo {
+    /** 运行时名称，如 "java-runtime-gamma" */
+    component: string;
+    version: string;
+    /** 已安装的版本，未安装时为空 */
+    installedVersion?: string;
+}
+/**
+ * 自动选择 Java 的结果
+ */
+export interface JavaSelection {
+    jre: JreInfo;
+    /** 版本要求的最低 Java 主版本号 */
+    requiredMajor: number;
+    /** 选择该 Java 的原因 */
+    reason: string;
+}
+
+/**
+ * 第三方 JDK 发行版
+ */
+export type JdkVendor = 'adoptium' | 'zulu' | 'liberica';
+
+export type JdkImageType = 'jre' | 'jdk';
+
+/**
+ * 查询到的可下载 JDK 压缩包
+ */
+export interface JdkPackage {
+    vendor: JdkVendor;
+    imageType: JdkImageType;
+    major: number;
+    /** 完整版本号 */
+    version: string;
+    url: string;
+    fileName: string;
+    size?: number;
+    sha256?: string;
+    sha1?: string;
+}
@@ -1,7 +1,9 @@
 // 下载源
 export enum DownloadSource {
     Official = 'official',
-    BmclApi = 'bmclapi'
+    BmclApi = 'bmclapi',
+    Auto = 'auto',
+    Custom = 'custom'
 }
 
 // 代理类型
@@ -26,6 +28,16 @@ export interface ProxyConfig {
     password?: string;
 }
 
+// 自定义镜像源，未填写的类别使用官方地址
+export interface CustomMirrorConfig {
+    meta?: string;
+    data?: string;
+    libraries?: string;
+    assets?: string;
+    fabricMeta?: string;
+    forgeMaven?: string;
+}
+
 // 启动器配置
 export interface LauncherConfig {
     lastGamePath: string;
@@ -37,6 +49,12 @@ export interface LauncherConfig {
     enableProxy: boolean;
     proxy: ProxyConfig;
     gamePath: Record<string, string>;
+    customMirror: CustomMirrorConfig;
+    maxDownloadConcurrency: number;
+    maxDownloadsPerHost: number;
+    offlineMode: boolean; // 手动开启离线模式
+    curseforgeApiKey?: string; // CurseForge Core API 的 API Key
+    maxWorldBackups: number; // 每个存档保留的备份数量，为 0 时不限制
 }
 
 // 默认值
@@ -56,5 +74,9 @@ export const defaultLauncherConfig = (): LauncherConfig => ({
     downloadSource: DownloadSource.Official,
     enableProxy: false,
     proxy: defaultProxyConfig(),
-    gamePath: {} // 在实际使用时会从 Tauri 后端获取默认值
+    gamePath: {}, // 在实际使用时会从 Tauri 后端获取默认值
+    customMirror: {},
+    maxDownloadConcurrency: 64,
+    maxDownloadsPerHost: 8,
+    offlineMode: false
 });
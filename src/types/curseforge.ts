@@ -0,0 +1,102 @@
+import { ModLoaderType } from './version';
+
+/**
+ * CurseForge 搜索条件
+ */
+export interface CurseForgeSearchQuery {
+    query: string;
+    loader?: ModLoaderType;
+    gameVersion?: string;
+    /** 分类 ID，默认为模组（6） */
+    classId?: number;
+    categoryId?: number;
+    /** 排序字段：1 精选、2 热度、3 最近更新、4 名称、5 作者、6 下载量 */
+    sortField?: number;
+    index?: number;
+    pageSize?: number;
+}
+
+export interface CurseForgePagination {
+    index: number;
+    pageSize: number;
+    resultCount: number;
+    totalCount: number;
+}
+
+/**
+ * 带分页信息的列表
+ */
+export interface CurseForgePage<T> {
+    data: T[];
+    pagination?: CurseForgePagination;
+}
+
+/**
+ * CurseForge 上的项目
+ */
+export interface CurseForgeMod {
+    id: number;
+    name: string;
+    slug: string;
+    links: { websiteUrl?: string };
+    summary: string;
+    downloadCount: number;
+    logo?: { url: string; thumbnailUrl?: string };
+    authors: { name: string }[];
+    classId?: number;
+    /** 为 false 时无法通过启动器下载其文件 */
+    allowModDistribution?: boolean;
+    dateModified?: string;
+}
+
+/**
+ * 项目的一个文件
+ */
+export interface CurseForgeFile {
+    id: number;
+    modId: number;
+    displayName: string;
+    fileName: string;
+    /** 1 正式版、2 测试版、3 开发版 */
+    releaseType: number;
+    fileDate: string;
+    fileLength: number;
+    /** 作者禁止第三方分发时为空 */
+    downloadUrl?: string;
+    /** 游戏版本与加载器名称 */
+    gameVersions: string[];
+    /** algo 为 1 时是 SHA-1，为 2 时是 MD5 */
+    hashes: { value: string; algo: number }[];
+    /** relationType 为 3 时是必需前置 */
+    dependencies: { modId: number; relationType: number }[];
+    fileFingerprint: number;
+}
+
+/**
+ * 通过指纹识别出的已安装文件
+ */
+export interface CurseForgeInstalledFile {
+    path: string;
+    fileName: string;
+    fingerprint: number;
+    file: CurseForgeFile;
+}
+
+/**
+ * 需要用户在网页上手动下载的文件
+ */
+export interface CurseForgeManualDownload {
+    modId: number;
+    fileId: number;
+    modName: string;
+    fileName: string;
+    /** 网页下载地址 */
+    url: string;
+    /** 下载后应放置的位置 */
+    path: string;
+}
+
+export interface CurseForgeDownloadResult {
+    downloaded: string[];
+    manual: CurseForgeManualDownload[];
+}
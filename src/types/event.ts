@@ -22,6 +22,7 @@ export interface DownloadProgress {
 }
 
 export interface DownloadError {
+  id: string;
   error: string;
 }
 
@@ -35,12 +36,48 @@ export type DownloadEvent =
       speed: number;
     };
   }
+  | {
+    event: 'jobProgress';
+    data: {
+      id: string;
+      downloadedBytes: number;
+      totalBytes: number;
+      speed: number;
+      eta?: number;
+    };
+  }
   | {
     event: 'error';
     data: {
+      id: string;
       error: string;
     };
   }
   | {
     event: 'finished';
-  }
\ No newline at end of file
+  }
+export type LaunchEvent =
+  | {
+    event: 'started';
+    data: {
+      pid: number;
+    };
+  }
+  | {
+    event: 'log';
+    data: {
+      line: string;
+    };
+  }
+  | {
+    event: 'warning';
+    data: {
+      message: string;
+    };
+  }
+  | {
+    event: 'exited';
+    data: {
+      code?: number;
+    };
+  }
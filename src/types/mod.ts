@@ -0,0 +1,92 @@
+import { ModLoaderType } from './version';
+
+/**
+ * 依赖关系的类型
+ */
+export type DependencyKind = 'required' | 'optional' | 'incompatible' | 'discouraged';
+
+/**
+ * 模组声明的依赖
+ */
+export interface ModDependency {
+    /** 依赖的模组 ID */
+    id: string;
+    kind: DependencyKind;
+    /** 版本要求，满足其中任意一个即可，为空表示任意版本 */
+    versions: string[];
+}
+
+/**
+ * 从模组元数据文件中解析出的信息
+ */
+export interface ModMetadata {
+    id: string;
+    name: string;
+    version: string;
+    description?: string;
+    authors: string[];
+    /** 图标在 jar 中的路径 */
+    icon?: string;
+    /** 元数据文件声明的加载器 */
+    loader: ModLoaderType;
+    dependencies: ModDependency[];
+    /** 额外提供的模组 ID */
+    provides: string[];
+}
+
+/**
+ * 模组目录中的一个模组文件
+ */
+export interface ModFile {
+    /** 文件名，禁用的模组包含 `.disabled` 后缀 */
+    fileName: string;
+    path: string;
+    enabled: boolean;
+    size: number;
+    /** 文件中声明的模组 */
+    mods: ModMetadata[];
+    /** 内嵌在 jar 中的模组（Jar-in-Jar） */
+    bundled: ModMetadata[];
+    /** 解析失败的原因 */
+    error?: string;
+}
+
+/**
+ * 模组问题的类型
+ */
+export type ModIssueKind =
+    | 'missingDependency'
+    | 'versionMismatch'
+    | 'incompatible'
+    | 'discouraged'
+    | 'duplicate'
+    | 'wrongLoader';
+
+/**
+ * 检查模组时发现的问题
+ */
+export interface ModIssue {
+    kind: ModIssueKind;
+    /** 出现问题的模组文件名 */
+    fileName: string;
+    modId: string;
+    modName: string;
+    /** 相关的模组 ID */
+    dependency?: string;
+    /** 版本要求 */
+    required: string[];
+    /** 实际找到的版本或文件 */
+    found?: string;
+    /** 是否会导致游戏无法启动 */
+    fatal: boolean;
+    message: string;
+}
+
+/**
+ * 模组检查结果
+ */
+export interface ModCheckReport {
+    /** 检查时使用的加载器，未安装加载器时为空且不做检查 */
+    loader?: ModLoaderType;
+    issues: ModIssue[];
+}
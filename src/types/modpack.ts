@@ -0,0 +1,72 @@
+import { CurseForgeManualDownload } from './curseforge';
+import { ModLoaderInfo } from './version';
+
+/**
+ * 导出 .mrpack 的选项
+ */
+export interface MrpackExportOptions {
+    name: string;
+    /** 整合包自身的版本号 */
+    versionId: string;
+    summary?: string;
+    /** 要打包的游戏目录下的文件与文件夹，如 mods、config、options.txt */
+    include: string[];
+}
+
+export type MrpackEnvSupport = 'required' | 'optional' | 'unsupported';
+
+export interface MrpackFile {
+    path: string;
+    hashes: Record<string, string>;
+    env?: { client: MrpackEnvSupport; server: MrpackEnvSupport };
+    downloads: string[];
+    fileSize: number;
+}
+
+/**
+ * .mrpack 中的 modrinth.index.json
+ */
+export interface MrpackIndex {
+    formatVersion: number;
+    game: string;
+    versionId: string;
+    name: string;
+    summary?: string;
+    files: MrpackFile[];
+    /** minecraft、fabric-loader、quilt-loader、forge、neoforge 到版本号的映射 */
+    dependencies: Record<string, string>;
+}
+
+/**
+ * 导入整合包的结果。导入暂不安装游戏与加载器，installed 为 false 时需按 minecraft 与 loaders
+ * 安装名为 versionName 的版本，pendingInstall 为展示给用户的提示，在此之前启动会报错
+ */
+export interface ModpackImportResult {
+    profileId: string;
+    name: string;
+    gameDirectory: string;
+    minecraft: string;
+    loaders: ModLoaderInfo[];
+    versionName: string;
+    installed: boolean;
+    /** 版本未安装时需要用户安装的内容 */
+    pendingInstall?: string;
+    /** 已下载或复制的文件数量 */
+    downloaded: number;
+    /** 禁止第三方分发，需要手动下载的文件 */
+    manual: CurseForgeManualDownload[];
+    /** 已被删除或无法获取，导入时跳过的文件 */
+    unavailable: ModpackUnavailableFile[];
+}
+
+/**
+ * 整合包中已被删除或无法获取的文件
+ */
+export interface ModpackUnavailableFile {
+    modId: number;
+    fileId: number;
+    /** 项目也无法获取时为空 */
+    modName?: string;
+    /** 项目页面地址 */
+    url: string;
+}
@@ -0,0 +1,96 @@
+/**
+ * Modrinth 搜索条件
+ */
+export interface ModrinthSearchQuery {
+    query: string;
+    /** 加载器，如 fabric、forge */
+    loader?: string;
+    gameVersion?: string;
+    category?: string;
+    /** 项目类型，默认为 mod */
+    projectType?: string;
+    /** 排序方式 */
+    index?: 'relevance' | 'downloads' | 'follows' | 'newest' | 'updated';
+    offset?: number;
+    limit?: number;
+}
+
+/**
+ * 搜索结果中的项目
+ */
+export interface ModrinthSearchHit {
+    project_id: string;
+    slug: string;
+    title: string;
+    description: string;
+    categories: string[];
+    downloads: number;
+    icon_url?: string;
+    author: string;
+    versions: string[];
+    project_type: string;
+    date_modified?: string;
+}
+
+export interface ModrinthSearchResult {
+    hits: ModrinthSearchHit[];
+    offset: number;
+    limit: number;
+    total_hits: number;
+}
+
+export type ModrinthDependencyType = 'required' | 'optional' | 'incompatible' | 'embedded';
+
+export interface ModrinthDependency {
+    version_id?: string;
+    project_id?: string;
+    file_name?: string;
+    dependency_type: ModrinthDependencyType;
+}
+
+export interface ModrinthFile {
+    /** 算法名到哈希值的映射 */
+    hashes: Record<string, string>;
+    url: string;
+    filename: string;
+    primary: boolean;
+    size: number;
+}
+
+/**
+ * 项目的一个版本
+ */
+export interface ModrinthVersion {
+    id: string;
+    project_id: string;
+    name: string;
+    version_number: string;
+    game_versions: string[];
+    loaders: string[];
+    version_type: 'release' | 'beta' | 'alpha';
+    date_published: string;
+    files: ModrinthFile[];
+    dependencies: ModrinthDependency[];
+}
+
+/**
+ * 安装到模组目录的文件
+ */
+export interface ModrinthInstalledFile {
+    projectId: string;
+    versionId: string;
+    versionNumber: string;
+    path: string;
+    /** 是否作为前置被自动安装 */
+    dependency: boolean;
+}
+
+/**
+ * 已安装模组的可用更新
+ */
+export interface ModrinthUpdate {
+    path: string;
+    fileName: string;
+    projectId: string;
+    latest: ModrinthVersion;
+}
@@ -0,0 +1,35 @@
+/**
+ * 包类型
+ */
+export type PackKind = 'resource' | 'shader' | 'data';
+
+/**
+ * 支持的格式范围（含两端）
+ */
+export interface FormatRange {
+    min: number;
+    max: number;
+}
+
+/**
+ * 资源包、光影包或数据包
+ */
+export interface PackInfo {
+    kind: PackKind;
+    fileName: string;
+    path: string;
+    /** 是否为文件夹形式 */
+    directory: boolean;
+    /** 去掉格式化后的描述 */
+    description?: string;
+    packFormat?: number;
+    supportedFormats?: FormatRange;
+    /** 是否包含 pack.png */
+    hasIcon: boolean;
+    /** 资源包是否在 options.txt 中启用，数据包是否未被存档禁用 */
+    enabled: boolean;
+    /** 与当前版本的格式不一致 */
+    incompatible: boolean;
+    /** 读取 pack.mcmeta 失败的原因 */
+    error?: string;
+}
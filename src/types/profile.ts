@@ -1,3 +1,5 @@
+import { ModLoaderInfo } from './version';
+
 // ProfileIcon 枚举
 export type ProfileIcon =
   | 'furnace'
@@ -32,6 +34,13 @@ export interface AdvancedProfile {
   gameArgs?: string;
   wrapCommand?: string;
   skipGameIntegrityCheck?: boolean;
+  skipModCheck?: boolean;
+}
+
+// 配置需要的游戏版本与加载器
+export interface VersionRequirement {
+  minecraft: string;
+  loaders: ModLoaderInfo[];
 }
 
 // Profile 结构体
@@ -47,6 +56,8 @@ export interface Profile {
   showLogs?: boolean;
   serverAddress?: string;
   advanced?: AdvancedProfile;
+  /** 整合包需要安装的游戏版本与加载器，版本未安装时用于提示 */
+  requirement?: VersionRequirement;
 }
 
 // ProfileSettings 结构体
@@ -59,3 +70,27 @@ export interface ProfileJson {
   lastProfile: string;
   profiles: Record<string, Profile>;
 }
+
+// 列出配置时返回的条目
+export interface ProfileSummary {
+  id: string;
+  profile: Profile;
+  installed: boolean;
+  gameDirectory: string;
+  gameVersion?: string;
+  loaders: ModLoaderInfo[];
+  lastUsed: boolean;
+}
+
+// 迁移为版本隔离的结果
+export interface MigrationReport {
+  moved: string[];
+  copied: string[];
+  skipped: string[];
+}
+
+// 从其他启动器导入配置的结果
+export interface ImportReport {
+  imported: string[];
+  skipped: string[];
+}
@@ -0,0 +1,66 @@
+/**
+ * servers.dat 中的服务器
+ */
+export interface ServerEntry {
+    name: string;
+    /** 服务器地址，可带端口 */
+    ip: string;
+    /** Base64 编码的 PNG 图标，由游戏在刷新服务器列表时写入 */
+    icon?: string;
+    /** 是否接受服务器资源包，未设置时由游戏询问 */
+    acceptTextures?: boolean;
+}
+
+/**
+ * 启动后直接进入的存档、服务器或 Realm
+ */
+export type QuickPlay =
+    | { type: 'singleplayer'; world: string }
+    | { type: 'multiplayer'; address: string }
+    | { type: 'realms'; realmId: string };
+
+/**
+ * MOTD 中样式相同的一段文本
+ */
+export interface MotdSegment {
+    text: string;
+    /** 颜色名称（如 gold）或 #RRGGBB */
+    color?: string;
+    bold: boolean;
+    italic: boolean;
+    underlined: boolean;
+    strikethrough: boolean;
+    obfuscated: boolean;
+}
+
+/**
+ * 服务器状态中列出的部分在线玩家
+ */
+export interface PlayerSample {
+    name: string;
+    id: string;
+}
+
+/**
+ * Server List Ping 的结果
+ */
+export interface ServerStatus {
+    /** 实际连接的主机与端口，经过 SRV 解析 */
+    host: string;
+    port: number;
+    /** 服务器版本名称，如 Paper 1.20.1 */
+    version?: string;
+    protocol?: number;
+    playersOnline?: number;
+    playersMax?: number;
+    playersSample: PlayerSample[];
+    motd: MotdSegment[];
+    /** 去掉格式后的 MOTD */
+    motdText: string;
+    /** 服务器图标的 data URL */
+    favicon?: string;
+    /** 延迟，毫秒 */
+    latency: number;
+    /** 是否通过 1.6 以前的旧协议获取 */
+    legacy: boolean;
+}
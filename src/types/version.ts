@@ -42,6 +42,17 @@ export interface LocalVersionInfo {
     info: ClientJson;
 }
 
+// 模组加载器类型
+export type ModLoaderType = 'fabric' | 'quilt' | 'forge' | 'neoforge' | 'optifine' | 'liteloader';
+
+// 模组加载器信息
+export interface ModLoaderInfo {
+    /** 加载器类型 */
+    loader: ModLoaderType;
+    /** 加载器版本号 */
+    version: string;
+}
+
 // 版本清单接口
 export interface VersionManifest {
     /** 最新版本 */
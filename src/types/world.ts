@@ -0,0 +1,48 @@
+/**
+ * 游戏模式
+ */
+export type GameMode = 'survival' | 'creative' | 'adventure' | 'spectator';
+
+/**
+ * 难度
+ */
+export type Difficulty = 'peaceful' | 'easy' | 'normal' | 'hard';
+
+/**
+ * saves 中的存档
+ */
+export interface WorldInfo {
+    /** 存档文件夹名称，用于后续的操作 */
+    folderName: string;
+    path: string;
+    /** 游戏中显示的名称，读取失败时为文件夹名称 */
+    levelName: string;
+    gameMode?: GameMode;
+    difficulty?: Difficulty;
+    hardcore: boolean;
+    /** 是否允许作弊 */
+    allowCommands: boolean;
+    dataVersion?: number;
+    /** 最后游玩的游戏版本，如 1.20.1 */
+    versionName?: string;
+    /** 最后游玩的时间，Unix 毫秒时间戳 */
+    lastPlayed?: number;
+    seed?: number;
+    /** 是否包含 icon.png */
+    hasIcon: boolean;
+    /** 读取 level.dat 失败的原因 */
+    error?: string;
+}
+
+/**
+ * backups 中的存档备份
+ */
+export interface WorldBackup {
+    fileName: string;
+    path: string;
+    /** 备份的存档文件夹名称 */
+    world: string;
+    /** 备份时间，Unix 毫秒时间戳 */
+    created: number;
+    size: number;
+}